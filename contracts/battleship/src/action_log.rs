@@ -0,0 +1,136 @@
+//! Action log for replays: every action taken in a game, in order. The `Game` record
+//! remains the state that gameplay reads and writes in full on every action; the log
+//! is the canonical move-by-move record for replays and disputes. It does not reduce
+//! storage writes. Each action costs one more entry plus the updated count.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::{pause_level, watch, DataKey, FleetGrid, Game, Winner};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most entries returned by one `game_actions` call.
+pub const MAX_ACTIONS_PER_PAGE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionKind {
+    Join,
+    Cancel,
+    CommitBoard,
+    CommitForwardBoard,
+    Shot(u32, u32, FleetGrid),
+    Report(bool),
+    Sonar(u32, u32),
+    LineSonar(u32),
+    Scout(u32, u32),
+    SonarReport(u32),
+    SonarCommit,
+    SonarTimeout,
+    SonarScrambled,
+    ShipSunk,
+    Submerge,
+    CarrierSunk,
+    FlagshipSunk,
+    ConvoyHit,
+    ClaimVictory,
+    HubSettled(Winner),
+    Voided,
+    /// A report as it reads in `game_actions` and in notices while the game's score
+    /// is hidden: the outcome is withheld until the game ends
+    ReportHidden,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameAction {
+    /// Who acted: a player, the hub, a keeper sweeping a timeout, or the contract
+    /// itself for a scripted opponent
+    pub actor: Address,
+    pub kind: ActionKind,
+    pub ledger: u32,
+}
+
+/// Number of actions logged for a game.
+fn action_count(env: &Env, game_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ActionCount(game_id))
+        .unwrap_or(0)
+}
+
+/// `kind` as anyone but the players may see it: with the score hidden, a report's
+/// outcome is left out.
+fn public_kind(game: Option<&Game>, kind: ActionKind) -> ActionKind {
+    match kind {
+        ActionKind::Report(_) if game.is_some_and(Game::score_hidden) => ActionKind::ReportHidden,
+        kind => kind,
+    }
+}
+
+fn load_game(env: &Env, game_id: u32) -> Option<Game> {
+    env.storage().persistent().get(&DataKey::Game(game_id))
+}
+
+/// Append an action to a game's log and notify anyone watching the game.
+/// Fails while the contract is fully paused (see `pause_level`).
+pub(crate) fn log_action(env: &Env, game_id: u32, actor: &Address, kind: ActionKind) {
+    pause_level::require_action(env, &kind);
+    // Only reports can be masked, so only they need the game
+    let notice = match kind {
+        ActionKind::Report(_) => public_kind(load_game(env, game_id).as_ref(), kind.clone()),
+        _ => kind.clone(),
+    };
+    watch::notify(env, game_id, actor, &notice);
+    let seq = action_count(env, game_id);
+    let action = GameAction {
+        actor: actor.clone(),
+        kind,
+        ledger: env.ledger().sequence(),
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::Action(game_id, seq), &action);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ActionCount(game_id), &(seq + 1));
+}
+
+/// Delete a game's whole log.
+pub(crate) fn clear(env: &Env, game_id: u32) {
+    for seq in 0..action_count(env, game_id) {
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Action(game_id, seq));
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::ActionCount(game_id));
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Number of actions logged for a game.
+    pub fn action_count(env: Env, game_id: u32) -> u32 {
+        action_count(&env, game_id)
+    }
+
+    /// Up to `limit` logged actions of a game starting at sequence number `start`,
+    /// in the order they happened. While the score is hidden, reports are shown as
+    /// `ReportHidden`.
+    pub fn game_actions(env: Env, game_id: u32, start: u32, limit: u32) -> Vec<GameAction> {
+        let limit = limit.min(MAX_ACTIONS_PER_PAGE);
+        let end = action_count(&env, game_id).min(start.saturating_add(limit));
+        let game = load_game(&env, game_id);
+        let mut actions = Vec::new(&env);
+        for seq in start..end {
+            let mut action: GameAction = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Action(game_id, seq))
+                .unwrap();
+            action.kind = public_kind(game.as_ref(), action.kind);
+            actions.push_back(action);
+        }
+        actions
+    }
+}
@@ -0,0 +1,49 @@
+//! Away flags. A player going on a break marks themselves away until a given ledger:
+//! their listed games drop out of the lobby view, sonar answer windows they owe are
+//! doubled, and the flag shows in game summaries so opponents know to expect delays.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::DataKey;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Longest a player can mark themselves away for at once (about a week at 5s per ledger).
+pub const MAX_AWAY_LEDGERS: u32 = 120_960;
+
+/// Last ledger `player` is away for, or `None` if they are not away.
+pub(crate) fn away_until(env: &Env, player: &Address) -> Option<u32> {
+    let until: Option<u32> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Away(player.clone()));
+    until.filter(|until| env.ledger().sequence() <= *until)
+}
+
+pub(crate) fn is_away(env: &Env, player: &Address) -> bool {
+    away_until(env, player).is_some()
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Mark the player away through `until_ledger`, or with `None` back now.
+    pub fn set_away(env: Env, player: Address, until_ledger: Option<u32>) {
+        player.require_auth();
+        let key = DataKey::Away(player);
+        match until_ledger {
+            Some(until) => {
+                let now = env.ledger().sequence();
+                assert!(
+                    until >= now && until - now <= MAX_AWAY_LEDGERS,
+                    "invalid away period"
+                );
+                env.storage().persistent().set(&key, &until);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// Last ledger the player is away for, or `None` if they are not away.
+    pub fn away_until(env: Env, player: Address) -> Option<u32> {
+        away_until(&env, &player)
+    }
+}
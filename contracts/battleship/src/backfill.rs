@@ -0,0 +1,35 @@
+//! Outcome backfill. Indexers started after launch have missed the `GameEnded` events
+//! of games that finished before them. `emit_outcome_event` publishes a finished
+//! game's outcome again as a `GameEndedReplayed` event, which carries the same fields
+//! under its own name so it can't be mistaken for a game ending now. It changes no
+//! state, so calling it again for the same game is harmless.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{events, keeper, DataKey, Game, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Re-publish the outcome of a completed or cancelled game as `GameEndedReplayed`.
+    /// `caller` must be the admin or a registered keeper.
+    pub fn emit_outcome_event(env: Env, caller: Address, game_id: u32) {
+        caller.require_auth();
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        assert!(
+            Some(&caller) == admin.as_ref() || keeper::is_registered(&env, &caller),
+            "only the admin or a keeper can replay outcomes"
+        );
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(
+            matches!(game.phase, Phase::Completed(_) | Phase::Cancelled),
+            "game not ended"
+        );
+        events::game_ended_replayed(&env, game_id, game.winner, game.end_reason);
+    }
+}
@@ -0,0 +1,84 @@
+//! Self-description for generic frontends and aggregators. The Wasm carries its rules
+//! and interface versions, game options and ability set as contract metadata, which
+//! can be read without calling the contract; `capabilities` returns the same at
+//! runtime.
+
+use soroban_sdk::{contractimpl, contractmeta, contracttype, Env, String, Symbol, Vec};
+
+use crate::Ability;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Declare a metadata entry and a constant holding the same value, so the runtime
+/// view cannot drift from what is embedded in the Wasm.
+macro_rules! meta {
+    ($(#[$attr:meta])* $name:ident, $key:literal, $val:literal) => {
+        contractmeta!(key = $key, val = $val);
+        $(#[$attr])*
+        pub(crate) const $name: &str = $val;
+    };
+}
+
+meta!(RULES_VERSION, "rules_version", "1");
+meta!(INTERFACE_VERSION, "interface_version", "1");
+meta!(
+    GAME_OPTIONS,
+    "game_options",
+    "legacy_turn_counting,mode,grid,practice,sonar_commit_window,hidden_score,loadouts,\
+     free_sonar_after_turn,no_touching,diagonal_ships,diving_submarine,chaos_weather"
+);
+meta!(
+    GAME_MODES,
+    "game_modes",
+    "Classic,Armada,Hunt,Flagship,Convoy"
+);
+meta!(
+    // Only read by the tests, which hold it to `ALL_ABILITIES`
+    #[allow(dead_code)]
+    ABILITIES,
+    "abilities",
+    "Sonar,Scramble,LineSonar,Scout"
+);
+
+const ALL_ABILITIES: [Ability; 4] = [
+    Ability::Sonar,
+    Ability::Scramble,
+    Ability::LineSonar,
+    Ability::Scout,
+];
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capabilities {
+    /// Changes whenever game outcomes could differ for the same moves
+    pub rules_version: String,
+    /// Changes whenever an entry point or contract type changes incompatibly
+    pub interface_version: String,
+    /// `GameConfig` fields this build understands
+    pub game_options: Vec<Symbol>,
+    /// `GameMode` variants this build can play
+    pub game_modes: Vec<Symbol>,
+    pub abilities: Vec<Ability>,
+}
+
+/// The comma-separated names in `list`, as symbols.
+fn symbols(env: &Env, list: &str) -> Vec<Symbol> {
+    let mut symbols = Vec::new(env);
+    for name in list.split(',') {
+        symbols.push_back(Symbol::new(env, name));
+    }
+    symbols
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// What this deployment supports, matching its embedded contract metadata.
+    pub fn capabilities(env: Env) -> Capabilities {
+        Capabilities {
+            rules_version: String::from_str(&env, RULES_VERSION),
+            interface_version: String::from_str(&env, INTERFACE_VERSION),
+            game_options: symbols(&env, GAME_OPTIONS),
+            game_modes: symbols(&env, GAME_MODES),
+            abilities: Vec::from_array(&env, ALL_ABILITIES),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Daily challenge: a solo mode where the admin posts one committed board per day and
+//! anyone may shoot at it. Hit/miss results come from an oracle account that knows the
+//! board, and players who sink the fleet are ranked by how few shots they needed.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::features::{self, FEATURE_CHALLENGE};
+use crate::pause_level::{self, PAUSE_ALL};
+use crate::{require_admin, BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, STANDARD_FLEET_CELLS};
+
+/// Seconds per challenge day; day N covers ledger timestamps [N * 86400, (N + 1) * 86400).
+pub const CHALLENGE_DAY_SECONDS: u64 = 86_400;
+
+/// Leaderboard entries kept per day.
+pub const CHALLENGE_LEADERBOARD_SIZE: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Challenge {
+    pub board_hash: BytesN<32>,
+    pub max_shots: u32,
+    /// Account that reports hit/miss for every challenge shot
+    pub oracle: Address,
+}
+
+/// One player's attempt at a day's challenge.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChallengeRun {
+    pub shots: u32,
+    pub hits: u32,
+    pub awaiting_report: bool,
+    pub last_shot_x: u32,
+    pub last_shot_y: u32,
+    /// Bit `y * 10 + x` is set once (x, y) has been fired at
+    pub fired_mask: u128,
+    /// Fleet sunk within the shot limit
+    pub completed: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChallengeEntry {
+    pub player: Address,
+    pub shots: u32,
+}
+
+fn current_day(env: &Env) -> u32 {
+    (env.ledger().timestamp() / CHALLENGE_DAY_SECONDS) as u32
+}
+
+fn get_challenge(env: &Env, day: u32) -> Challenge {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Challenge(day))
+        .expect("no challenge for this day")
+}
+
+/// Insert a finished run into the day's leaderboard, keeping it sorted by shots
+/// (earlier finishers win ties) and capped at `CHALLENGE_LEADERBOARD_SIZE`.
+fn record_on_leaderboard(env: &Env, day: u32, player: Address, shots: u32) {
+    let key = DataKey::ChallengeBoard(day);
+    let mut board: Vec<ChallengeEntry> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+
+    let pos = board
+        .iter()
+        .position(|e| e.shots > shots)
+        .unwrap_or(board.len() as usize) as u32;
+    if pos >= CHALLENGE_LEADERBOARD_SIZE {
+        return;
+    }
+    board.insert(pos, ChallengeEntry { player, shots });
+    if board.len() > CHALLENGE_LEADERBOARD_SIZE {
+        board.pop_back();
+    }
+    env.storage().persistent().set(&key, &board);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin posts the challenge for a day (today or later). Each day has exactly one board.
+    pub fn post_challenge(
+        env: Env,
+        day: u32,
+        board_hash: BytesN<32>,
+        max_shots: u32,
+        oracle: Address,
+    ) {
+        require_admin(&env);
+        assert!(day >= current_day(&env), "day already passed");
+        assert!(
+            !env.storage().persistent().has(&DataKey::Challenge(day)),
+            "challenge already posted"
+        );
+        assert!(max_shots >= STANDARD_FLEET_CELLS, "shot limit too low");
+
+        let challenge = Challenge {
+            board_hash,
+            max_shots,
+            oracle,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Challenge(day), &challenge);
+    }
+
+    /// Fire a shot at today's challenge board. One shot may be pending at a time.
+    pub fn challenge_shot(env: Env, player: Address, x: u32, y: u32) {
+        player.require_auth();
+        features::require(&env, FEATURE_CHALLENGE);
+        pause_level::require(&env, PAUSE_ALL);
+
+        let day = current_day(&env);
+        let challenge = get_challenge(&env, day);
+        assert!(x < 10 && y < 10, "shot out of bounds");
+
+        let key = DataKey::ChallengeRun(day, player);
+        let mut run: ChallengeRun = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or(ChallengeRun {
+                shots: 0,
+                hits: 0,
+                awaiting_report: false,
+                last_shot_x: 0,
+                last_shot_y: 0,
+                fired_mask: 0,
+                completed: false,
+            });
+        assert!(!run.completed, "challenge already completed");
+        assert!(!run.awaiting_report, "waiting for hit report");
+        assert!(run.shots < challenge.max_shots, "out of shots");
+        // Re-firing at a known hit would count it twice
+        let bit = 1u128 << (y * 10 + x);
+        assert!(run.fired_mask & bit == 0, "cell already fired at");
+
+        run.fired_mask |= bit;
+
+        run.shots += 1;
+        run.last_shot_x = x;
+        run.last_shot_y = y;
+        run.awaiting_report = true;
+        env.storage().persistent().set(&key, &run);
+    }
+
+    /// Oracle reports the result of a player's pending challenge shot for `day`.
+    pub fn report_challenge_shot(env: Env, day: u32, player: Address, hit: bool) {
+        let challenge = get_challenge(&env, day);
+        challenge.oracle.require_auth();
+
+        let key = DataKey::ChallengeRun(day, player.clone());
+        let mut run: ChallengeRun = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .expect("no challenge run");
+        assert!(run.awaiting_report, "no shot to report on");
+
+        run.awaiting_report = false;
+        if hit {
+            run.hits += 1;
+        }
+        if run.hits == STANDARD_FLEET_CELLS {
+            run.completed = true;
+            record_on_leaderboard(&env, day, player, run.shots);
+        }
+        env.storage().persistent().set(&key, &run);
+    }
+
+    /// The challenge posted for a day.
+    pub fn get_challenge(env: Env, day: u32) -> Challenge {
+        get_challenge(&env, day)
+    }
+
+    /// A player's attempt at a day's challenge, if they have fired at it.
+    pub fn challenge_run(env: Env, day: u32, player: Address) -> Option<ChallengeRun> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ChallengeRun(day, player))
+    }
+
+    /// Fewest-shots leaderboard for a day.
+    pub fn challenge_leaderboard(env: Env, day: u32) -> Vec<ChallengeEntry> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ChallengeBoard(day))
+            .unwrap_or(Vec::new(&env))
+    }
+}
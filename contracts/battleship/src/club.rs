@@ -0,0 +1,237 @@
+//! Clubs: communities that run their own ladder on a shared deployment. A club admin
+//! manages membership, members tag games between each other as club games, and every
+//! completed club game updates the club's win/loss standings.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, String, Vec};
+
+use crate::club_match::slot_of;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{coach, DataKey, Game, Phase, Winner};
+
+/// Longest club name accepted, in bytes.
+pub const MAX_CLUB_NAME_LEN: u32 = 32;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Club {
+    pub name: String,
+    pub admin: Address,
+    pub members: Vec<Address>,
+    /// Club games played to a result
+    pub games_completed: u32,
+    /// Settled inter-club matches
+    pub matches_won: u32,
+    pub matches_lost: u32,
+}
+
+/// One member's record in club games.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClubStanding {
+    pub player: Address,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+pub(crate) fn load_club(env: &Env, club_id: u32) -> Club {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Club(club_id))
+        .expect("club not found")
+}
+
+pub(crate) fn save_club(env: &Env, club_id: u32, club: &Club) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Club(club_id), club);
+}
+
+/// The club a game was tagged with, if any.
+pub(crate) fn club_of(env: &Env, game_id: u32) -> Option<u32> {
+    env.storage().persistent().get(&DataKey::GameClub(game_id))
+}
+
+/// True if `player` may play in club `club_id`'s games.
+pub(crate) fn is_member(env: &Env, club_id: u32, player: &Address) -> bool {
+    load_club(env, club_id).members.contains(player)
+}
+
+/// Panics unless `player` may play in club `club_id`'s games.
+pub(crate) fn assert_member(env: &Env, club_id: u32, player: &Address) {
+    assert!(is_member(env, club_id, player), "not a club member");
+}
+
+/// Credit a completed club game to the club's standings. No-op for untagged games
+/// and games that ended without a winner.
+pub(crate) fn record_result(env: &Env, game_id: u32, game: &Game) {
+    apply_result(env, game_id, game, false);
+}
+
+/// Take a flagged club game back out of the club's standings.
+pub(crate) fn revoke_result(env: &Env, game_id: u32, game: &Game) {
+    apply_result(env, game_id, game, true);
+}
+
+fn apply_result(env: &Env, game_id: u32, game: &Game, revoke: bool) {
+    let Some(club_id) = club_of(env, game_id) else {
+        return;
+    };
+    let (winner, loser) = match game.phase {
+        Phase::Completed(Winner::Player1) => (&game.player1, &game.player2),
+        Phase::Completed(Winner::Player2) => (&game.player2, &game.player1),
+        _ => return,
+    };
+
+    let step = |n: u32| if revoke { n - 1 } else { n + 1 };
+    let mut club = load_club(env, club_id);
+    club.games_completed = step(club.games_completed);
+    save_club(env, club_id, &club);
+
+    let key = DataKey::ClubStandings(club_id);
+    let mut standings: Vec<ClubStanding> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    for (player, won) in [(winner, true), (loser, false)] {
+        let pos = standings.iter().position(|s| s.player == *player);
+        let mut standing = match pos {
+            Some(i) => {
+                let standing = standings.get_unchecked(i as u32);
+                standings.remove(i as u32);
+                standing
+            }
+            None => ClubStanding {
+                player: player.clone(),
+                wins: 0,
+                losses: 0,
+            },
+        };
+        if won {
+            standing.wins = step(standing.wins);
+        } else {
+            standing.losses = step(standing.losses);
+        }
+        // Keep the list ordered by wins, fewer losses first on ties
+        let at = standings
+            .iter()
+            .position(|s| {
+                s.wins < standing.wins || (s.wins == standing.wins && s.losses > standing.losses)
+            })
+            .unwrap_or(standings.len() as usize) as u32;
+        standings.insert(at, standing);
+    }
+    env.storage().persistent().set(&key, &standings);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Register a club. `admin` manages its membership and starts as its only member.
+    /// Returns the club ID.
+    pub fn create_club(env: Env, name: String, admin: Address) -> u32 {
+        admin.require_auth();
+        assert!(
+            !name.is_empty() && name.len() <= MAX_CLUB_NAME_LEN,
+            "invalid club name"
+        );
+
+        let club_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClubCount)
+            .unwrap_or(0)
+            + 1;
+        let club = Club {
+            name,
+            admin: admin.clone(),
+            members: Vec::from_array(&env, [admin]),
+            games_completed: 0,
+            matches_won: 0,
+            matches_lost: 0,
+        };
+        save_club(&env, club_id, &club);
+        env.storage().instance().set(&DataKey::ClubCount, &club_id);
+
+        club_id
+    }
+
+    /// Club admin adds a member.
+    pub fn add_club_member(env: Env, club_id: u32, member: Address) {
+        let mut club = load_club(&env, club_id);
+        club.admin.require_auth();
+        assert!(!club.members.contains(&member), "already a member");
+
+        club.members.push_back(member);
+        save_club(&env, club_id, &club);
+    }
+
+    /// Remove a member. Either the club admin or the member themselves may do this;
+    /// the admin cannot be removed. Standings are kept.
+    pub fn remove_club_member(env: Env, club_id: u32, caller: Address, member: Address) {
+        caller.require_auth();
+        let mut club = load_club(&env, club_id);
+        assert!(
+            caller == club.admin || caller == member,
+            "only the club admin or the member can do this"
+        );
+        assert!(member != club.admin, "cannot remove the club admin");
+
+        let pos = club
+            .members
+            .first_index_of(&member)
+            .expect("not a club member");
+        club.members.remove(pos);
+        save_club(&env, club_id, &club);
+    }
+
+    /// Mark a game that has not started yet as a club game. Only the creator may tag
+    /// it, and every player in it must be a club member (a later joiner is checked
+    /// when they join).
+    pub fn tag_club_game(env: Env, game_id: u32, player: Address, club_id: u32) {
+        player.require_auth();
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(game.phase.status() == 0, "game not in setup phase");
+        assert!(player == game.player1, "only the creator can tag a game");
+        assert!(!game.config.practice, "practice games cannot be club games");
+        assert!(
+            club_of(&env, game_id).is_none() && slot_of(&env, game_id).is_none(),
+            "game already tagged"
+        );
+        assert!(
+            !coach::is_coached(&env, game_id, &game),
+            "coached games cannot be club games"
+        );
+
+        assert_member(&env, club_id, &game.player1);
+        if game.player2 != game.player1 {
+            assert_member(&env, club_id, &game.player2);
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::GameClub(game_id), &club_id);
+    }
+
+    /// Get a club (view function)
+    pub fn get_club(env: Env, club_id: u32) -> Club {
+        load_club(&env, club_id)
+    }
+
+    /// The club a game belongs to, if it was tagged.
+    pub fn game_club(env: Env, game_id: u32) -> Option<u32> {
+        club_of(&env, game_id)
+    }
+
+    /// Members' records in club games, most wins first.
+    pub fn club_leaderboard(env: Env, club_id: u32) -> Vec<ClubStanding> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ClubStandings(club_id))
+            .unwrap_or(Vec::new(&env))
+    }
+}
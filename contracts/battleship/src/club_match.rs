@@ -0,0 +1,316 @@
+//! Inter-club team matches. Two clubs agree on a number of boards; members of each
+//! club enter games against members of the other, and every decided game scores a
+//! point for the winner's club. Once all boards are decided the match is settled and
+//! the hub is told which club won the aggregate.
+
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::club::{assert_member, club_of, is_member, load_club, save_club};
+use crate::events;
+use crate::features::{self, FEATURE_CLUB_MATCHES};
+use crate::hub::{notify_hub_end, notify_hub_start, HubOutcome, HubStartInfo};
+use crate::pause_level::{self, PAUSE_ALL, PAUSE_NEW_GAMES};
+use crate::{coach, DataKey, Game, Phase, Winner};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most boards a single club match can be played over.
+pub const MAX_MATCH_BOARDS: u32 = 9;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClubMatch {
+    pub home_club: u32,
+    pub away_club: u32,
+    /// Games the match is decided over; always odd so the aggregate has a winner
+    pub boards: u32,
+    /// Games entered so far
+    pub games: Vec<u32>,
+    pub home_score: u32,
+    pub away_score: u32,
+    /// Hub session the aggregate is reported under
+    pub session_id: u32,
+    pub settled: bool,
+}
+
+/// Which match a game counts towards, and which side its creator plays for.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchSlot {
+    pub match_id: u32,
+    pub player1_home: bool,
+}
+
+fn load_match(env: &Env, match_id: u32) -> ClubMatch {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ClubMatch(match_id))
+        .expect("club match not found")
+}
+
+fn save_match(env: &Env, match_id: u32, club_match: &ClubMatch) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ClubMatch(match_id), club_match);
+}
+
+pub(crate) fn slot_of(env: &Env, game_id: u32) -> Option<MatchSlot> {
+    env.storage().persistent().get(&DataKey::GameMatch(game_id))
+}
+
+fn opposing_club(club_match: &ClubMatch, player1_home: bool) -> u32 {
+    if player1_home {
+        club_match.away_club
+    } else {
+        club_match.home_club
+    }
+}
+
+/// True if `player2` may join game `game_id` as far as club matches go: they must
+/// belong to the club opposing the creator's. Always true for games outside a match.
+pub(crate) fn may_join(env: &Env, game_id: u32, player2: &Address) -> bool {
+    match slot_of(env, game_id) {
+        Some(slot) => {
+            let club_match = load_match(env, slot.match_id);
+            is_member(env, opposing_club(&club_match, slot.player1_home), player2)
+        }
+        None => true,
+    }
+}
+
+/// Score a finished match game for the winner's club. A game that ends without a
+/// winner frees its board for another game.
+pub(crate) fn record_result(env: &Env, game_id: u32, game: &Game) {
+    let Some(slot) = slot_of(env, game_id) else {
+        return;
+    };
+    let mut club_match = load_match(env, slot.match_id);
+    match game.phase {
+        Phase::Completed(winner) => {
+            if (winner == Winner::Player1) == slot.player1_home {
+                club_match.home_score += 1;
+            } else {
+                club_match.away_score += 1;
+            }
+        }
+        _ => release_board(env, &mut club_match, game_id),
+    }
+    save_match(env, slot.match_id, &club_match);
+}
+
+/// Take a flagged game's point back out of its match and free its board for a
+/// replacement game. Settled matches are final and left alone.
+pub(crate) fn revoke_result(env: &Env, game_id: u32, game: &Game) {
+    let (Some(slot), Phase::Completed(winner)) = (slot_of(env, game_id), game.phase) else {
+        return;
+    };
+    let mut club_match = load_match(env, slot.match_id);
+    if club_match.settled {
+        return;
+    }
+    if (winner == Winner::Player1) == slot.player1_home {
+        club_match.home_score -= 1;
+    } else {
+        club_match.away_score -= 1;
+    }
+    release_board(env, &mut club_match, game_id);
+    save_match(env, slot.match_id, &club_match);
+}
+
+/// Drop a game that no longer counts from its match.
+fn release_board(env: &Env, club_match: &mut ClubMatch, game_id: u32) {
+    let pos = club_match.games.first_index_of(game_id).unwrap();
+    club_match.games.remove(pos);
+    env.storage()
+        .persistent()
+        .remove(&DataKey::GameMatch(game_id));
+}
+
+fn club_matches(env: &Env, club_id: u32) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ClubMatches(club_id))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_club_matches(env: &Env, club_id: u32, matches: &Vec<u32>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ClubMatches(club_id), matches);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Arrange a match between two clubs over `boards` games. Both club admins must
+    /// authorize. The match gets its own hub session, reported when it is settled.
+    /// Returns the match ID.
+    pub fn create_club_match(env: Env, home_club: u32, away_club: u32, boards: u32) -> u32 {
+        features::require(&env, FEATURE_CLUB_MATCHES);
+        pause_level::require(&env, PAUSE_NEW_GAMES);
+        assert!(home_club != away_club, "a club cannot play itself");
+        assert!(
+            boards % 2 == 1 && boards <= MAX_MATCH_BOARDS,
+            "invalid board count"
+        );
+        let home_admin = load_club(&env, home_club).admin;
+        let away_admin = load_club(&env, away_club).admin;
+        home_admin.require_auth();
+        away_admin.require_auth();
+
+        // Sessions share the game id sequence so hub sessions never collide
+        let session_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GameCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::GameCount, &session_id);
+
+        let match_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ClubMatchCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::ClubMatchCount, &match_id);
+
+        let club_match = ClubMatch {
+            home_club,
+            away_club,
+            boards,
+            games: Vec::new(&env),
+            home_score: 0,
+            away_score: 0,
+            session_id,
+            settled: false,
+        };
+        save_match(&env, match_id, &club_match);
+        for club_id in [home_club, away_club] {
+            let mut matches = club_matches(&env, club_id);
+            matches.push_back(match_id);
+            set_club_matches(&env, club_id, &matches);
+        }
+
+        if let Some(hub) = env.storage().instance().get(&DataKey::Hub) {
+            let info = HubStartInfo {
+                config_digest: env.crypto().sha256(&club_match.to_xdr(&env)).to_bytes(),
+                first_mover: None,
+            };
+            notify_hub_start(&env, &hub, session_id, &home_admin, &away_admin, info);
+        }
+
+        match_id
+    }
+
+    /// Enter a game that has not started yet as one of a match's boards. Only the
+    /// creator may enter it, and they must belong to one of the two clubs; the
+    /// opponent must belong to the other.
+    pub fn enter_match_game(env: Env, match_id: u32, game_id: u32, player: Address) {
+        player.require_auth();
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(game.phase.status() == 0, "game not in setup phase");
+        assert!(player == game.player1, "only the creator can enter a game");
+        assert!(!game.config.practice, "practice games cannot be club games");
+        assert!(
+            slot_of(&env, game_id).is_none() && club_of(&env, game_id).is_none(),
+            "game already tagged"
+        );
+        assert!(
+            !coach::is_coached(&env, game_id, &game),
+            "coached games cannot be club games"
+        );
+
+        let mut club_match = load_match(&env, match_id);
+        assert!(!club_match.settled, "club match already settled");
+        assert!(
+            club_match.games.len() < club_match.boards,
+            "all boards taken"
+        );
+
+        let player1_home = load_club(&env, club_match.home_club)
+            .members
+            .contains(&game.player1);
+        if !player1_home {
+            assert_member(&env, club_match.away_club, &game.player1);
+        }
+        if game.player2 != game.player1 {
+            assert_member(
+                &env,
+                opposing_club(&club_match, player1_home),
+                &game.player2,
+            );
+        }
+
+        club_match.games.push_back(game_id);
+        save_match(&env, match_id, &club_match);
+        env.storage().persistent().set(
+            &DataKey::GameMatch(game_id),
+            &MatchSlot {
+                match_id,
+                player1_home,
+            },
+        );
+    }
+
+    /// Settle a match once every board has been decided: update both clubs' records
+    /// and report the aggregate winner to the hub. Anyone may call this.
+    pub fn settle_club_match(env: Env, match_id: u32) {
+        pause_level::require(&env, PAUSE_ALL);
+        let mut club_match = load_match(&env, match_id);
+        assert!(!club_match.settled, "club match already settled");
+        assert!(
+            club_match.home_score + club_match.away_score == club_match.boards,
+            "club match still in progress"
+        );
+
+        club_match.settled = true;
+        save_match(&env, match_id, &club_match);
+
+        let home_won = club_match.home_score > club_match.away_score;
+        for club_id in [club_match.home_club, club_match.away_club] {
+            let mut club = load_club(&env, club_id);
+            if home_won == (club_id == club_match.home_club) {
+                club.matches_won += 1;
+            } else {
+                club.matches_lost += 1;
+            }
+            save_club(&env, club_id, &club);
+
+            let mut matches = club_matches(&env, club_id);
+            if let Some(pos) = matches.first_index_of(match_id) {
+                matches.remove(pos);
+            }
+            set_club_matches(&env, club_id, &matches);
+        }
+
+        if let Some(hub) = env.storage().instance().get(&DataKey::Hub) {
+            let outcome = if club_match.home_score == club_match.away_score {
+                HubOutcome::Draw
+            } else {
+                HubOutcome::win(home_won)
+            };
+            notify_hub_end(&env, &hub, club_match.session_id, outcome);
+        }
+
+        events::club_match_settled(&env, match_id, club_match.home_score, club_match.away_score);
+    }
+
+    /// Get a club match (view function)
+    pub fn get_club_match(env: Env, match_id: u32) -> ClubMatch {
+        load_match(&env, match_id)
+    }
+
+    /// IDs of a club's matches that have not been settled yet.
+    pub fn club_matches(env: Env, club_id: u32) -> Vec<u32> {
+        club_matches(&env, club_id)
+    }
+}
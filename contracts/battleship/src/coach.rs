@@ -0,0 +1,92 @@
+//! Coaching. In games that don't count toward club standings, a player can register
+//! a coach for that game. The coach may read the player's caller-restricted views
+//! (such as `incoming_shot`) and post emotes to them, but can never take a gameplay
+//! action: those still require the player's own authorization.
+
+use soroban_sdk::{contractimpl, Address, Env, Symbol};
+
+use crate::club::club_of;
+use crate::club_match::slot_of;
+use crate::events;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, Game};
+
+fn load_game(env: &Env, game_id: u32) -> Game {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found")
+}
+
+fn coach_of(env: &Env, game_id: u32, player: &Address) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Coach(game_id, player.clone()))
+}
+
+/// Whether either player of the game has a coach.
+pub(crate) fn is_coached(env: &Env, game_id: u32, game: &Game) -> bool {
+    coach_of(env, game_id, &game.player1).is_some()
+        || coach_of(env, game_id, &game.player2).is_some()
+}
+
+/// Authenticated reads: panics unless `viewer` authorized the call and may see
+/// `player`'s private view of the game, i.e. is the player or their coach. Views that
+/// answer differently per role call this first, so the role check isn't left to
+/// clients.
+pub(crate) fn require_viewer(env: &Env, game_id: u32, player: &Address, viewer: &Address) {
+    viewer.require_auth();
+    assert!(
+        viewer == player || coach_of(env, game_id, player).as_ref() == Some(viewer),
+        "not allowed to view"
+    );
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Register (or with `None`, remove) `player`'s coach for an unfinished game.
+    /// Club and club match games cannot be coached.
+    pub fn set_coach(env: Env, game_id: u32, player: Address, coach: Option<Address>) {
+        player.require_auth();
+
+        let game = load_game(&env, game_id);
+        assert!(
+            player == game.player1 || player == game.player2,
+            "not a player"
+        );
+        assert!(game.phase.status() < 2, "game is over");
+        let key = DataKey::Coach(game_id, player);
+        match coach {
+            Some(coach) => {
+                assert!(
+                    club_of(&env, game_id).is_none() && slot_of(&env, game_id).is_none(),
+                    "club games cannot be coached"
+                );
+                assert!(
+                    coach != game.player1 && coach != game.player2,
+                    "a player cannot coach"
+                );
+                env.storage().persistent().set(&key, &coach);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// The coach `player` registered for a game, if any.
+    pub fn game_coach(env: Env, game_id: u32, player: Address) -> Option<Address> {
+        coach_of(&env, game_id, &player)
+    }
+
+    /// Coach only: send `player` an emote during the game.
+    pub fn coach_emote(env: Env, game_id: u32, coach: Address, player: Address, emote: Symbol) {
+        coach.require_auth();
+
+        let game = load_game(&env, game_id);
+        assert!(game.phase.status() < 2, "game is over");
+        assert!(
+            coach_of(&env, game_id, &player) == Some(coach),
+            "not this player's coach"
+        );
+        events::coach_emote(&env, game_id, player, emote);
+    }
+}
@@ -0,0 +1,75 @@
+//! Capture-the-convoy mode (`GameMode::Convoy`). Besides their fleet, each player
+//! places a few convoy cells on open water. A shot on a convoy cell is answered with
+//! `report_convoy_hit` instead of `report_result`: it counts as a miss on the fleet
+//! but scores the shooter a convoy point, and the first to `points_to_win` wins even
+//! with ships still afloat.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::OrPanic;
+use crate::{heatmap, is_retried_action, weather, DataKey, Game, Weather};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvoyRules {
+    /// Convoy cells each player places
+    pub cells: u32,
+    /// Convoy points that win the game; at most `cells`
+    pub points_to_win: u32,
+}
+
+impl ConvoyRules {
+    pub fn is_valid(&self) -> bool {
+        self.points_to_win > 0 && self.points_to_win <= self.cells
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Convoy mode: the defender reports that the last shot struck one of their convoy
+    /// cells. Accepts a ZK proof stored for opponent verification. A repeated
+    /// `action_id` is treated as a retry and ignored. Shots miss convoys too in a storm.
+    pub fn report_convoy_hit(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        proof: Bytes,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        let rules = game.config.convoy().cloned().expect("not a convoy game");
+        game.check_report(&player).or_panic(&env);
+        assert!(game.weather != Weather::Storm, "shots miss in a storm");
+        let points = if game.turn == 1 {
+            game.p1_shots += 1;
+            &mut game.p1_convoy_points
+        } else {
+            game.p2_shots += 1;
+            &mut game.p2_convoy_points
+        };
+        assert!(*points < rules.cells, "every convoy cell already hit");
+        *points += 1;
+
+        game.last_shot_hit = false;
+        game.last_shot_proof = proof;
+        heatmap::record(&env, game_id, &game, true);
+        game.end_turn();
+        weather::after_turn(&env, game_id, &mut game);
+        action_log::log_action(&env, game_id, &player, ActionKind::ConvoyHit);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+}
@@ -0,0 +1,64 @@
+//! Deadlines a game is currently running against, as ledger sequence numbers, so
+//! clients render the same countdowns after a reconnect instead of timing from when
+//! they happened to load the game.
+//!
+//! Only deadlines the contract enforces are reported: the sonar commit and reveal
+//! windows and lobby listing expiry. Shooting and reporting on a shot have no time
+//! limit and no timeout claim, so there is no turn or report deadline to derive from
+//! the config and `last_action_ledger`, and those phases report all zeros. Adding
+//! them needs a move window in `GameConfig` and a claim that enforces it first.
+
+use soroban_sdk::{contractimpl, contracttype, Env};
+
+use crate::lobby::Listing;
+use crate::{sonar_commit, DataKey, Game, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Each field is the last ledger in which the pending step can still be taken, or
+/// zero when no such deadline applies.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deadlines {
+    /// Commit–reveal games: the defender must commit the pending sonar answer
+    pub commit_by: u32,
+    /// Listed games waiting for an opponent: join before anyone may expire the listing.
+    /// Not set for a player's turn, which has no time limit.
+    pub act_by: u32,
+    /// Commit–reveal games: the defender must reveal the committed sonar answer
+    pub report_by: u32,
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// The deadlines currently running for a game. After one passes, the matching
+    /// claim (`claim_sonar_timeout`, `expire_listing`) becomes available.
+    pub fn deadlines(env: Env, game_id: u32) -> Deadlines {
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        let mut deadlines = Deadlines::default();
+        match game.phase {
+            Phase::Setup => {
+                let listing: Option<Listing> =
+                    env.storage().persistent().get(&DataKey::Listing(game_id));
+                if let Some(listing) = listing {
+                    deadlines.act_by = listing.expires_ledger - 1;
+                }
+            }
+            Phase::AwaitingSonarReport => {
+                if let Some(pending) = sonar_commit::load(&env, game_id) {
+                    let deadline = sonar_commit::deadline(&env, game_id, &pending);
+                    if pending.commitment.is_none() {
+                        deadlines.commit_by = deadline;
+                    } else {
+                        deadlines.report_by = deadline;
+                    }
+                }
+            }
+            _ => {}
+        }
+        deadlines
+    }
+}
@@ -0,0 +1,107 @@
+//! Diving submarines (`GameConfig::diving_submarine`). Once per game a player may
+//! submerge their submarine, and the opponent's next `DIVE_SHOTS` shots on its cells
+//! are reported as misses. The contract never sees where the submarine is, so it only
+//! records when the dive happened; the shot proof takes whether the shot fell inside
+//! the dive as a public input. Nor can it tell which ship a hit struck, so a player
+//! can only dive while their home fleet is untouched, which guarantees the submarine
+//! has not been hit. Sonar still counts a submerged submarine.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::action_log::{self, ActionKind};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, Game};
+
+/// Opponent shots a dive lasts for.
+pub const DIVE_SHOTS: u32 = 3;
+
+/// A recorded dive, as the numbers of the opponent's shots it covers. Shots are
+/// numbered from 1 in the order fired, as counted by `p1_shots_taken` and
+/// `p2_shots_taken`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dive {
+    pub first_shot: u32,
+    pub last_shot: u32,
+}
+
+impl Dive {
+    pub fn covers(&self, shot: u32) -> bool {
+        (self.first_shot..=self.last_shot).contains(&shot)
+    }
+}
+
+fn load_dive(env: &Env, game_id: u32, player: &Address) -> Option<Dive> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Dive(game_id, player.clone()))
+}
+
+/// Whether the last shot fired at `defender` (player 1 or 2) fell inside their dive,
+/// i.e. whether their report on it treats the submarine as absent.
+fn last_shot_submerged(env: &Env, game_id: u32, game: &Game, defender: u32) -> bool {
+    let shots = if defender == 1 {
+        game.p2_shots_taken
+    } else {
+        game.p1_shots_taken
+    };
+    load_dive(env, game_id, &game.player(defender)).is_some_and(|dive| dive.covers(shots))
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Submerge the player's submarine for the opponent's next `DIVE_SHOTS` shots,
+    /// once per game. Does not use up the player's turn. Only allowed before the
+    /// opponent has hit the player's home fleet, so a hit submarine can't dive.
+    pub fn submerge(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(game.config.diving_submarine, "no diving submarines");
+        assert!(game.phase.is_in_progress(), "game not in progress");
+        let (opponent_shots, opponent_hits) = if player == game.player1 {
+            (game.p2_shots_taken, game.p2_hits)
+        } else if player == game.player2 {
+            (game.p1_shots_taken, game.p1_hits)
+        } else {
+            panic!("not a player");
+        };
+        assert!(opponent_hits == 0, "fleet already hit");
+        let key = DataKey::Dive(game_id, player.clone());
+        assert!(!env.storage().persistent().has(&key), "already submerged");
+
+        let dive = Dive {
+            first_shot: opponent_shots + 1,
+            last_shot: opponent_shots + DIVE_SHOTS,
+        };
+        env.storage().persistent().set(&key, &dive);
+        action_log::log_action(&env, game_id, &player, ActionKind::Submerge);
+    }
+
+    /// Whether the shot `defender` is to report on (or last reported on) fell inside
+    /// their dive. The shot proof takes this as its `submerged` input.
+    pub fn shot_submerged(env: Env, game_id: u32, defender: Address) -> bool {
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        let defender = if defender == game.player1 {
+            1
+        } else if defender == game.player2 {
+            2
+        } else {
+            panic!("not a player");
+        };
+        last_shot_submerged(&env, game_id, &game, defender)
+    }
+
+    /// The player's dive, if they have submerged this game.
+    pub fn dive(env: Env, game_id: u32, player: Address) -> Option<Dive> {
+        load_dive(&env, game_id, &player)
+    }
+}
@@ -0,0 +1,179 @@
+//! Errors for the core game calls and the lobby, treasury, admin, moderation and
+//! relay calls around them. Entry points fail with the error code; the
+//! `can_*` views return the same errors so clients can check a call before asking
+//! the user to sign it.
+//!
+//! While frontends move over from matching on panic text, the default
+//! `panic-messages` feature keeps entry points panicking with each error's old
+//! message instead. Build without default features to get the error codes.
+
+use soroban_sdk::{contracterror, Env};
+
+#[contracterror]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    GameNotFound = 1,
+    NotInSetup = 2,
+    AlreadyJoined = 3,
+    CannotJoinOwnGame = 4,
+    NotClubMember = 5,
+    OnlyCreatorCanCancel = 6,
+    HunterHasNoBoard = 7,
+    BoardAlreadyCommitted = 8,
+    NotInThisGame = 9,
+    NotAPlayer = 10,
+    NotInProgress = 11,
+    WaitingForHitReport = 12,
+    WaitingForSonarReport = 13,
+    ShotOutOfBounds = 14,
+    SonarOutOfBounds = 15,
+    NotArmada = 16,
+    HunterOutOfTurns = 17,
+    NotYourTurn = 18,
+    NoShotToReport = 19,
+    NoSonarToReport = 20,
+    WrongPlayerReporting = 21,
+    InvalidSonarCount = 22,
+    OnlyHunterHasSonar = 23,
+    SonarAlreadyUsed = 24,
+    SonarNotAvailable = 25,
+    NotEnoughHits = 26,
+    HunterHasTurnsLeft = 27,
+    AbilityNotInLoadout = 28,
+    NotScheduledOpponent = 29,
+    MatchNotConfirmed = 30,
+    MatchNotStarted = 31,
+    MatchAlreadyConfirmed = 32,
+    ZeroBoardHash = 33,
+    DuplicateBoardHash = 34,
+    OpponentNotJoined = 35,
+    PlayerBarred = 36,
+    FeatureDisabled = 37,
+    ContractPaused = 38,
+    GameNotOpen = 39,
+    OnlyCreatorCanList = 40,
+    AlreadyListed = 41,
+    ListingNotConfigured = 42,
+    InvalidDeposit = 43,
+    NotListed = 44,
+    ListingNotExpired = 45,
+    InvalidAmount = 46,
+    WithdrawalNotFound = 47,
+    WithdrawalTimeLocked = 48,
+    InsufficientTreasury = 49,
+    TimeLocked = 50,
+    AdminActionNotFound = 51,
+    TooManyAdminActions = 52,
+    AdminActionTimeLocked = 53,
+    GameNotCompleted = 54,
+    AlreadyFlagged = 55,
+    OnlyAdminOrHubCanVoid = 56,
+    VoidReasonTooLong = 57,
+    AlreadyVoided = 58,
+    GameNotStarted = 59,
+    NoGameplayKey = 60,
+    NoRelayer = 61,
+}
+
+impl Error {
+    /// The panic message entry points use for this error under `panic-messages`.
+    pub fn message(&self) -> &'static str {
+        match self {
+            Error::GameNotFound => "game not found",
+            Error::NotInSetup => "game not in setup phase",
+            Error::AlreadyJoined => "player 2 already joined",
+            Error::CannotJoinOwnGame => "cannot join your own game",
+            Error::NotClubMember => "not a club member",
+            Error::OnlyCreatorCanCancel => "only the creator can cancel",
+            Error::HunterHasNoBoard => "the hunter has no board",
+            Error::BoardAlreadyCommitted => "board already committed",
+            Error::NotInThisGame => "not a player in this game",
+            Error::NotAPlayer => "not a player",
+            Error::NotInProgress => "game not in progress",
+            Error::WaitingForHitReport => "waiting for hit report",
+            Error::WaitingForSonarReport => "waiting for sonar report",
+            Error::ShotOutOfBounds => "shot out of bounds",
+            Error::SonarOutOfBounds => "sonar out of bounds",
+            Error::NotArmada => "not an armada game",
+            Error::HunterOutOfTurns => "hunter is out of turns",
+            Error::NotYourTurn => "not your turn",
+            Error::NoShotToReport => "no shot to report on",
+            Error::NoSonarToReport => "no sonar to report on",
+            Error::WrongPlayerReporting => "wrong player reporting",
+            Error::InvalidSonarCount => "invalid sonar count",
+            Error::OnlyHunterHasSonar => "only the hunter has sonar",
+            Error::SonarAlreadyUsed => "sonar already used",
+            Error::SonarNotAvailable => "sonar not available this turn",
+            Error::NotEnoughHits => "not enough hits to win",
+            Error::HunterHasTurnsLeft => "hunter still has turns left",
+            Error::AbilityNotInLoadout => "ability not in loadout",
+            Error::NotScheduledOpponent => "not the scheduled opponent",
+            Error::MatchNotConfirmed => "scheduled match not confirmed",
+            Error::MatchNotStarted => "scheduled match has not started",
+            Error::MatchAlreadyConfirmed => "scheduled match already confirmed",
+            Error::ZeroBoardHash => "board hash is zero",
+            Error::DuplicateBoardHash => "board hash matches the opponent's",
+            Error::OpponentNotJoined => "waiting for an opponent to join",
+            Error::PlayerBarred => "barred after cheating strikes",
+            Error::FeatureDisabled => "feature disabled",
+            Error::ContractPaused => "contract paused",
+            Error::GameNotOpen => "game not open",
+            Error::OnlyCreatorCanList => "only the creator can list a game",
+            Error::AlreadyListed => "game already listed",
+            Error::ListingNotConfigured => "listing not configured",
+            Error::InvalidDeposit => "invalid deposit",
+            Error::NotListed => "game not listed",
+            Error::ListingNotExpired => "listing not expired",
+            Error::InvalidAmount => "invalid amount",
+            Error::WithdrawalNotFound => "withdrawal not found",
+            Error::WithdrawalTimeLocked => "withdrawal still time-locked",
+            Error::InsufficientTreasury => "insufficient treasury balance",
+            Error::TimeLocked => "time-locked: propose the change",
+            Error::AdminActionNotFound => "admin action not found",
+            Error::TooManyAdminActions => "too many pending admin actions",
+            Error::AdminActionTimeLocked => "admin action still time-locked",
+            Error::GameNotCompleted => "game not completed",
+            Error::AlreadyFlagged => "game already flagged",
+            Error::OnlyAdminOrHubCanVoid => "only the admin or hub can void games",
+            Error::VoidReasonTooLong => "void reason too long",
+            Error::AlreadyVoided => "game already voided",
+            Error::GameNotStarted => "game not started",
+            Error::NoGameplayKey => "no gameplay key",
+            Error::NoRelayer => "no relayer configured",
+        }
+    }
+}
+
+/// Abort the current entry point with `error`.
+#[cfg(feature = "panic-messages")]
+pub(crate) fn fail(_env: &Env, error: Error) -> ! {
+    panic!("{}", error.message())
+}
+
+/// Abort the current entry point with `error`.
+#[cfg(not(feature = "panic-messages"))]
+pub(crate) fn fail(env: &Env, error: Error) -> ! {
+    soroban_sdk::panic_with_error!(env, error)
+}
+
+/// Abort the current entry point with `error` unless `condition` holds.
+pub(crate) fn ensure(env: &Env, condition: bool, error: Error) {
+    if !condition {
+        fail(env, error);
+    }
+}
+
+/// Unwrap a check result inside an entry point, failing with the error.
+pub(crate) trait OrPanic<T> {
+    fn or_panic(self, env: &Env) -> T;
+}
+
+impl<T> OrPanic<T> for Result<T, Error> {
+    fn or_panic(self, env: &Env) -> T {
+        match self {
+            Ok(value) => value,
+            Err(e) => fail(env, e),
+        }
+    }
+}
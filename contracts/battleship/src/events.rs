@@ -0,0 +1,409 @@
+//! Every event the contract publishes, in one place so indexers have a single
+//! reference. Topics are the event's name, then the schema version, then the fields
+//! marked `#[topic]` in order; the remaining fields are the data, as a map keyed by
+//! field name. Events are published through the functions below rather than by
+//! building the structs at call sites.
+//!
+//! The version topic is `EVENT_SCHEMA_VERSION`. A change to an event's topics or
+//! data (a field added, removed, renamed or retyped) bumps it for that event, so an
+//! indexer can tell layouts apart instead of misreading them. The literal is repeated
+//! in each `#[contractevent]` attribute because the macro only takes string literals.
+
+use soroban_sdk::{contractevent, Address, BytesN, Env, String, Symbol};
+
+use crate::action_log::ActionKind;
+use crate::strikes::AppealStatus;
+use crate::timelock::AdminAction;
+use crate::treasury::{Inflow, Outflow};
+use crate::{EndReason, Weather};
+
+/// Schema version topic carried by every event.
+pub const EVENT_SCHEMA_VERSION: &str = "v1";
+
+/// A game's boards are all committed and the first shot can be taken.
+#[contractevent(topics = ["started", "v1"])]
+pub struct GameStarted {
+    #[topic]
+    pub game_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    pub first_mover: Address,
+    /// The game's `rules_digest`
+    pub rules_digest: BytesN<32>,
+}
+
+/// Published whenever a game reaches a terminal phase.
+#[contractevent(topics = ["game_ended", "v1"])]
+pub struct GameEnded {
+    #[topic]
+    pub game_id: u32,
+    pub winner: Option<Address>,
+    pub end_reason: EndReason,
+}
+
+/// A finished game's outcome, published again by `emit_outcome_event` for indexers
+/// backfilling history. Same fields as `GameEnded`.
+#[contractevent(topics = ["game_ended_replayed", "v1"])]
+pub struct GameEndedReplayed {
+    #[topic]
+    pub game_id: u32,
+    pub winner: Option<Address>,
+    pub end_reason: EndReason,
+}
+
+/// An action was taken in a game `observer` is watching.
+#[contractevent(topics = ["game_notice", "v1"])]
+pub struct GameNotice {
+    #[topic]
+    pub observer: Address,
+    #[topic]
+    pub game_id: u32,
+    pub actor: Address,
+    pub kind: ActionKind,
+}
+
+/// A coach sent their player an emote.
+#[contractevent(topics = ["coach_emote", "v1"])]
+pub struct CoachEmote {
+    #[topic]
+    pub game_id: u32,
+    #[topic]
+    pub player: Address,
+    pub emote: Symbol,
+}
+
+/// The defender scrambled the pending sonar ping.
+#[contractevent(topics = ["sonar_scrambled", "v1"])]
+pub struct SonarScrambled {
+    #[topic]
+    pub game_id: u32,
+    pub defender: Address,
+}
+
+/// A weather event was drawn for the round that just started.
+#[contractevent(topics = ["weather_changed", "v1"])]
+pub struct WeatherChanged {
+    #[topic]
+    pub game_id: u32,
+    pub round: u32,
+    pub weather: Weather,
+}
+
+/// Published when a club match is settled.
+#[contractevent(topics = ["club_match_settled", "v1"])]
+pub struct ClubMatchSettled {
+    #[topic]
+    pub match_id: u32,
+    pub home_score: u32,
+    pub away_score: u32,
+}
+
+/// A completed game's reward effects were revoked after review.
+#[contractevent(topics = ["game_flagged", "v1"])]
+pub struct GameFlagged {
+    #[topic]
+    pub game_id: u32,
+}
+
+/// A game was voided by the admin or the hub.
+#[contractevent(topics = ["game_voided", "v1"])]
+pub struct GameVoided {
+    #[topic]
+    pub game_id: u32,
+    pub reason: String,
+}
+
+/// A strike was recorded against a player.
+#[contractevent(topics = ["strike_recorded", "v1"])]
+pub struct StrikeRecorded {
+    #[topic]
+    pub player: Address,
+    pub game_id: u32,
+}
+
+/// A player appealed a strike.
+#[contractevent(topics = ["strike_appealed", "v1"])]
+pub struct StrikeAppealed {
+    #[topic]
+    pub player: Address,
+    pub game_id: u32,
+    pub evidence_hash: BytesN<32>,
+    pub deadline_ledger: u32,
+}
+
+/// The admin ruled on an appeal.
+#[contractevent(topics = ["strike_appeal_decided", "v1"])]
+pub struct StrikeAppealDecided {
+    #[topic]
+    pub player: Address,
+    pub game_id: u32,
+    pub status: AppealStatus,
+}
+
+/// Funds entered the treasury.
+#[contractevent(topics = ["treasury_inflow", "v1"])]
+pub struct TreasuryInflow {
+    #[topic]
+    pub token: Address,
+    pub source: Inflow,
+    pub amount: i128,
+}
+
+/// Funds left the treasury.
+#[contractevent(topics = ["treasury_outflow", "v1"])]
+pub struct TreasuryOutflow {
+    #[topic]
+    pub token: Address,
+    pub to: Address,
+    pub reason: Outflow,
+    pub amount: i128,
+}
+
+/// The admin requested a withdrawal.
+#[contractevent(topics = ["withdrawal_requested", "v1"])]
+pub struct WithdrawalRequested {
+    #[topic]
+    pub withdrawal_id: u32,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub executable_ledger: u32,
+}
+
+/// The admin proposed a time-locked change.
+#[contractevent(topics = ["admin_action_proposed", "v1"])]
+pub struct AdminActionProposed {
+    #[topic]
+    pub action_id: u32,
+    pub action: AdminAction,
+    pub executable_ledger: u32,
+}
+
+/// A proposed change was executed.
+#[contractevent(topics = ["admin_action_executed", "v1"])]
+pub struct AdminActionExecuted {
+    #[topic]
+    pub action_id: u32,
+}
+
+/// A proposed change was cancelled.
+#[contractevent(topics = ["admin_action_cancelled", "v1"])]
+pub struct AdminActionCancelled {
+    #[topic]
+    pub action_id: u32,
+}
+
+/// The admin changed which features are disabled.
+#[contractevent(topics = ["features_changed", "v1"])]
+pub struct FeaturesChanged {
+    pub disabled: u32,
+}
+
+/// The admin changed the contract-wide pause level.
+#[contractevent(topics = ["pause_level_changed", "v1"])]
+pub struct PauseLevelChanged {
+    pub level: u32,
+}
+
+pub(crate) fn game_started(
+    env: &Env,
+    game_id: u32,
+    player1: Address,
+    player2: Address,
+    first_mover: Address,
+    rules_digest: BytesN<32>,
+) {
+    GameStarted {
+        game_id,
+        player1,
+        player2,
+        first_mover,
+        rules_digest,
+    }
+    .publish(env);
+}
+
+pub(crate) fn game_ended(env: &Env, game_id: u32, winner: Option<Address>, end_reason: EndReason) {
+    GameEnded {
+        game_id,
+        winner,
+        end_reason,
+    }
+    .publish(env);
+}
+
+pub(crate) fn game_ended_replayed(
+    env: &Env,
+    game_id: u32,
+    winner: Option<Address>,
+    end_reason: EndReason,
+) {
+    GameEndedReplayed {
+        game_id,
+        winner,
+        end_reason,
+    }
+    .publish(env);
+}
+
+pub(crate) fn game_notice(
+    env: &Env,
+    observer: Address,
+    game_id: u32,
+    actor: Address,
+    kind: ActionKind,
+) {
+    GameNotice {
+        observer,
+        game_id,
+        actor,
+        kind,
+    }
+    .publish(env);
+}
+
+pub(crate) fn coach_emote(env: &Env, game_id: u32, player: Address, emote: Symbol) {
+    CoachEmote {
+        game_id,
+        player,
+        emote,
+    }
+    .publish(env);
+}
+
+pub(crate) fn sonar_scrambled(env: &Env, game_id: u32, defender: Address) {
+    SonarScrambled { game_id, defender }.publish(env);
+}
+
+pub(crate) fn weather_changed(env: &Env, game_id: u32, round: u32, weather: Weather) {
+    WeatherChanged {
+        game_id,
+        round,
+        weather,
+    }
+    .publish(env);
+}
+
+pub(crate) fn club_match_settled(env: &Env, match_id: u32, home_score: u32, away_score: u32) {
+    ClubMatchSettled {
+        match_id,
+        home_score,
+        away_score,
+    }
+    .publish(env);
+}
+
+pub(crate) fn game_flagged(env: &Env, game_id: u32) {
+    GameFlagged { game_id }.publish(env);
+}
+
+pub(crate) fn game_voided(env: &Env, game_id: u32, reason: String) {
+    GameVoided { game_id, reason }.publish(env);
+}
+
+pub(crate) fn strike_recorded(env: &Env, player: Address, game_id: u32) {
+    StrikeRecorded { player, game_id }.publish(env);
+}
+
+pub(crate) fn strike_appealed(
+    env: &Env,
+    player: Address,
+    game_id: u32,
+    evidence_hash: BytesN<32>,
+    deadline_ledger: u32,
+) {
+    StrikeAppealed {
+        player,
+        game_id,
+        evidence_hash,
+        deadline_ledger,
+    }
+    .publish(env);
+}
+
+pub(crate) fn strike_appeal_decided(
+    env: &Env,
+    player: Address,
+    game_id: u32,
+    status: AppealStatus,
+) {
+    StrikeAppealDecided {
+        player,
+        game_id,
+        status,
+    }
+    .publish(env);
+}
+
+pub(crate) fn treasury_inflow(env: &Env, token: Address, source: Inflow, amount: i128) {
+    TreasuryInflow {
+        token,
+        source,
+        amount,
+    }
+    .publish(env);
+}
+
+pub(crate) fn treasury_outflow(
+    env: &Env,
+    token: Address,
+    to: Address,
+    reason: Outflow,
+    amount: i128,
+) {
+    TreasuryOutflow {
+        token,
+        to,
+        reason,
+        amount,
+    }
+    .publish(env);
+}
+
+pub(crate) fn withdrawal_requested(
+    env: &Env,
+    withdrawal_id: u32,
+    token: Address,
+    to: Address,
+    amount: i128,
+    executable_ledger: u32,
+) {
+    WithdrawalRequested {
+        withdrawal_id,
+        token,
+        to,
+        amount,
+        executable_ledger,
+    }
+    .publish(env);
+}
+
+pub(crate) fn admin_action_proposed(
+    env: &Env,
+    action_id: u32,
+    action: AdminAction,
+    executable_ledger: u32,
+) {
+    AdminActionProposed {
+        action_id,
+        action,
+        executable_ledger,
+    }
+    .publish(env);
+}
+
+pub(crate) fn admin_action_executed(env: &Env, action_id: u32) {
+    AdminActionExecuted { action_id }.publish(env);
+}
+
+pub(crate) fn admin_action_cancelled(env: &Env, action_id: u32) {
+    AdminActionCancelled { action_id }.publish(env);
+}
+
+pub(crate) fn features_changed(env: &Env, disabled: u32) {
+    FeaturesChanged { disabled }.publish(env);
+}
+
+pub(crate) fn pause_level_changed(env: &Env, level: u32) {
+    PauseLevelChanged { level }.publish(env);
+}
@@ -0,0 +1,63 @@
+//! Feature switches. The admin keeps a bitmap of disabled subsystems so a misbehaving
+//! one can be turned off on its own, without a pause or an upgrade. Each switch blocks
+//! the calls listed for it and nothing else: a sonar ping already made can still be
+//! answered and listed games can still be joined, so games in flight can finish, and
+//! relayed players fall back to submitting their own calls.
+
+use soroban_sdk::{contractimpl, Env};
+
+use crate::error::{Error, OrPanic};
+use crate::events;
+use crate::{require_admin, DataKey};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// `use_sonar`, `use_line_sonar` and `use_scout`.
+pub const FEATURE_SONAR: u32 = 1 << 0;
+/// `list_game`.
+pub const FEATURE_LOBBY: u32 = 1 << 1;
+/// `relay_action`, `take_shot_signed` and `report_result_signed`.
+pub const FEATURE_RELAY: u32 = 1 << 2;
+/// `challenge_shot`.
+pub const FEATURE_CHALLENGE: u32 = 1 << 3;
+/// `claim_quest_reward`.
+pub const FEATURE_QUEST_REWARDS: u32 = 1 << 4;
+/// `create_club_match`.
+pub const FEATURE_CLUB_MATCHES: u32 = 1 << 5;
+
+fn disabled_features(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::DisabledFeatures)
+        .unwrap_or(0)
+}
+
+/// `Err(FeatureDisabled)` if `feature` is switched off.
+pub(crate) fn check(env: &Env, feature: u32) -> Result<(), Error> {
+    if disabled_features(env) & feature != 0 {
+        return Err(Error::FeatureDisabled);
+    }
+    Ok(())
+}
+
+/// Fail the current entry point if `feature` is switched off.
+pub(crate) fn require(env: &Env, feature: u32) {
+    check(env, feature).or_panic(env);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets the bitmap of disabled features (`FEATURE_*`). Takes effect at once,
+    /// so an emergency switch-off does not wait out the admin delay.
+    pub fn set_disabled_features(env: Env, disabled: u32) {
+        require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::DisabledFeatures, &disabled);
+        events::features_changed(&env, disabled);
+    }
+
+    /// Bitmap of the features the admin has disabled.
+    pub fn disabled_features(env: Env) -> u32 {
+        disabled_features(&env)
+    }
+}
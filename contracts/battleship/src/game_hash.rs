@@ -0,0 +1,80 @@
+//! Creator-derived game ids. Besides its sequential `u32` id, every game gets a
+//! 32-byte id hashed from the instance, its creator and the creator's game nonce, so a
+//! client knows the id before its `new_game` lands, whatever other creators submit in
+//! the same ledger, and ids from different instances never collide. The `u32` id stays
+//! the key for every other entry point; these views translate between the two.
+
+use soroban_sdk::{contractimpl, xdr::ToXdr, Address, BytesN, Env};
+
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, Game};
+
+/// The hash id of the `nonce`-th game (counting from 0) created by `creator` on the
+/// instance at `contract`: sha256 of the XDR of `(contract, creator, nonce)`.
+pub fn game_hash_for(env: &Env, contract: &Address, creator: &Address, nonce: u32) -> BytesN<32> {
+    let preimage = (contract.clone(), creator.clone(), nonce).to_xdr(env);
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+pub(crate) fn creator_nonce(env: &Env, creator: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CreatorNonce(creator.clone()))
+        .unwrap_or(0)
+}
+
+/// The game `creator` already created with `nonce`, or `None` if `nonce` is their
+/// next one or that game has since been removed. Panics on a nonce beyond the next.
+pub(crate) fn created_with(env: &Env, creator: &Address, nonce: u32) -> Option<u32> {
+    let next = creator_nonce(env, creator);
+    assert!(nonce <= next, "creation nonce ahead of creator_nonce");
+    if nonce == next {
+        return None;
+    }
+    let hash = game_hash_for(env, &env.current_contract_address(), creator, nonce);
+    let game_id: u32 = env.storage().persistent().get(&DataKey::GameByHash(hash))?;
+    let exists = env.storage().persistent().has(&DataKey::Game(game_id));
+    exists.then_some(game_id)
+}
+
+/// Give a newly created game its hash id and advance the creator's nonce.
+pub(crate) fn assign(env: &Env, game_id: u32, creator: &Address) {
+    let nonce = creator_nonce(env, creator);
+    let hash = game_hash_for(env, &env.current_contract_address(), creator, nonce);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GameByHash(hash.clone()), &game_id);
+    env.storage()
+        .persistent()
+        .set(&DataKey::GameHash(game_id), &hash);
+    env.storage()
+        .persistent()
+        .set(&DataKey::CreatorNonce(creator.clone()), &(nonce + 1));
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// The nonce the creator's next game will be hashed with (the number of games they
+    /// have created so far).
+    pub fn creator_nonce(env: Env, creator: Address) -> u32 {
+        creator_nonce(&env, &creator)
+    }
+
+    /// The hash id of a game, or `None` for games created before hash ids existed.
+    pub fn game_hash(env: Env, game_id: u32) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::GameHash(game_id))
+    }
+
+    /// The `u32` id of the game with this hash id, if there is one.
+    pub fn game_id_by_hash(env: Env, game_hash: BytesN<32>) -> Option<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::GameByHash(game_hash))
+    }
+
+    /// Read a game by its hash id, as `get_game` does by `u32` id.
+    pub fn get_game_by_hash(env: Env, game_hash: BytesN<32>) -> Game {
+        let game_id = Self::game_id_by_hash(env.clone(), game_hash).expect("game not found");
+        Self::get_game(env, game_id)
+    }
+}
@@ -0,0 +1,104 @@
+//! Board geometry. All coordinate validation and sonar-area sizing goes through [`Grid`]
+//! so that rules keyed off the board shape stay in one place.
+
+use soroban_sdk::contracttype;
+
+/// Side length of the standard square board.
+pub const SQUARE_SIZE: u32 = 10;
+
+/// Side length of the large square board.
+pub const LARGE_SIZE: u32 = 12;
+
+/// Smallest hex radius that fits the standard fleet: radius 2 gives a 5-cell middle
+/// row for the carrier and 19 cells for the 17 ship cells.
+pub const MIN_HEX_RADIUS: u32 = 2;
+
+/// Largest hex radius a game may be created with (a 19-cell-wide board).
+pub const MAX_HEX_RADIUS: u32 = 9;
+
+/// Shape of both players' boards.
+///
+/// Hex boards use axial coordinates `(q, r)` with `max(|q|, |r|, |q + r|) <= radius`.
+/// Contract calls take them offset by the radius, i.e. `x = q + radius`, `y = r + radius`,
+/// so every coordinate stays unsigned and the center cell is `(radius, radius)`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Grid {
+    /// The classic 10x10 board
+    #[default]
+    Square,
+    /// A 12x12 board
+    Large,
+    /// Hexagonal board with the given radius around the center cell
+    Hex(u32),
+}
+
+impl Grid {
+    /// Whether the Noir circuits can prove games on this board. They are written for
+    /// the classic 10x10 board, so games on any other shape can't be created until
+    /// circuits for it exist.
+    pub fn has_circuits(&self) -> bool {
+        *self == Grid::Square
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Grid::Square | Grid::Large => true,
+            Grid::Hex(radius) => (MIN_HEX_RADIUS..=MAX_HEX_RADIUS).contains(&radius),
+        }
+    }
+
+    /// Whether `(x, y)` is a cell on this board.
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        match *self {
+            Grid::Square => x < SQUARE_SIZE && y < SQUARE_SIZE,
+            Grid::Large => x < LARGE_SIZE && y < LARGE_SIZE,
+            Grid::Hex(radius) => {
+                let r = radius as i64;
+                let q = x as i64 - r;
+                let s = y as i64 - r;
+                q.abs() <= r && s.abs() <= r && (q + s).abs() <= r
+            }
+        }
+    }
+
+    /// Side length of the board's bounding square: coordinates run from 0 to `width() - 1`.
+    pub fn width(&self) -> u32 {
+        match *self {
+            Grid::Square => SQUARE_SIZE,
+            Grid::Large => LARGE_SIZE,
+            Grid::Hex(radius) => 2 * radius + 1,
+        }
+    }
+
+    /// Number of cells in column `x` (zero if there is no such column), which bounds
+    /// the count a defender may report for a line sonar.
+    pub fn column_cells(&self, x: u32) -> u32 {
+        match *self {
+            Grid::Square if x < SQUARE_SIZE => SQUARE_SIZE,
+            Grid::Large if x < LARGE_SIZE => LARGE_SIZE,
+            Grid::Hex(radius) if x <= 2 * radius => 2 * radius + 1 - x.abs_diff(radius),
+            _ => 0,
+        }
+    }
+
+    /// Whether two distinct cells are neighbours: any of the 8 surrounding cells on
+    /// square boards (diagonals included), any of the 6 on hex boards.
+    pub fn touches(&self, a: (u32, u32), b: (u32, u32)) -> bool {
+        let dx = a.0 as i64 - b.0 as i64;
+        let dy = a.1 as i64 - b.1 as i64;
+        match self {
+            Grid::Square | Grid::Large => dx.abs() <= 1 && dy.abs() <= 1,
+            Grid::Hex(_) => dx.abs() <= 1 && dy.abs() <= 1 && (dx + dy).abs() <= 1,
+        }
+    }
+
+    /// Number of cells a sonar ping covers, which bounds the count a defender may report:
+    /// the 3x3 block on square boards, the center hex and its 6 neighbours on hex boards.
+    pub fn sonar_area_cells(&self) -> u32 {
+        match self {
+            Grid::Square | Grid::Large => 9,
+            Grid::Hex(_) => 7,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Per-attacker map of resolved cells, so simple clients can gray out cells already
+//! fired at without replaying the action log. Built only from the attacker's own
+//! shots and the reports on them; it says nothing about unresolved cells.
+
+use soroban_sdk::{contractimpl, Address, Bytes, Env, Vec};
+
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, FleetGrid, Game};
+
+/// Not fired at yet (or an off-board cell of a hex grid's bounding square)
+pub const CELL_UNKNOWN: u32 = 0;
+/// Fired at and reported as a miss
+pub const CELL_MISS: u32 = 1;
+/// Fired at and reported as a hit
+pub const CELL_HIT: u32 = 2;
+
+/// Record the report on the mover's pending shot. Call before the turn passes.
+pub(crate) fn record(env: &Env, game_id: u32, game: &Game, hit: bool) {
+    let key = DataKey::ShotMap(game_id, game.turn, game.last_shot_grid);
+    let width = game.config.grid.width();
+    let mut map: Bytes = env.storage().persistent().get(&key).unwrap_or_else(|| {
+        let mut empty = Bytes::new(env);
+        for _ in 0..width * width {
+            empty.push_back(CELL_UNKNOWN as u8);
+        }
+        empty
+    });
+    let cell = if hit { CELL_HIT } else { CELL_MISS };
+    map.set(game.last_shot_y * width + game.last_shot_x, cell as u8);
+    env.storage().persistent().set(&key, &map);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Row-major map of the cells `attacker` has resolved on the opponent's `grid`:
+    /// `CELL_UNKNOWN`, `CELL_MISS` or `CELL_HIT` for each cell of the board's
+    /// bounding square (`Grid::width()` cells per row). Refused while the game's
+    /// score is hidden.
+    pub fn public_heatmap(env: Env, game_id: u32, attacker: Address, grid: FleetGrid) -> Vec<u32> {
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(!game.score_hidden(), "score hidden until the game ends");
+        let attacker_num = if attacker == game.player1 {
+            1
+        } else if attacker == game.player2 {
+            2
+        } else {
+            panic!("not a player");
+        };
+
+        let width = game.config.grid.width();
+        let map: Option<Bytes> =
+            env.storage()
+                .persistent()
+                .get(&DataKey::ShotMap(game_id, attacker_num, grid));
+        let mut cells = Vec::new(&env);
+        for i in 0..width * width {
+            let cell = map
+                .as_ref()
+                .map_or(CELL_UNKNOWN, |m| m.get_unchecked(i) as u32);
+            cells.push_back(cell);
+        }
+        cells
+    }
+}
@@ -0,0 +1,86 @@
+//! Calls out to the game hub, through the plumbing in `dark-fleet-game-core` that any
+//! game contract in the workspace can share. Each hub's interface version is probed
+//! the first time it is needed and remembered per hub address.
+
+use dark_fleet_game_core::{probe_version, HubReportable, SessionStart};
+use dark_fleet_game_core::{HUB_INTERFACE_V1, HUB_INTERFACE_V3};
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{require_admin, DataKey};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+pub use dark_fleet_game_core::{HubOutcome, HubStartInfo};
+
+impl HubReportable for BattleshipContract {
+    fn stored_hub_version(env: &Env, hub: &Address) -> Option<u32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::HubInterfaceVersion(hub.clone()))
+    }
+
+    fn store_hub_version(env: &Env, hub: &Address, version: u32) {
+        env.storage()
+            .instance()
+            .set(&DataKey::HubInterfaceVersion(hub.clone()), &version);
+    }
+}
+
+fn configured_hub(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Hub)
+        .expect("not initialized")
+}
+
+/// Tell the hub a session has started between `player1` and `player2`. Battleship
+/// sessions carry no stakes.
+pub(crate) fn notify_hub_start(
+    env: &Env,
+    hub: &Address,
+    session_id: u32,
+    player1: &Address,
+    player2: &Address,
+    info: HubStartInfo,
+) {
+    let session = SessionStart {
+        session_id,
+        player1: player1.clone(),
+        player2: player2.clone(),
+        player1_points: 0,
+        player2_points: 0,
+        info,
+    };
+    BattleshipContract::notify_hub_start(env, hub, session);
+}
+
+/// Tell the hub how a session it was told about has ended.
+pub(crate) fn notify_hub_end(env: &Env, hub: &Address, session_id: u32, outcome: HubOutcome) {
+    BattleshipContract::notify_hub_end(env, hub, session_id, outcome);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Override the interface version used with the configured hub (admin only).
+    pub fn set_hub_interface_version(env: Env, version: u32) {
+        require_admin(&env);
+        assert!(
+            (HUB_INTERFACE_V1..=HUB_INTERFACE_V3).contains(&version),
+            "unsupported hub interface version"
+        );
+        Self::store_hub_version(&env, &configured_hub(&env), version);
+    }
+
+    /// Probe the configured hub again, e.g. after it was upgraded, and remember the answer.
+    pub fn refresh_hub_interface_version(env: Env) -> u32 {
+        let hub = configured_hub(&env);
+        let version = probe_version(&env, &hub);
+        Self::store_hub_version(&env, &hub, version);
+        version
+    }
+
+    /// The interface version used when notifying the configured hub.
+    pub fn get_hub_interface_version(env: Env) -> u32 {
+        let hub = configured_hub(&env);
+        Self::stored_hub_version(&env, &hub).unwrap_or_else(|| probe_version(&env, &hub))
+    }
+}
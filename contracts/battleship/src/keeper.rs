@@ -0,0 +1,89 @@
+//! Keeper registry. Anyone may call `gc` and `sweep_timeouts`, but addresses that
+//! register as keepers get their maintenance work and bounties tallied on-chain, so
+//! operators can see who keeps the deployment tidy.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map};
+
+use crate::DataKey;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Maintenance done by a registered keeper since registering.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeeperStats {
+    /// Ledger the keeper registered in
+    pub registered_ledger: u32,
+    /// Dead games deleted through `gc`
+    pub games_removed: u32,
+    /// Timeouts resolved through `sweep_timeouts`
+    pub timeouts_swept: u32,
+}
+
+fn load(env: &Env, keeper: &Address) -> Option<KeeperStats> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Keeper(keeper.clone()))
+}
+
+/// Whether `address` has registered as a keeper.
+pub(crate) fn is_registered(env: &Env, address: &Address) -> bool {
+    load(env, address).is_some()
+}
+
+fn bounties(env: &Env, keeper: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::KeeperBounty(keeper.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+/// Tally work done by `caller` if it is a registered keeper. `paid` is the bounty
+/// paid for it, per token.
+pub(crate) fn record(
+    env: &Env,
+    caller: &Address,
+    removed: u32,
+    swept: u32,
+    paid: Map<Address, i128>,
+) {
+    let Some(mut stats) = load(env, caller) else {
+        return;
+    };
+    stats.games_removed += removed;
+    stats.timeouts_swept += swept;
+    env.storage()
+        .persistent()
+        .set(&DataKey::Keeper(caller.clone()), &stats);
+    for (token, amount) in paid.iter() {
+        let total = bounties(env, caller, &token) + amount;
+        env.storage()
+            .persistent()
+            .set(&DataKey::KeeperBounty(caller.clone(), token), &total);
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Register as a keeper so maintenance calls are tallied in `keeper_stats`.
+    pub fn register_keeper(env: Env, keeper: Address) {
+        keeper.require_auth();
+        assert!(load(&env, &keeper).is_none(), "already registered");
+        let stats = KeeperStats {
+            registered_ledger: env.ledger().sequence(),
+            ..Default::default()
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Keeper(keeper), &stats);
+    }
+
+    /// A registered keeper's tallies, or `None` for unregistered addresses.
+    pub fn keeper_stats(env: Env, keeper: Address) -> Option<KeeperStats> {
+        load(&env, &keeper)
+    }
+
+    /// Bounties a registered keeper has been paid in `token`.
+    pub fn keeper_bounties(env: Env, keeper: Address, token: Address) -> i128 {
+        bounties(&env, &keeper, &token)
+    }
+}
@@ -1,16 +1,441 @@
 #![no_std]
+use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, IntoVal, Symbol, Val, Vec,
+    contract, contractimpl, contracttype, Address, Bytes, BytesN, Env, Vec,
 };
 
+mod action_log;
+mod away;
+mod backfill;
+mod capabilities;
+mod challenge;
+mod error;
+mod events;
+mod features;
+mod club;
+mod club_match;
+mod coach;
+mod convoy;
+mod deadlines;
+mod dive;
+mod game_hash;
+mod grid;
+mod heatmap;
+mod hub;
+mod keeper;
+mod limits;
+mod loadout;
+mod lobby;
+mod moderation;
+mod pause;
+mod pause_level;
+mod placement;
+mod quest;
+mod relay;
+mod schedule;
+mod schema;
+mod scout;
+mod scramble;
+mod sonar_commit;
+mod stats;
+mod strikes;
+mod summary;
+mod sunk;
+mod timelock;
+mod treasury;
+mod sweep;
+mod turns;
+mod tutorial;
+mod validate;
+mod watch;
+mod weather;
+
+pub use action_log::{ActionKind, GameAction};
+pub use capabilities::Capabilities;
+pub use challenge::{Challenge, ChallengeEntry, ChallengeRun};
+pub use error::Error;
+use error::{ensure, OrPanic};
+pub use club::{Club, ClubStanding};
+pub use club_match::{ClubMatch, MatchSlot};
+pub use convoy::ConvoyRules;
+pub use deadlines::Deadlines;
+pub use dive::{Dive, DIVE_SHOTS};
+pub use events::{AdminActionCancelled, AdminActionExecuted, AdminActionProposed};
+pub use events::{ClubMatchSettled, CoachEmote, FeaturesChanged, GameEnded, GameEndedReplayed};
+pub use events::{GameFlagged, GameNotice, GameStarted, GameVoided, PauseLevelChanged};
+pub use events::{SonarScrambled, StrikeAppealDecided, StrikeAppealed, StrikeRecorded};
+pub use events::{TreasuryInflow, TreasuryOutflow, WeatherChanged, WithdrawalRequested};
+pub use events::EVENT_SCHEMA_VERSION;
+pub use game_hash::game_hash_for;
+pub use features::{FEATURE_CHALLENGE, FEATURE_CLUB_MATCHES, FEATURE_LOBBY};
+pub use features::{FEATURE_QUEST_REWARDS, FEATURE_RELAY, FEATURE_SONAR};
+pub use grid::{Grid, MIN_HEX_RADIUS};
+use hub::{notify_hub_end, notify_hub_start};
+pub use hub::{HubOutcome, HubStartInfo};
+pub use keeper::KeeperStats;
+pub use limits::CreationLimits;
+pub use loadout::{Ability, LOADOUT_SIZE};
+pub use lobby::{Listing, ListingDeposit};
+pub use pause::PauseState;
+pub use pause_level::{PAUSE_ALL, PAUSE_DEPOSITS, PAUSE_NEW_GAMES};
+pub use placement::{headings, is_ship_line, ship_cell, ships_cross};
+pub use quest::{Quest, QuestGoal};
+pub use relay::{ActionPayload, SignedAction};
+pub use schedule::ScheduledMatch;
+pub use schema::{SchemaCheck, UPGRADE_CHECK_SAMPLE};
+pub use scout::SCOUT_AREA;
+pub use sonar_commit::{sonar_answer_hash, SonarCommitment};
+pub use strikes::{AppealStatus, Strike, StrikeAppeal, StrikePolicy};
+pub use strikes::APPEAL_WINDOW_LEDGERS;
+pub use stats::InstanceStats;
+pub use summary::GameSummary;
+pub use sunk::MAX_SHIP_CELLS;
+pub use timelock::{AdminAction, PendingAdminAction, MAX_PENDING_ADMIN_ACTIONS};
+pub use treasury::{Inflow, Outflow, Withdrawal, WITHDRAWAL_DELAY_LEDGERS};
+pub use weather::{Weather, WEATHER_INTERVAL};
+
+/// Storage keys. Every key scoped to one game must also be listed in `purge_game_keys`.
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
     Game(u32),
     GameCount,
     Hub,
+    RecentActions(u32),
+    Admin,
+    Challenge(u32),
+    ChallengeRun(u32, Address),
+    ChallengeBoard(u32),
+    ClubCount,
+    Club(u32),
+    GameClub(u32),
+    ClubStandings(u32),
+    ClubMatchCount,
+    ClubMatch(u32),
+    GameMatch(u32),
+    ClubMatches(u32),
+    QuestCount,
+    Quest(u32),
+    ActiveQuests,
+    QuestProgress(u32, Address),
+    QuestClaimed(u32, Address),
+    QuestPool(Address),
+    CreationLimits,
+    RateLimitExempt(Address),
+    LastCreated(Address),
+    OpenGames(Address),
+    ListingDeposit,
+    Listing(u32),
+    OpenIndex,
+    /// Treasury balance per token (see `treasury`)
+    TreasuryBalance(Address),
+    /// Bounty still owed for cleaning up a game whose listing deposit was forfeited
+    GameBounty(u32),
+    Action(u32, u32),
+    ActionCount(u32),
+    HubInterfaceVersion(Address),
+    Relayer,
+    GameplayKey(Address),
+    ActionNonce(Address),
+    SonarCommit(u32),
+    ShotMap(u32, u32, FleetGrid),
+    Keeper(Address),
+    /// Bounties paid to a keeper, per token
+    KeeperBounty(Address, Address),
+    QuestCredits(u32),
+    FlaggedGame(u32),
+    VoidReason(u32),
+    ScheduledMatch(u32),
+    Pause(u32),
+    MaxPauseLedgers,
+    Away(Address),
+    Observers(u32),
+    Coach(u32, Address),
+    Dive(u32, Address),
+    Strikes(Address),
+    StrikePolicy,
+    StrikeAppeal(Address, u32),
+    DefaultConfig,
+    ActiveGames,
+    GameByHash(BytesN<32>),
+    GameHash(u32),
+    CreatorNonce(Address),
+    TreasuryWithdrawalCount,
+    TreasuryWithdrawal(u32),
+    AdminDelay,
+    AdminActionCount,
+    PendingAdminActions,
+    DisabledFeatures,
+    PauseLevel,
+}
+
+/// Delete `game` and everything stored under keys scoped to it. The creator's nonce is
+/// kept, so a later game can never be handed a removed game's hash id. Keys only play
+/// can create are skipped for games that never started, to keep `gc` batches within
+/// the footprint limit.
+pub(crate) fn purge_game_keys(env: &Env, game_id: u32, game: &Game) {
+    let storage = env.storage().persistent();
+    if let Some(hash) = storage.get::<_, BytesN<32>>(&DataKey::GameHash(game_id)) {
+        storage.remove(&DataKey::GameByHash(hash));
+    }
+    for key in [
+        DataKey::Game(game_id),
+        DataKey::GameHash(game_id),
+        DataKey::RecentActions(game_id),
+        DataKey::GameClub(game_id),
+        DataKey::GameMatch(game_id),
+        DataKey::Listing(game_id),
+        DataKey::GameBounty(game_id),
+        DataKey::FlaggedGame(game_id),
+        DataKey::VoidReason(game_id),
+        DataKey::ScheduledMatch(game_id),
+        DataKey::Observers(game_id),
+        DataKey::Coach(game_id, game.player1.clone()),
+        DataKey::Coach(game_id, game.player2.clone()),
+    ] {
+        storage.remove(&key);
+    }
+
+    if game.boards_committed == game.boards_required() {
+        for key in [
+            DataKey::SonarCommit(game_id),
+            DataKey::QuestCredits(game_id),
+            DataKey::Pause(game_id),
+        ] {
+            storage.remove(&key);
+        }
+        for (num, player) in [(1, &game.player1), (2, &game.player2)] {
+            storage.remove(&DataKey::StrikeAppeal(player.clone(), game_id));
+            storage.remove(&DataKey::ShotMap(game_id, num, FleetGrid::Home));
+            if game.config.is_armada() {
+                storage.remove(&DataKey::ShotMap(game_id, num, FleetGrid::Forward));
+            }
+            if game.config.diving_submarine {
+                storage.remove(&DataKey::Dive(game_id, player.clone()));
+            }
+        }
+    }
+    action_log::clear(env, game_id);
+}
+
+/// Which player a completed game was decided in favour of.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Winner {
+    Player1,
+    Player2,
+}
+
+/// Why a game ended. `NotEnded` while the game is still in setup or in progress.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EndReason {
+    NotEnded,
+    /// The winner sank every ship cell of the opponent's fleet
+    AllShipsSunk,
+    /// The creator cancelled before the game started
+    Cancelled,
+    /// Armada mode: the winner sank the carriers of both opposing grids
+    CarriersSunk,
+    /// Hunt mode: the fleet player outlasted the hunter's turn limit
+    Survived,
+    /// The hub settled the game via `hub_end_game` (decided off-chain)
+    HubSettled,
+    /// Commit–reveal sonar: the defender missed the commit or reveal deadline
+    SonarTimeout,
+    /// Flagship mode: the winner sank the opponent's flagship
+    FlagshipSunk,
+    /// Convoy mode: the winner reached the convoy points target
+    ConvoyCaptured,
+    /// Voided by the admin or hub (`void_game`)
+    Voided,
+}
+
+/// Which of the defender's grids a shot targets. Only armada games have a forward grid.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FleetGrid {
+    Home,
+    Forward,
+}
+
+/// A cell on one of a player's grids.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coord {
+    pub x: u32,
+    pub y: u32,
+    pub grid: FleetGrid,
+}
+
+/// A player's running score, as returned by `player_score`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerScore {
+    pub hits: u32,
+    pub forward_hits: u32,
+    /// Reported shots
+    pub shots: u32,
+    pub hits_to_win: u32,
+}
+
+/// Lifecycle of a game. This is the source of truth for what action is pending;
+/// `Game::status` is kept alongside it for clients that only understand the u32 codes.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    /// Created, waiting for an opponent to join. Only the creator may commit yet; the
+    /// game starts once both players are seated and every board is committed.
+    Setup,
+    /// Both players present, waiting for the board commitments, in either order
+    AwaitingCommit,
+    /// In progress, the player whose turn it is must shoot or use sonar
+    AwaitingShot,
+    /// In progress, the defender must report hit/miss for the last shot
+    AwaitingReport,
+    /// In progress, the defender must report the sonar count
+    AwaitingSonarReport,
+    Completed(Winner),
+    Cancelled,
 }
 
+impl Phase {
+    /// Legacy status code: 0=created, 1=in_progress, 2=completed, 3=cancelled
+    pub fn status(&self) -> u32 {
+        match self {
+            Phase::Setup | Phase::AwaitingCommit => 0,
+            Phase::AwaitingShot | Phase::AwaitingReport | Phase::AwaitingSonarReport => 1,
+            Phase::Completed(_) => 2,
+            Phase::Cancelled => 3,
+        }
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        self.status() == 1
+    }
+}
+
+/// Asymmetric "hunter vs fleet" rules. Only the fleet player commits a board; the
+/// hunter fires several shots per turn and has a sonar that recharges instead of
+/// being single-use. The hunter wins by sinking the fleet, the fleet by surviving
+/// `turn_limit` hunter turns.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HuntRules {
+    /// True if the creator (player 1) is the hunter, false if the joiner is
+    pub hunter_is_player1: bool,
+    /// Shots (or sonar pings) the hunter gets per turn
+    pub shots_per_turn: u32,
+    /// Hunter turns between sonar uses; the first ping is available after this many turns
+    pub sonar_recharge_turns: u32,
+    /// Hunter turns the fleet must survive to win
+    pub turn_limit: u32,
+    /// Ship cells in the hidden fleet
+    pub fleet_cells: u32,
+}
+
+/// Variant of the game being played.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum GameMode {
+    /// One board per player, first to sink the opposing fleet wins
+    #[default]
+    Classic,
+    /// Each player also commits a forward fleet on a second grid, and wins by
+    /// sinking the whole home fleet or both carriers
+    Armada,
+    /// Asymmetric hunter-vs-fleet
+    Hunt(HuntRules),
+    /// Classic fleets, but each player designates one ship as their flagship and
+    /// sinking it wins outright
+    Flagship,
+    /// Classic fleets plus convoy cells that score points when hit; the first to the
+    /// points target wins even with ships remaining
+    Convoy(ConvoyRules),
+}
+
+/// Per-game rule options, fixed at creation.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameConfig {
+    /// Count ability uses (sonar) towards sonar eligibility alongside shots,
+    /// matching the original single `turns_taken` counter.
+    pub legacy_turn_counting: bool,
+    pub mode: GameMode,
+    /// Board shape shared by both players
+    pub grid: Grid,
+    /// Solo practice: the creator commits both boards and plays both sides.
+    /// The hub is never notified.
+    pub practice: bool,
+    /// Commit–reveal sonar: the defender answers a ping by committing to the count
+    /// within this many ledgers and revealing it within as many again (see
+    /// `commit_sonar`/`reveal_sonar`). `None` keeps the single `report_sonar` call.
+    pub sonar_commit_window: Option<u32>,
+    /// While the game is in progress `get_game` shows hit totals and `last_shot_hit`
+    /// as zero, `hits_to_win`/`accuracy`/`public_heatmap` refuse to answer, and reports
+    /// read as `ReportHidden` in the action log and in notices. Players read their own
+    /// score with the authenticated `player_score`.
+    pub hidden_score: bool,
+    /// Players pick their abilities when committing their board
+    /// (`commit_board_with_loadout`) and may only use those.
+    pub loadouts: bool,
+    /// Late-game pacing: once a player has played this many turns (shots and
+    /// abilities), their sonar is no longer single-use. Has no effect in hunt mode,
+    /// where sonar recharges anyway.
+    pub free_sonar_after_turn: Option<u32>,
+    /// House rule: ships may not touch, not even diagonally. The board proof takes
+    /// the flag as a public input and rejects touching layouts, and disclosed sunk
+    /// ships (`report_ship_sunk`) are checked against it.
+    pub no_touching: bool,
+    /// Ships may also lie diagonally on square boards. The board proof takes the
+    /// flag as a public input; disclosed sunk ships are checked against it.
+    pub diagonal_ships: bool,
+    /// Each player's submarine may submerge once per game (see `submerge`). The shot
+    /// proof takes whether a shot fell inside the dive as a public input.
+    pub diving_submarine: bool,
+    /// Chaos mode: every tenth round has drawn weather (a storm makes shots miss,
+    /// clear skies make sonar free); see `Game::weather`.
+    pub chaos_weather: bool,
+}
+
+impl GameConfig {
+    /// sha256 of the config's XDR encoding, identifying the rules a game is played under.
+    pub fn digest(&self, env: &Env) -> BytesN<32> {
+        env.crypto().sha256(&self.clone().to_xdr(env)).to_bytes()
+    }
+
+    pub fn is_armada(&self) -> bool {
+        self.mode == GameMode::Armada
+    }
+
+    pub fn is_flagship(&self) -> bool {
+        self.mode == GameMode::Flagship
+    }
+
+    pub fn hunt(&self) -> Option<&HuntRules> {
+        match &self.mode {
+            GameMode::Hunt(rules) => Some(rules),
+            _ => None,
+        }
+    }
+
+    pub fn convoy(&self) -> Option<&ConvoyRules> {
+        match &self.mode {
+            GameMode::Convoy(rules) => Some(rules),
+            _ => None,
+        }
+    }
+}
+
+/// Ship cells in the standard fleet: carrier 5, battleship 4, cruiser 3, submarine 3, destroyer 2.
+pub(crate) const STANDARD_FLEET_CELLS: u32 = 17;
+
+/// Shots a player must have fired before sonar unlocks.
+const SONAR_UNLOCK_TURNS: u32 = 3;
+
+/// How many client-supplied action ids are remembered per game for retry detection.
+const MAX_RECENT_ACTIONS: u32 = 8;
+
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub struct Game {
@@ -22,21 +447,425 @@ pub struct Game {
     pub turn: u32,       // 1 = player1's turn to shoot, 2 = player2's turn
     pub p1_hits: u32,    // total hits scored by player 1
     pub p2_hits: u32,    // total hits scored by player 2
-    pub status: u32,     // 0=created, 1=in_progress, 2=completed
+    pub p1_shots: u32,   // shots fired by player 1 that have been reported on
+    pub p2_shots: u32,   // shots fired by player 2 that have been reported on
+    pub status: u32,     // mirrors phase: 0=created, 1=in_progress, 2=completed, 3=cancelled
+    pub phase: Phase,
     pub session_id: u32,
-    pub awaiting_report: bool,
     pub last_shot_x: u32,
     pub last_shot_y: u32,
-    pub p1_turns_taken: u32,
-    pub p2_turns_taken: u32,
+    pub p1_shots_taken: u32,     // shots fired, including one awaiting a report
+    pub p2_shots_taken: u32,
+    pub p1_abilities_used: u32,  // turns spent on abilities (sonar) instead of shooting
+    pub p2_abilities_used: u32,
     pub p1_sonar_used: bool,
     pub p2_sonar_used: bool,
-    pub awaiting_sonar: bool,
     pub sonar_center_x: u32,
     pub sonar_center_y: u32,
     pub last_sonar_count: u32,
     pub last_shot_proof: Bytes,
     pub last_sonar_proof: Bytes,
+    pub winner: Option<Address>,
+    pub end_reason: EndReason,
+    pub config: GameConfig,
+    pub rules_digest: BytesN<32>, // config.digest(), fixed at creation
+    // Armada mode only
+    pub forward_hash1: BytesN<32>,
+    pub forward_hash2: BytesN<32>,
+    pub p1_forward_hits: u32,
+    pub p2_forward_hits: u32,
+    pub p1_carriers_sunk: u32, // bitmask of opponent carriers sunk: bit 0 home, bit 1 forward
+    pub p2_carriers_sunk: u32,
+    pub last_shot_grid: FleetGrid,
+    pub last_shot_hit: bool,
+    // Hunt mode only
+    pub hunt_turns: u32,            // hunter turns completed
+    pub hunt_actions_in_turn: u32,  // shots/pings resolved in the current hunter turn
+    pub hunt_sonar_ready_turn: u32, // hunter turn from which sonar can be used again
+    // Loadout games only
+    pub p1_loadout: Vec<Ability>,
+    pub p2_loadout: Vec<Ability>,
+    pub p1_scramble_used: bool,
+    pub p2_scramble_used: bool,
+    pub last_sonar_scrambled: bool, // the last sonar answer was declared scrambled, not a count
+    pub awaiting_line_sonar: bool,  // the pending sonar is a line scan of column sonar_center_x
+    pub p1_scout_used: bool,
+    pub p2_scout_used: bool,
+    pub awaiting_scout: bool, // the pending sonar is a scout flight from sonar_center_x/y
+    // Flagship mode only
+    pub p1_flagship_sunk: bool, // player 1 sank player 2's flagship
+    pub p2_flagship_sunk: bool,
+    // Convoy mode only
+    pub p1_convoy_points: u32, // opposing convoy cells player 1 has hit
+    pub p2_convoy_points: u32,
+    // Cells of opposing ships player 1 has sunk, one entry per ship, as disclosed
+    // by the defender with `report_ship_sunk`
+    pub p1_sunk_ships: Vec<Vec<Coord>>,
+    pub p2_sunk_ships: Vec<Vec<Coord>>,
+    pub weather: Weather, // modifier for the current round; always calm without chaos_weather
+}
+
+impl Game {
+    /// Whether player 1 or 2 may use `ability` at all: outside loadout games
+    /// everyone has sonar and nothing else, otherwise only what they picked.
+    fn has_ability(&self, player_num: u32, ability: Ability) -> bool {
+        if !self.config.loadouts {
+            return ability == Ability::Sonar;
+        }
+        let loadout = if player_num == 1 {
+            &self.p1_loadout
+        } else {
+            &self.p2_loadout
+        };
+        loadout.contains(ability)
+    }
+
+    /// Whether hit totals are masked from the public views right now.
+    pub(crate) fn score_hidden(&self) -> bool {
+        self.config.hidden_score && self.phase.is_in_progress()
+    }
+
+    /// Address of player `num` (1 or 2).
+    fn player(&self, num: u32) -> Address {
+        if num == 1 {
+            self.player1.clone()
+        } else {
+            self.player2.clone()
+        }
+    }
+
+    fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+        self.status = phase.status();
+    }
+
+    /// Board commitments needed before play starts: one per player, two in armada mode,
+    /// and only the fleet player's in hunt mode.
+    fn boards_required(&self) -> u32 {
+        if self.config.is_armada() {
+            4
+        } else if self.config.hunt().is_some() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Player number (1 or 2) of the hunter in hunt mode.
+    fn hunter(&self) -> Option<u32> {
+        self.config
+            .hunt()
+            .map(|h| if h.hunter_is_player1 { 1 } else { 2 })
+    }
+
+    /// Total ship cells a player must hit to win: the standard fleet, or the hunt fleet.
+    fn fleet_cells(&self) -> u32 {
+        match self.config.hunt() {
+            Some(hunt) => hunt.fleet_cells,
+            None => STANDARD_FLEET_CELLS,
+        }
+    }
+
+    /// Hand the move to the other player after a report. In hunt mode the hunter keeps
+    /// the move and the report instead counts towards the hunter's current turn.
+    fn end_turn(&mut self) {
+        if let Some(hunt) = self.config.hunt() {
+            self.hunt_actions_in_turn += 1;
+            if self.hunt_actions_in_turn == hunt.shots_per_turn {
+                self.hunt_actions_in_turn = 0;
+                self.hunt_turns += 1;
+            }
+        }
+        self.pass_move();
+        self.set_phase(Phase::AwaitingShot);
+    }
+
+    /// Spend the mover's sonar charge on a scan at `(x, y)` (a column `x` for a line
+    /// scan) and wait for the defender's count.
+    fn start_scan(&mut self, x: u32, y: u32, line: bool) {
+        if let Some(hunt) = self.config.hunt() {
+            self.hunt_sonar_ready_turn = self.hunt_turns + hunt.sonar_recharge_turns;
+        }
+        if self.turn == 1 {
+            self.p1_sonar_used = true;
+            self.p1_abilities_used += 1;
+        } else {
+            self.p2_sonar_used = true;
+            self.p2_abilities_used += 1;
+        }
+
+        self.sonar_center_x = x;
+        self.sonar_center_y = y;
+        self.awaiting_line_sonar = line;
+        self.set_phase(Phase::AwaitingSonarReport);
+    }
+
+    /// Why player 1 or 2 cannot use `scan` (sonar or line sonar) right now, ignoring
+    /// whose turn it is, or `None` if they can. Both scan shapes share one charge.
+    fn sonar_blocker(&self, player_num: u32, scan: Ability) -> Option<Error> {
+        if !self.has_ability(player_num, scan) {
+            return Some(Error::AbilityNotInLoadout);
+        }
+        if self.config.hunt().is_some() {
+            if self.hunter() != Some(player_num) {
+                return Some(Error::OnlyHunterHasSonar);
+            }
+            if self.hunt_turns < self.hunt_sonar_ready_turn {
+                return Some(Error::SonarNotAvailable);
+            }
+            return None;
+        }
+
+        let used = if player_num == 1 {
+            self.p1_sonar_used
+        } else {
+            self.p2_sonar_used
+        };
+        if used && !self.sonar_is_free(player_num) {
+            Some(Error::SonarAlreadyUsed)
+        } else if self.sonar_turns(player_num) < SONAR_UNLOCK_TURNS {
+            Some(Error::SonarNotAvailable)
+        } else {
+            None
+        }
+    }
+
+    /// Turns that count towards sonar eligibility for player 1 or 2: shots fired,
+    /// plus ability uses when the game opted into legacy turn counting.
+    fn sonar_turns(&self, player_num: u32) -> u32 {
+        let (shots, abilities) = if player_num == 1 {
+            (self.p1_shots_taken, self.p1_abilities_used)
+        } else {
+            (self.p2_shots_taken, self.p2_abilities_used)
+        };
+        if self.config.legacy_turn_counting {
+            shots + abilities
+        } else {
+            shots
+        }
+    }
+
+    /// Whether player 1 or 2 is past the game's `free_sonar_after_turn` threshold, or
+    /// the round has clear skies, so using sonar no longer spends their single charge.
+    fn sonar_is_free(&self, player_num: u32) -> bool {
+        if self.weather == Weather::ClearSkies {
+            return true;
+        }
+        let (shots, abilities) = if player_num == 1 {
+            (self.p1_shots_taken, self.p1_abilities_used)
+        } else {
+            (self.p2_shots_taken, self.p2_abilities_used)
+        };
+        self.config
+            .free_sonar_after_turn
+            .is_some_and(|threshold| shots + abilities >= threshold)
+    }
+
+    /// Checks that the player to move may shoot or use sonar right now.
+    fn check_awaiting_shot(&self) -> Result<(), Error> {
+        match self.phase {
+            Phase::AwaitingShot => Ok(()),
+            Phase::AwaitingReport => Err(Error::WaitingForHitReport),
+            Phase::AwaitingSonarReport => Err(Error::WaitingForSonarReport),
+            _ => Err(Error::NotInProgress),
+        }
+    }
+
+
+    /// Checks for `join_game`, except club membership (see `validate::check_join`).
+    fn check_join(&self, player2: &Address) -> Result<(), Error> {
+        if self.phase.status() != 0 {
+            return Err(Error::NotInSetup);
+        }
+        if self.phase != Phase::Setup {
+            return Err(Error::AlreadyJoined);
+        }
+        if *player2 == self.player1 {
+            return Err(Error::CannotJoinOwnGame);
+        }
+        Ok(())
+    }
+
+    /// Checks for `cancel_game`.
+    fn check_cancel(&self, player: &Address) -> Result<(), Error> {
+        if self.phase.status() != 0 {
+            return Err(Error::NotInSetup);
+        }
+        if *player != self.player1 {
+            return Err(Error::OnlyCreatorCanCancel);
+        }
+        Ok(())
+    }
+
+    /// Checks for `commit_board`, returning which board (1 or 2) the commitment fills.
+    fn check_commit(&self, env: &Env, player: &Address) -> Result<u32, Error> {
+        if self.phase.status() != 0 {
+            return Err(Error::NotInSetup);
+        }
+        // The creator may commit while the game is listed; nobody else is seated yet
+        if self.phase == Phase::Setup && *player != self.player1 {
+            return Err(Error::OpponentNotJoined);
+        }
+
+        let zero_hash = BytesN::from_array(env, &[0u8; 32]);
+        let (board, hash) = if self.config.practice
+            && *player == self.player1
+            && self.board_hash1 != zero_hash
+        {
+            // Practice: the creator's second commitment is the opposing board
+            (2, &self.board_hash2)
+        } else if *player == self.player1 {
+            (1, &self.board_hash1)
+        } else if self.player2 != self.player1 && *player == self.player2 {
+            (2, &self.board_hash2)
+        } else {
+            return Err(Error::NotInThisGame);
+        };
+        if self.hunter() == Some(board) {
+            return Err(Error::HunterHasNoBoard);
+        }
+        if *hash != zero_hash {
+            return Err(Error::BoardAlreadyCommitted);
+        }
+        Ok(board)
+    }
+
+    /// Checks for `take_shot`.
+    fn check_shot(&self, player: &Address, x: u32, y: u32, grid: FleetGrid) -> Result<(), Error> {
+        self.check_awaiting_shot()?;
+        if !self.config.grid.contains(x, y) {
+            return Err(Error::ShotOutOfBounds);
+        }
+        if grid != FleetGrid::Home && !self.config.is_armada() {
+            return Err(Error::NotArmada);
+        }
+        if let Some(hunt) = self.config.hunt() {
+            if self.hunt_turns >= hunt.turn_limit {
+                return Err(Error::HunterOutOfTurns);
+            }
+        }
+        self.check_turn(player)
+    }
+
+    /// Checks for `report_result`.
+    fn check_report(&self, player: &Address) -> Result<(), Error> {
+        if !self.phase.is_in_progress() {
+            return Err(Error::NotInProgress);
+        }
+        if self.phase != Phase::AwaitingReport {
+            return Err(Error::NoShotToReport);
+        }
+        self.check_defender(player)
+    }
+
+    /// Checks for `use_sonar`.
+    fn check_sonar(&self, player: &Address, center_x: u32, center_y: u32) -> Result<(), Error> {
+        self.check_awaiting_shot()?;
+        if !self.config.grid.contains(center_x, center_y) {
+            return Err(Error::SonarOutOfBounds);
+        }
+        self.check_turn(player)?;
+        if let Some(e) = self.sonar_blocker(self.turn, Ability::Sonar) {
+            return Err(e);
+        }
+        if let Some(hunt) = self.config.hunt() {
+            if self.hunt_turns >= hunt.turn_limit {
+                return Err(Error::HunterOutOfTurns);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks for `use_line_sonar`.
+    fn check_line_sonar(&self, player: &Address, column: u32) -> Result<(), Error> {
+        self.check_awaiting_shot()?;
+        if self.config.grid.column_cells(column) == 0 {
+            return Err(Error::SonarOutOfBounds);
+        }
+        self.check_turn(player)?;
+        if let Some(e) = self.sonar_blocker(self.turn, Ability::LineSonar) {
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Checks for `report_sonar`.
+    fn check_sonar_report(&self, player: &Address, count: u32) -> Result<(), Error> {
+        if !self.phase.is_in_progress() {
+            return Err(Error::NotInProgress);
+        }
+        if self.phase != Phase::AwaitingSonarReport {
+            return Err(Error::NoSonarToReport);
+        }
+        let max_count = if self.awaiting_scout {
+            // A scout only answers whether the area holds any ship cell
+            1
+        } else if self.awaiting_line_sonar {
+            self.config.grid.column_cells(self.sonar_center_x)
+        } else {
+            self.config.grid.sonar_area_cells()
+        };
+        if count > max_count {
+            return Err(Error::InvalidSonarCount);
+        }
+        self.check_defender(player)
+    }
+
+    /// Checks for `claim_victory`, returning the side `player` wins as and why.
+    fn check_claim(&self, player: &Address) -> Result<(Winner, EndReason), Error> {
+        if !self.phase.is_in_progress() {
+            return Err(Error::NotInProgress);
+        }
+
+        // Both sides share one address in practice, so credit the side that got there
+        let as_player1 = if self.config.practice {
+            *player == self.player1 && self.p1_hits >= self.p2_hits
+        } else {
+            *player == self.player1
+        };
+        let (winner, hits, carriers_sunk, flagship_sunk, convoy_points) = if as_player1 {
+            (
+                Winner::Player1,
+                self.p1_hits,
+                self.p1_carriers_sunk,
+                self.p1_flagship_sunk,
+                self.p1_convoy_points,
+            )
+        } else if *player == self.player2 {
+            (
+                Winner::Player2,
+                self.p2_hits,
+                self.p2_carriers_sunk,
+                self.p2_flagship_sunk,
+                self.p2_convoy_points,
+            )
+        } else {
+            return Err(Error::NotAPlayer);
+        };
+
+        let is_fleet = self.hunter().is_some_and(|h| h != winner_num(winner));
+        let reason = if is_fleet {
+            if self.hunt_turns < self.config.hunt().unwrap().turn_limit {
+                return Err(Error::HunterHasTurnsLeft);
+            }
+            EndReason::Survived
+        } else if hits >= self.fleet_cells() {
+            EndReason::AllShipsSunk
+        } else if self.config.is_armada() && carriers_sunk == 0b11 {
+            EndReason::CarriersSunk
+        } else if flagship_sunk {
+            EndReason::FlagshipSunk
+        } else if self
+            .config
+            .convoy()
+            .is_some_and(|c| convoy_points >= c.points_to_win)
+        {
+            EndReason::ConvoyCaptured
+        } else {
+            return Err(Error::NotEnoughHits);
+        };
+        Ok((winner, reason))
+    }
 }
 
 #[contract]
@@ -44,19 +873,91 @@ pub struct BattleshipContract;
 
 #[contractimpl]
 impl BattleshipContract {
-    /// Initialize the contract with the game hub address
-    pub fn initialize(env: Env, hub: Address) {
+    /// Initialize the contract with the game hub address and the admin
+    /// (who posts daily challenges)
+    pub fn initialize(env: Env, hub: Address, admin: Address) {
         assert!(
             !env.storage().instance().has(&DataKey::Hub),
             "already initialized"
         );
         env.storage().instance().set(&DataKey::Hub, &hub);
+        env.storage().instance().set(&DataKey::Admin, &admin);
         env.storage().instance().set(&DataKey::GameCount, &0u32);
     }
 
-    /// Create a new game. Player 2 joins later via join_game(). Returns the game/session ID.
-    pub fn new_game(env: Env, player1: Address) -> u32 {
+    /// Create a new game under `default_config`. Player 2 joins later via join_game().
+    /// Returns the game/session ID.
+    ///
+    /// `creation_nonce`, if given, must be the creator's `creator_nonce`. A retried
+    /// transaction that repeats an already used nonce returns the game that nonce
+    /// created instead of creating another, and fails if that game has been removed.
+    pub fn new_game(env: Env, player1: Address, creation_nonce: Option<u32>) -> u32 {
+        if let Some(nonce) = creation_nonce {
+            if let Some(game_id) = game_hash::created_with(&env, &player1, nonce) {
+                player1.require_auth();
+                return game_id;
+            }
+            assert!(
+                nonce == game_hash::creator_nonce(&env, &player1),
+                "game created with this nonce was removed"
+            );
+        }
+        let config = Self::default_config(env.clone());
+        Self::new_game_with_config(env, player1, config)
+    }
+
+    /// Admin sets the rules `new_game` uses, e.g. a league's house rules. The config
+    /// is checked when a game is created with it.
+    pub fn set_default_config(env: Env, config: GameConfig) {
+        require_admin(&env);
+        timelock::require_no_delay(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::DefaultConfig, &config);
+    }
+
+    /// The rules `new_game` uses: `GameConfig::default()` unless the admin set others.
+    pub fn default_config(env: Env) -> GameConfig {
+        env.storage()
+            .instance()
+            .get(&DataKey::DefaultConfig)
+            .unwrap_or_default()
+    }
+
+    /// Create a new game with non-default rule options. Returns the game/session ID.
+    pub fn new_game_with_config(env: Env, player1: Address, config: GameConfig) -> u32 {
         player1.require_auth();
+        pause_level::require(&env, PAUSE_NEW_GAMES);
+        assert!(config.grid.is_valid(), "invalid grid");
+        assert!(config.grid.has_circuits(), "no proof circuits for this grid");
+        if let Some(hunt) = config.hunt() {
+            assert!(
+                hunt.shots_per_turn > 0
+                    && hunt.turn_limit > 0
+                    && hunt.fleet_cells > 0,
+                "invalid hunt rules"
+            );
+        }
+        if let Some(convoy) = config.convoy() {
+            assert!(convoy.is_valid(), "invalid convoy rules");
+        }
+        assert!(
+            !config.practice || config.mode == GameMode::Classic,
+            "practice games use classic rules"
+        );
+        // The hunter commits no board to pick a loadout with
+        assert!(
+            !config.loadouts || config.hunt().is_none(),
+            "hunt games have no loadouts"
+        );
+        assert!(
+            !config.diving_submarine || config.hunt().is_none(),
+            "hunt fleets have no submarine"
+        );
+        assert!(
+            !config.chaos_weather || config.hunt().is_none(),
+            "hunt games have no weather"
+        );
 
         let mut count: u32 = env
             .storage()
@@ -65,37 +966,86 @@ impl BattleshipContract {
             .unwrap_or(0);
         count += 1;
 
+        let sonar_ready_turn = config.hunt().map(|h| h.sonar_recharge_turns).unwrap_or(0);
+        // A practice game has no opponent to wait for
+        let phase = if config.practice {
+            Phase::AwaitingCommit
+        } else {
+            Phase::Setup
+        };
+        ensure(
+            &env,
+            config.practice || !strikes::is_barred(&env, &player1),
+            Error::PlayerBarred,
+        );
+        limits::record_creation(&env, &player1, phase == Phase::Setup);
         let zero_hash = BytesN::from_array(&env, &[0u8; 32]);
         let game = Game {
             player1: player1.clone(),
             player2: player1.clone(), // sentinel: player2 == player1 means "no opponent yet"
             board_hash1: zero_hash.clone(),
-            board_hash2: zero_hash,
+            board_hash2: zero_hash.clone(),
             boards_committed: 0,
             turn: 1,
             p1_hits: 0,
             p2_hits: 0,
+            p1_shots: 0,
+            p2_shots: 0,
             status: 0,
+            phase,
             session_id: count,
-            awaiting_report: false,
             last_shot_x: 0,
             last_shot_y: 0,
-            p1_turns_taken: 0,
-            p2_turns_taken: 0,
+            p1_shots_taken: 0,
+            p2_shots_taken: 0,
+            p1_abilities_used: 0,
+            p2_abilities_used: 0,
             p1_sonar_used: false,
             p2_sonar_used: false,
-            awaiting_sonar: false,
             sonar_center_x: 0,
             sonar_center_y: 0,
             last_sonar_count: 0,
             last_shot_proof: Bytes::new(&env),
             last_sonar_proof: Bytes::new(&env),
+            winner: None,
+            end_reason: EndReason::NotEnded,
+            rules_digest: config.digest(&env),
+            config,
+            forward_hash1: zero_hash.clone(),
+            forward_hash2: zero_hash,
+            p1_forward_hits: 0,
+            p2_forward_hits: 0,
+            p1_carriers_sunk: 0,
+            p2_carriers_sunk: 0,
+            last_shot_grid: FleetGrid::Home,
+            last_shot_hit: false,
+            hunt_turns: 0,
+            hunt_actions_in_turn: 0,
+            hunt_sonar_ready_turn: sonar_ready_turn,
+            p1_loadout: Vec::new(&env),
+            p2_loadout: Vec::new(&env),
+            p1_scramble_used: false,
+            p2_scramble_used: false,
+            last_sonar_scrambled: false,
+            awaiting_line_sonar: false,
+            p1_scout_used: false,
+            p2_scout_used: false,
+            awaiting_scout: false,
+            p1_flagship_sunk: false,
+            p2_flagship_sunk: false,
+            p1_convoy_points: 0,
+            p2_convoy_points: 0,
+            p1_sunk_ships: Vec::new(&env),
+            p2_sunk_ships: Vec::new(&env),
+            weather: Weather::Calm,
         };
 
         env.storage()
             .persistent()
             .set(&DataKey::Game(count), &game);
         env.storage().instance().set(&DataKey::GameCount, &count);
+        stats::record_created(&env);
+        game_hash::assign(&env, count, &player1);
 
         count
     }
@@ -109,21 +1059,25 @@ impl BattleshipContract {
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 0, "game not in setup phase");
-
-        assert!(game.player2 == game.player1, "player 2 already joined");
-        assert!(player2 != game.player1, "cannot join your own game");
+        validate::check_join(&env, game_id, &game, &player2).or_panic(&env);
 
+        action_log::log_action(&env, game_id, &player2, ActionKind::Join);
         game.player2 = player2;
+        game.set_phase(Phase::AwaitingCommit);
+        // A hunt fleet may already be committed, leaving nothing to wait for
+        if game.boards_committed == game.boards_required() {
+            start_game(&env, game_id, &mut game);
+        }
+        limits::release_open_game(&env, &game.player1);
+        lobby::close_listing(&env, game_id);
 
         env.storage()
             .persistent()
             .set(&DataKey::Game(game_id), &game);
     }
 
-    /// Commit a board hash (Pedersen hash of ship positions).
-    /// When both players have committed, the game starts and the hub is notified.
-    pub fn commit_board(env: Env, game_id: u32, player: Address, board_hash: BytesN<32>) {
+    /// Cancel a game that has not started yet. Only the creator (player 1) may cancel.
+    pub fn cancel_game(env: Env, game_id: u32, player: Address) {
         player.require_auth();
 
         let mut game: Game = env
@@ -131,51 +1085,31 @@ impl BattleshipContract {
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 0, "game not in setup phase");
-
-        let zero_hash = BytesN::from_array(&env, &[0u8; 32]);
-
-        if player == game.player1 {
-            assert!(game.board_hash1 == zero_hash, "board already committed");
-            game.board_hash1 = board_hash;
-        } else if game.player2 != game.player1 && player == game.player2 {
-            assert!(game.board_hash2 == zero_hash, "board already committed");
-            game.board_hash2 = board_hash;
-        } else {
-            panic!("not a player in this game");
+        validate::check_cancel(&env, game_id, &game, &player).or_panic(&env);
+        if game.phase == Phase::Setup {
+            limits::release_open_game(&env, &player);
+            lobby::close_listing(&env, game_id);
         }
 
-        game.boards_committed += 1;
-
-        if game.boards_committed == 2 {
-            game.status = 1;
-
-            // Notify game hub
-            if env.storage().instance().has(&DataKey::Hub) {
-                let hub: Address = env.storage().instance().get(&DataKey::Hub).unwrap();
-                let game_addr = env.current_contract_address();
-                let args: Vec<Val> = Vec::from_array(
-                    &env,
-                    [
-                        game_addr.into_val(&env),
-                        game.session_id.into_val(&env),
-                        game.player1.clone().into_val(&env),
-                        game.player2.clone().into_val(&env),
-                        0i128.into_val(&env),
-                        0i128.into_val(&env),
-                    ],
-                );
-                env.invoke_contract::<Val>(&hub, &Symbol::new(&env, "start_game"), args);
-            }
-        }
+        action_log::log_action(&env, game_id, &player, ActionKind::Cancel);
+        end_game(&env, game_id, &mut game, None, EndReason::Cancelled);
 
         env.storage()
             .persistent()
             .set(&DataKey::Game(game_id), &game);
     }
 
-    /// Take a shot at the opponent's board. Must be the caller's turn.
-    pub fn take_shot(env: Env, game_id: u32, player: Address, x: u32, y: u32) {
+    /// Commit a board hash (Pedersen hash of ship positions).
+    /// When both players have committed, the game starts and the hub is notified.
+    pub fn commit_board(env: Env, game_id: u32, player: Address, board_hash: BytesN<32>) {
+        player.require_auth();
+
+        apply_commit_board(&env, game_id, &player, board_hash, None);
+    }
+
+    /// Armada mode: commit the hash of the forward fleet (second grid).
+    /// The game starts once both players have committed both boards.
+    pub fn commit_forward_board(env: Env, game_id: u32, player: Address, board_hash: BytesN<32>) {
         player.require_auth();
 
         let mut game: Game = env
@@ -183,36 +1117,74 @@ impl BattleshipContract {
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 1, "game not in progress");
-        assert!(!game.awaiting_report, "waiting for hit report");
-        assert!(x < 10 && y < 10, "shot out of bounds");
-
-        if game.turn == 1 {
-            assert!(player == game.player1, "not your turn");
-        } else {
-            assert!(player == game.player2, "not your turn");
-        }
+        assert!(game.phase.status() == 0, "game not in setup phase");
+        assert!(game.config.is_armada(), "not an armada game");
 
-        game.last_shot_x = x;
-        game.last_shot_y = y;
-        game.awaiting_report = true;
+        let zero_hash = BytesN::from_array(&env, &[0u8; 32]);
 
-        // Increment turn counter for the shooter
-        if game.turn == 1 {
-            game.p1_turns_taken += 1;
+        if player == game.player1 {
+            assert!(game.forward_hash1 == zero_hash, "board already committed");
+            game.forward_hash1 = board_hash;
+        } else if game.player2 != game.player1 && player == game.player2 {
+            assert!(game.forward_hash2 == zero_hash, "board already committed");
+            game.forward_hash2 = board_hash;
         } else {
-            game.p2_turns_taken += 1;
+            panic!("not a player in this game");
         }
 
+        action_log::log_action(&env, game_id, &player, ActionKind::CommitForwardBoard);
+        record_commitment(&env, game_id, &mut game);
+
         env.storage()
             .persistent()
             .set(&DataKey::Game(game_id), &game);
     }
 
+    /// Take a shot at the opponent's board. Must be the caller's turn.
+    /// `grid` selects the target grid; anything but `Home` requires armada mode.
+    /// A repeated `action_id` is treated as a retry and ignored.
+    pub fn take_shot(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        x: u32,
+        y: u32,
+        grid: FleetGrid,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        apply_shot(&env, game_id, &player, x, y, grid);
+    }
+
     /// Report whether the last shot was a hit or miss.
     /// Called by the DEFENDER (the player who was shot at).
     /// Accepts a ZK proof (Bytes) that is stored on-chain for opponent verification.
-    pub fn report_result(env: Env, game_id: u32, player: Address, hit: bool, proof: Bytes) {
+    /// A repeated `action_id` is treated as a retry and ignored.
+    pub fn report_result(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        hit: bool,
+        proof: Bytes,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        apply_report(&env, game_id, &player, hit, proof);
+    }
+
+    /// Claim victory when you've sunk all opponent ships (every cell of the fleet hit),
+    /// both carriers in armada mode, the flagship in flagship mode, reached the points
+    /// target in convoy mode, or outlasted the hunter in hunt mode.
+    /// Notifies the game hub.
+    pub fn claim_victory(env: Env, game_id: u32, player: Address) {
         player.require_auth();
 
         let mut game: Game = env
@@ -220,37 +1192,46 @@ impl BattleshipContract {
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 1, "game not in progress");
-        assert!(game.awaiting_report, "no shot to report on");
+        let (winner, reason) = game.check_claim(&player).or_panic(&env);
 
-        // The reporting player is the defender (opponent of the shooter)
-        if game.turn == 1 {
-            // Player 1 shot, so player 2 reports
-            assert!(player == game.player2, "wrong player reporting");
-            if hit {
-                game.p1_hits += 1;
-            }
-        } else {
-            // Player 2 shot, so player 1 reports
-            assert!(player == game.player1, "wrong player reporting");
-            if hit {
-                game.p2_hits += 1;
-            }
-        }
+        action_log::log_action(&env, game_id, &player, ActionKind::ClaimVictory);
+        end_game(&env, game_id, &mut game, Some(winner), reason);
 
-        game.awaiting_report = false;
-        game.last_shot_proof = proof;
-        // Swap turns
-        game.turn = if game.turn == 1 { 2 } else { 1 };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+
+    /// Settle an in-progress game decided off-chain (conduct violations, arbitration).
+    /// Only the configured hub may call this; the hub is not notified back.
+    pub fn hub_end_game(env: Env, game_id: u32, winner: Winner) {
+        let hub: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Hub)
+            .expect("not initialized");
+        hub.require_auth();
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(game.phase.is_in_progress(), "game not in progress");
+        assert!(hub_for(&env, &game).is_some(), "game not tracked by the hub");
+
+        action_log::log_action(&env, game_id, &hub, ActionKind::HubSettled(winner));
+        end_game(&env, game_id, &mut game, Some(winner), EndReason::HubSettled);
 
         env.storage()
             .persistent()
             .set(&DataKey::Game(game_id), &game);
     }
 
-    /// Claim victory when you've sunk all opponent ships (17 hits).
-    /// Notifies the game hub.
-    pub fn claim_victory(env: Env, game_id: u32, player: Address) {
+    /// Armada mode: the defender declares that the hit they just reported sank the
+    /// carrier on the targeted grid. Accepts a ZK proof stored for opponent verification.
+    /// Must be called on the defender's own turn, before they act.
+    pub fn report_carrier_sunk(env: Env, game_id: u32, player: Address, proof: Bytes) {
         player.require_auth();
 
         let mut game: Game = env
@@ -258,39 +1239,79 @@ impl BattleshipContract {
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 1, "game not in progress");
+        assert!(game.config.is_armada(), "not an armada game");
+        assert!(
+            game.phase == Phase::AwaitingShot && game.last_shot_hit,
+            "no hit to report on"
+        );
 
-        let player1_won = if player == game.player1 {
-            assert!(game.p1_hits >= 17, "not enough hits to win");
-            true
-        } else if player == game.player2 {
-            assert!(game.p2_hits >= 17, "not enough hits to win");
-            false
+        // Turns already swapped: the defender of the last shot is now the player to move
+        let grid_bit = match game.last_shot_grid {
+            FleetGrid::Home => 0b01,
+            FleetGrid::Forward => 0b10,
+        };
+        let carriers_sunk = if game.turn == 1 {
+            assert!(player == game.player1, "wrong player reporting");
+            &mut game.p2_carriers_sunk
         } else {
-            panic!("not a player");
+            assert!(player == game.player2, "wrong player reporting");
+            &mut game.p1_carriers_sunk
         };
+        assert!(*carriers_sunk & grid_bit == 0, "carrier already sunk");
+        *carriers_sunk |= grid_bit;
+        let shooter = if game.turn == 1 {
+            game.player2.clone()
+        } else {
+            game.player1.clone()
+        };
+        quest::record_carrier_sunk(&env, game_id, &game, &shooter);
 
-        game.status = 2;
+        game.last_shot_hit = false;
+        game.last_shot_proof = proof;
+        action_log::log_action(&env, game_id, &player, ActionKind::CarrierSunk);
 
-        // Notify game hub
-        if env.storage().instance().has(&DataKey::Hub) {
-            let hub: Address = env.storage().instance().get(&DataKey::Hub).unwrap();
-            let args: Vec<Val> = Vec::from_array(
-                &env,
-                [
-                    game.session_id.into_val(&env),
-                    player1_won.into_val(&env),
-                ],
-            );
-            env.invoke_contract::<Val>(&hub, &Symbol::new(&env, "end_game"), args);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+
+    /// Flagship mode: the defender declares that the hit they just reported sank their
+    /// flagship, handing the shooter the game. Accepts a ZK proof stored for opponent
+    /// verification. Must be called on the defender's own turn, before they act.
+    pub fn report_flagship_sunk(env: Env, game_id: u32, player: Address, proof: Bytes) {
+        player.require_auth();
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(game.config.is_flagship(), "not a flagship game");
+        assert!(
+            game.phase == Phase::AwaitingShot && game.last_shot_hit,
+            "no hit to report on"
+        );
+
+        // Turns already swapped: the defender of the last shot is now the player to move
+        if game.turn == 1 {
+            assert!(player == game.player1, "wrong player reporting");
+            game.p2_flagship_sunk = true;
+        } else {
+            assert!(player == game.player2, "wrong player reporting");
+            game.p1_flagship_sunk = true;
         }
 
+        game.last_shot_hit = false;
+        game.last_shot_proof = proof;
+        action_log::log_action(&env, game_id, &player, ActionKind::FlagshipSunk);
+
         env.storage()
             .persistent()
             .set(&DataKey::Game(game_id), &game);
     }
 
-    /// Check if sonar is available for a player (every 3 turns, one use per game)
+    /// Check if sonar is available for a player on their turn (after 3 shots, one use
+    /// per game; recharging for the hunter in hunt mode)
     pub fn sonar_available(env: Env, game_id: u32, player: Address) -> bool {
         let game: Game = env
             .storage()
@@ -298,106 +1319,179 @@ impl BattleshipContract {
             .get(&DataKey::Game(game_id))
             .expect("game not found");
 
-        if game.status != 1 || game.awaiting_report || game.awaiting_sonar {
+        if game.phase != Phase::AwaitingShot {
             return false;
         }
 
-        if player == game.player1 {
-            if game.turn != 1 || game.p1_sonar_used {
-                return false;
-            }
-            game.p1_turns_taken >= 3
+        if game.config.practice && player == game.player1 {
+            game.sonar_blocker(game.turn, Ability::Sonar).is_none()
+        } else if player == game.player1 {
+            game.turn == 1 && game.sonar_blocker(1, Ability::Sonar).is_none()
         } else if player == game.player2 {
-            if game.turn != 2 || game.p2_sonar_used {
-                return false;
-            }
-            game.p2_turns_taken >= 3
+            game.turn == 2 && game.sonar_blocker(2, Ability::Sonar).is_none()
         } else {
             false
         }
     }
 
     /// Use sonar instead of firing a shot. Consumes the turn.
-    pub fn use_sonar(env: Env, game_id: u32, player: Address, center_x: u32, center_y: u32) {
+    /// A repeated `action_id` is treated as a retry and ignored.
+    pub fn use_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        center_x: u32,
+        center_y: u32,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        apply_sonar(&env, game_id, &player, center_x, center_y);
+    }
+
+    /// Loadout games: scan a whole column instead of firing a shot. Uses the same
+    /// charge as `use_sonar`; the defender answers with `report_sonar`, counting ship
+    /// cells in the column. A repeated `action_id` is treated as a retry and ignored.
+    pub fn use_line_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        column: u32,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        apply_line_sonar(&env, game_id, &player, column);
+    }
+
+    /// Report sonar result — opponent reports count of ship cells in the sonar area
+    /// (3x3 block, or center plus neighbours on hex boards, or the column of a line sonar).
+    /// Accepts a ZK proof (Bytes) that is stored on-chain for opponent verification.
+    /// A repeated `action_id` is treated as a retry and ignored.
+    pub fn report_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        count: u32,
+        proof: Bytes,
+        action_id: Option<BytesN<16>>,
+    ) {
         player.require_auth();
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
 
+        apply_sonar_report(&env, game_id, &player, count, proof);
+    }
+
+    /// Get game state (view function)
+    pub fn get_game(env: Env, game_id: u32) -> Game {
         let mut game: Game = env
             .storage()
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 1, "game not in progress");
-        assert!(!game.awaiting_report, "waiting for hit report");
-        assert!(!game.awaiting_sonar, "waiting for sonar report");
-        assert!(center_x < 10 && center_y < 10, "sonar out of bounds");
-
-        if game.turn == 1 {
-            assert!(player == game.player1, "not your turn");
-            assert!(!game.p1_sonar_used, "sonar already used");
-            assert!(
-                game.p1_turns_taken >= 3,
-                "sonar not available this turn"
-            );
-            game.p1_sonar_used = true;
-            game.p1_turns_taken += 1;
-        } else {
-            assert!(player == game.player2, "not your turn");
-            assert!(!game.p2_sonar_used, "sonar already used");
-            assert!(
-                game.p2_turns_taken >= 3,
-                "sonar not available this turn"
-            );
-            game.p2_sonar_used = true;
-            game.p2_turns_taken += 1;
+        if game.score_hidden() {
+            game.p1_hits = 0;
+            game.p2_hits = 0;
+            game.p1_forward_hits = 0;
+            game.p2_forward_hits = 0;
+            game.last_shot_hit = false;
         }
+        game
+    }
 
-        game.sonar_center_x = center_x;
-        game.sonar_center_y = center_y;
-        game.awaiting_sonar = true;
-
-        env.storage()
+    /// Hits the player still needs before they can claim victory.
+    pub fn hits_to_win(env: Env, game_id: u32, player: Address) -> u32 {
+        let game: Game = env
+            .storage()
             .persistent()
-            .set(&DataKey::Game(game_id), &game);
-    }
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(!game.score_hidden(), "score hidden until the game ends");
 
-    /// Report sonar result — opponent reports count of ship cells in 3x3 area.
-    /// Accepts a ZK proof (Bytes) that is stored on-chain for opponent verification.
-    pub fn report_sonar(env: Env, game_id: u32, player: Address, count: u32, proof: Bytes) {
-        player.require_auth();
+        let hits = if player == game.player1 {
+            game.p1_hits
+        } else if player == game.player2 {
+            game.p2_hits
+        } else {
+            panic!("not a player");
+        };
+        game.fleet_cells().saturating_sub(hits)
+    }
 
-        let mut game: Game = env
+    /// A player's own running score. Only the player or their coach may ask, and
+    /// unlike `hits_to_win`/`accuracy` it answers in hidden-score games too.
+    pub fn player_score(env: Env, game_id: u32, player: Address, viewer: Address) -> PlayerScore {
+        coach::require_viewer(&env, game_id, &player, &viewer);
+        let game: Game = env
             .storage()
             .persistent()
             .get(&DataKey::Game(game_id))
             .expect("game not found");
-        assert!(game.status == 1, "game not in progress");
-        assert!(game.awaiting_sonar, "no sonar to report on");
-        assert!(count <= 9, "invalid sonar count");
 
-        // The reporting player is the defender (opponent of the sonar user)
-        if game.turn == 1 {
-            assert!(player == game.player2, "wrong player reporting");
+        let (hits, forward_hits, shots) = if player == game.player1 {
+            (game.p1_hits, game.p1_forward_hits, game.p1_shots)
+        } else if player == game.player2 {
+            (game.p2_hits, game.p2_forward_hits, game.p2_shots)
         } else {
-            assert!(player == game.player1, "wrong player reporting");
+            panic!("not a player");
+        };
+        PlayerScore {
+            hits,
+            forward_hits,
+            shots,
+            hits_to_win: game.fleet_cells().saturating_sub(hits),
         }
+    }
 
-        game.last_sonar_count = count;
-        game.awaiting_sonar = false;
-        game.last_sonar_proof = proof;
-        // Swap turns
-        game.turn = if game.turn == 1 { 2 } else { 1 };
-
-        env.storage()
+    /// The shot `defender` owes a hit/miss report on, or `None` if it is not their
+    /// report that is pending. Only the defender or their coach may ask.
+    pub fn incoming_shot(
+        env: Env,
+        game_id: u32,
+        defender: Address,
+        viewer: Address,
+    ) -> Option<Coord> {
+        coach::require_viewer(&env, game_id, &defender, &viewer);
+        let game: Game = env
+            .storage()
             .persistent()
-            .set(&DataKey::Game(game_id), &game);
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+
+        game.check_report(&defender).ok().map(|_| Coord {
+            x: game.last_shot_x,
+            y: game.last_shot_y,
+            grid: game.last_shot_grid,
+        })
     }
 
-    /// Get game state (view function)
-    pub fn get_game(env: Env, game_id: u32) -> Game {
-        env.storage()
+    /// Shooting accuracy of a player in basis points (hits * 10000 / reported shots).
+    /// Returns 0 before the player's first shot has been reported.
+    pub fn accuracy(env: Env, game_id: u32, player: Address) -> u32 {
+        let game: Game = env
+            .storage()
             .persistent()
             .get(&DataKey::Game(game_id))
-            .expect("game not found")
+            .expect("game not found");
+        assert!(!game.score_hidden(), "score hidden until the game ends");
+
+        let (hits, shots) = if player == game.player1 {
+            (game.p1_hits + game.p1_forward_hits, game.p1_shots)
+        } else if player == game.player2 {
+            (game.p2_hits + game.p2_forward_hits, game.p2_shots)
+        } else {
+            panic!("not a player");
+        };
+
+        (hits * 10_000).checked_div(shots).unwrap_or(0)
     }
 
     /// Get total number of games created
@@ -409,11 +1503,328 @@ impl BattleshipContract {
     }
 }
 
+/// Require the configured admin's authorization, returning the admin address.
+pub(crate) fn require_admin(env: &Env) -> Address {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("not initialized");
+    admin.require_auth();
+    admin
+}
+
+/// The hub to notify about this game, if one is configured and the game counts there.
+fn hub_for(env: &Env, game: &Game) -> Option<Address> {
+    if game.config.practice || tutorial::is_scripted(env, game) {
+        return None;
+    }
+    env.storage().instance().get(&DataKey::Hub)
+}
+
+fn winner_num(winner: Winner) -> u32 {
+    match winner {
+        Winner::Player1 => 1,
+        Winner::Player2 => 2,
+    }
+}
+
+/// Record `player`'s board commitment. Callers check authorization.
+/// Loadout games need a `loadout`; other games must not pass one.
+fn apply_commit_board(
+    env: &Env,
+    game_id: u32,
+    player: &Address,
+    board_hash: BytesN<32>,
+    loadout: Option<Vec<Ability>>,
+) {
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    let board = validate::check_commit(env, game_id, &game, player).or_panic(env);
+    validate::check_board_hash(env, &game, board, &board_hash).or_panic(env);
+    let loadout = match loadout {
+        Some(loadout) => {
+            assert!(game.config.loadouts, "not a loadout game");
+            loadout::assert_valid(&loadout);
+            loadout
+        }
+        None => {
+            assert!(!game.config.loadouts, "loadout required");
+            Vec::new(env)
+        }
+    };
+    if board == 1 {
+        game.board_hash1 = board_hash;
+        game.p1_loadout = loadout;
+    } else {
+        game.board_hash2 = board_hash;
+        game.p2_loadout = loadout;
+    }
+
+    action_log::log_action(env, game_id, player, ActionKind::CommitBoard);
+    record_commitment(env, game_id, &mut game);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Fire `player`'s shot. Callers check authorization.
+fn apply_shot(env: &Env, game_id: u32, player: &Address, x: u32, y: u32, grid: FleetGrid) {
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    game.check_shot(player, x, y, grid).or_panic(env);
+
+    game.last_shot_x = x;
+    game.last_shot_y = y;
+    game.last_shot_grid = grid;
+    game.set_phase(Phase::AwaitingReport);
+
+    // Increment shot counter for the shooter
+    if game.turn == 1 {
+        game.p1_shots_taken += 1;
+    } else {
+        game.p2_shots_taken += 1;
+    }
+    action_log::log_action(env, game_id, player, ActionKind::Shot(x, y, grid));
+    if tutorial::is_scripted(env, &game) {
+        tutorial::answer_shot(env, game_id, &mut game);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Record `player`'s hit/miss report. Callers check authorization.
+fn apply_report(env: &Env, game_id: u32, player: &Address, hit: bool, proof: Bytes) {
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    // The reporting player is the defender (opponent of the shooter)
+    game.check_report(player).or_panic(env);
+    // Every shot misses in a storm, whatever the board says
+    let hit = hit && game.weather != Weather::Storm;
+    if game.turn == 1 {
+        game.p1_shots += 1;
+        if hit && game.last_shot_grid == FleetGrid::Forward {
+            game.p1_forward_hits += 1;
+        } else if hit {
+            game.p1_hits += 1;
+        }
+    } else {
+        game.p2_shots += 1;
+        if hit && game.last_shot_grid == FleetGrid::Forward {
+            game.p2_forward_hits += 1;
+        } else if hit {
+            game.p2_hits += 1;
+        }
+    }
+
+    game.last_shot_hit = hit;
+    game.last_shot_proof = proof;
+    heatmap::record(env, game_id, &game, hit);
+    game.end_turn();
+    weather::after_turn(env, game_id, &mut game);
+    action_log::log_action(env, game_id, player, ActionKind::Report(hit));
+    if tutorial::is_scripted(env, &game) {
+        tutorial::after_report(env, game_id, &mut game);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Fire `player`'s sonar ping. Callers check authorization.
+fn apply_sonar(env: &Env, game_id: u32, player: &Address, center_x: u32, center_y: u32) {
+    features::require(env, FEATURE_SONAR);
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    game.check_sonar(player, center_x, center_y).or_panic(env);
+
+    game.start_scan(center_x, center_y, false);
+    action_log::log_action(env, game_id, player, ActionKind::Sonar(center_x, center_y));
+    sonar_commit::open(env, game_id, &game);
+    if tutorial::is_scripted(env, &game) {
+        tutorial::answer_sonar(env, game_id, &mut game);
+    }
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Fire `player`'s line sonar. Callers check authorization.
+fn apply_line_sonar(env: &Env, game_id: u32, player: &Address, column: u32) {
+    features::require(env, FEATURE_SONAR);
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    game.check_line_sonar(player, column).or_panic(env);
+
+    game.start_scan(column, 0, true);
+    action_log::log_action(env, game_id, player, ActionKind::LineSonar(column));
+    sonar_commit::open(env, game_id, &game);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Record `player`'s sonar count. Callers check authorization.
+fn apply_sonar_report(env: &Env, game_id: u32, player: &Address, count: u32, proof: Bytes) {
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    // The reporting player is the defender (opponent of the sonar user)
+    game.check_sonar_report(player, count).or_panic(env);
+    sonar_commit::assert_not_pending(env, game_id);
+
+    game.last_sonar_count = count;
+    game.last_sonar_scrambled = false;
+    game.awaiting_line_sonar = false;
+    game.awaiting_scout = false;
+    game.last_sonar_proof = proof;
+    game.end_turn();
+    weather::after_turn(env, game_id, &mut game);
+    action_log::log_action(env, game_id, player, ActionKind::SonarReport(count));
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+}
+
+/// Count a board commitment and start the game once every required board is in.
+fn record_commitment(env: &Env, game_id: u32, game: &mut Game) {
+    game.boards_committed += 1;
+
+    // A creator who commits before anyone joins waits for `join_game` to start the game
+    if game.boards_committed == game.boards_required() && game.phase != Phase::Setup {
+        start_game(env, game_id, game);
+    }
+}
+
+/// Move a game with both players seated and every board committed into play.
+fn start_game(env: &Env, game_id: u32, game: &mut Game) {
+    game.set_phase(Phase::AwaitingShot);
+    // The hunter always has the move in hunt mode
+    if let Some(hunter) = game.hunter() {
+        game.turn = hunter;
+    }
+
+    // Notify game hub
+    if let Some(hub) = hub_for(env, game) {
+        let info = HubStartInfo {
+            config_digest: game.rules_digest.clone(),
+            first_mover: Some(game.player(game.turn)),
+        };
+        notify_hub_start(env, &hub, game.session_id, &game.player1, &game.player2, info);
+    }
+
+    events::game_started(
+        env,
+        game_id,
+        game.player1.clone(),
+        game.player2.clone(),
+        game.player(game.turn),
+        game.rules_digest.clone(),
+    );
+}
+
+/// Move a game into its terminal phase, record the outcome, and notify the hub.
+/// `winner` is `None` only for games that end without being played out (cancellation).
+/// The caller is responsible for persisting `game`.
+fn end_game(
+    env: &Env,
+    game_id: u32,
+    game: &mut Game,
+    winner: Option<Winner>,
+    end_reason: EndReason,
+) {
+    let winner_addr = match winner {
+        Some(w) => {
+            game.set_phase(Phase::Completed(w));
+            Some(if w == Winner::Player1 {
+                game.player1.clone()
+            } else {
+                game.player2.clone()
+            })
+        }
+        None => {
+            game.set_phase(Phase::Cancelled);
+            None
+        }
+    };
+    game.winner = winner_addr.clone();
+    game.end_reason = end_reason;
+    sonar_commit::clear(env, game_id);
+    pause::clear(env, game_id);
+    stats::record_ended(env);
+    club::record_result(env, game_id, game);
+    club_match::record_result(env, game_id, game);
+    if let Some(w) = winner {
+        quest::record_win(env, game_id, game, winner_num(w));
+    }
+
+    // Notify game hub (cancelled games were never started there, and the hub
+    // already knows about games it settled itself)
+    if let Some(w) = winner.filter(|_| end_reason != EndReason::HubSettled) {
+        if let Some(hub) = hub_for(env, game) {
+            notify_hub_end(env, &hub, game.session_id, HubOutcome::win(w == Winner::Player1));
+        }
+    }
+
+    events::game_ended(env, game_id, winner_addr, end_reason);
+}
+
+/// Returns true if `action_id` was already applied to this game (a resubmitted
+/// transaction). Otherwise records it, evicting the oldest remembered id.
+fn is_retried_action(env: &Env, game_id: u32, action_id: &Option<BytesN<16>>) -> bool {
+    let Some(action_id) = action_id else {
+        return false;
+    };
+
+    let key = DataKey::RecentActions(game_id);
+    let mut recent: Vec<BytesN<16>> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    if recent.contains(action_id) {
+        return true;
+    }
+
+    if recent.len() >= MAX_RECENT_ACTIONS {
+        recent.pop_front();
+    }
+    recent.push_back(action_id.clone());
+    env.storage().persistent().set(&key, &recent);
+    false
+}
+
 #[cfg(test)]
 mod test {
+    extern crate std;
+
     use super::*;
-    use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::{Bytes, Env};
+    use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+    use soroban_sdk::{Bytes, Env, Event};
 
     fn setup_game(env: &Env) -> (Address, Address, Address, u32) {
         let contract_id = env.register(BattleshipContract, ());
@@ -422,12 +1833,103 @@ mod test {
         let player1 = Address::generate(env);
         let player2 = Address::generate(env);
 
-        let game_id = client.new_game(&player1);
+        let game_id = client.new_game(&player1, &None);
         client.join_game(&game_id, &player2);
 
         (contract_id, player1, player2, game_id)
     }
 
+    /// Stand-in game hub that records the sessions it was told about.
+    #[contract]
+    struct MockHub;
+
+    #[contractimpl]
+    impl MockHub {
+        pub fn start_game(
+            env: Env,
+            _game: Address,
+            session_id: u32,
+            _player1: Address,
+            _player2: Address,
+            _p1_points: i128,
+            _p2_points: i128,
+        ) {
+            env.storage().instance().set(&session_id, &false);
+        }
+
+        pub fn end_game(env: Env, session_id: u32, _player1_won: bool) {
+            env.storage().instance().set(&session_id, &true);
+        }
+
+        /// `Some(ended)` once the session has been started
+        pub fn session(env: Env, session_id: u32) -> Option<bool> {
+            env.storage().instance().get(&session_id)
+        }
+    }
+
+    /// Stand-in version 2 hub that keeps the start info it was sent.
+    #[contract]
+    struct MockHubV2;
+
+    #[contractimpl]
+    impl MockHubV2 {
+        #[allow(clippy::too_many_arguments)]
+        pub fn start_game(
+            env: Env,
+            _game: Address,
+            session_id: u32,
+            _player1: Address,
+            _player2: Address,
+            _p1_points: i128,
+            _p2_points: i128,
+            info: HubStartInfo,
+        ) {
+            env.storage().instance().set(&session_id, &info);
+        }
+
+        pub fn end_game(_env: Env, _session_id: u32, _player1_won: bool) {}
+
+        pub fn hub_interface_version() -> u32 {
+            2
+        }
+
+        pub fn start_info(env: Env, session_id: u32) -> Option<HubStartInfo> {
+            env.storage().instance().get(&session_id)
+        }
+    }
+
+    /// Stand-in version 3 hub that keeps the outcomes it was sent.
+    #[contract]
+    struct MockHubV3;
+
+    #[contractimpl]
+    impl MockHubV3 {
+        #[allow(clippy::too_many_arguments)]
+        pub fn start_game(
+            _env: Env,
+            _game: Address,
+            _session_id: u32,
+            _player1: Address,
+            _player2: Address,
+            _p1_points: i128,
+            _p2_points: i128,
+            _info: HubStartInfo,
+        ) {
+        }
+
+        pub fn end_game_with_outcome(env: Env, session_id: u32, outcome: HubOutcome) {
+            env.storage().instance().set(&session_id, &outcome);
+        }
+
+        pub fn hub_interface_version() -> u32 {
+            3
+        }
+
+        pub fn outcome(env: Env, session_id: u32) -> Option<HubOutcome> {
+            env.storage().instance().get(&session_id)
+        }
+    }
+
     #[test]
     fn test_new_game() {
         let env = Env::default();
@@ -439,7 +1941,7 @@ mod test {
         let player1 = Address::generate(&env);
         let player2 = Address::generate(&env);
 
-        let game_id = client.new_game(&player1);
+        let game_id = client.new_game(&player1, &None);
         let game = client.get_game(&game_id);
         assert_eq!(game.player1, player1);
         assert_eq!(game.status, 0);
@@ -451,105 +1953,472 @@ mod test {
     }
 
     #[test]
-    fn test_commit_boards() {
+    fn test_phase_transitions() {
         let env = Env::default();
         env.mock_all_auths();
 
         let (contract_id, player1, player2, game_id) = setup_game(&env);
         let client = BattleshipContractClient::new(&env, &contract_id);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingCommit);
 
         let hash1 = BytesN::from_array(&env, &[1u8; 32]);
         let hash2 = BytesN::from_array(&env, &[2u8; 32]);
-
         client.commit_board(&game_id, &player1, &hash1);
-        let game = client.get_game(&game_id);
-        assert_eq!(game.boards_committed, 1);
-        assert_eq!(game.status, 0);
-
-        // Second board commit - no hub set, so it just updates status
         client.commit_board(&game_id, &player2, &hash2);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingShot);
+
+        let empty_proof = Bytes::new(&env);
+        for i in 0..17u32 {
+            client.take_shot(&game_id, &player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+        client.claim_victory(&game_id, &player1);
         let game = client.get_game(&game_id);
-        assert_eq!(game.boards_committed, 2);
-        assert_eq!(game.status, 1);
+        assert_eq!(game.phase, Phase::Completed(Winner::Player1));
+        assert_eq!(game.status, 2);
     }
 
     #[test]
-    fn test_take_shot_and_report() {
+    fn test_action_log_records_moves() {
         let env = Env::default();
         env.mock_all_auths();
 
         let (contract_id, player1, player2, game_id) = setup_game(&env);
         let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        client.take_shot(&game_id, &player1, &3, &4, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &Bytes::new(&env), &None);
 
-        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
-        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
-        client.commit_board(&game_id, &player1, &hash1);
-        client.commit_board(&game_id, &player2, &hash2);
-
-        // Player 1 shoots
-        client.take_shot(&game_id, &player1, &3, &4);
-        let game = client.get_game(&game_id);
-        assert_eq!(game.last_shot_x, 3);
-        assert_eq!(game.last_shot_y, 4);
-        assert!(game.awaiting_report);
+        assert_eq!(client.action_count(&game_id), 5);
+        let summary = client
+            .game_summaries(&soroban_sdk::vec![&env, game_id, 99])
+            .get(0)
+            .unwrap();
+        assert_eq!(summary.player2, Some(player2.clone()));
+        assert_eq!((summary.status, summary.turn), (1, 2));
+        assert_eq!(summary.last_action_ledger, Some(env.ledger().sequence()));
+        let kinds: std::vec::Vec<ActionKind> = client
+            .game_actions(&game_id, &2, &10)
+            .iter()
+            .map(|a| a.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            std::vec![
+                ActionKind::CommitBoard,
+                ActionKind::Shot(3, 4, FleetGrid::Home),
+                ActionKind::Report(true),
+            ]
+        );
+        let first = client.game_actions(&game_id, &0, &1).get(0).unwrap();
+        assert_eq!((first.actor, first.kind), (player2, ActionKind::Join));
+    }
 
-        // Player 2 reports hit
-        let empty_proof = Bytes::new(&env);
-        client.report_result(&game_id, &player2, &true, &empty_proof);
+    #[test]
+    fn test_dry_run_validators() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let home = FleetGrid::Home;
+        assert_eq!(
+            client.try_can_take_shot(&game_id, &player1, &0, &0, &home),
+            Err(Ok(Error::NotInProgress))
+        );
+        assert_eq!(
+            client.try_can_take_shot(&99, &player1, &0, &0, &home),
+            Err(Ok(Error::GameNotFound))
+        );
+
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(
+            client.try_can_commit_board(&game_id, &player1),
+            Err(Ok(Error::BoardAlreadyCommitted))
+        );
+        client.can_commit_board(&game_id, &player2);
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+
+        client.can_take_shot(&game_id, &player1, &9, &9, &home);
+        assert_eq!(
+            client.try_can_take_shot(&game_id, &player2, &0, &0, &home),
+            Err(Ok(Error::NotYourTurn))
+        );
+        assert_eq!(
+            client.try_can_take_shot(&game_id, &player1, &10, &0, &home),
+            Err(Ok(Error::ShotOutOfBounds))
+        );
+        assert_eq!(
+            client.try_can_use_sonar(&game_id, &player1, &5, &5),
+            Err(Ok(Error::SonarNotAvailable))
+        );
+        assert_eq!(
+            client.try_can_claim_victory(&game_id, &player1),
+            Err(Ok(Error::NotEnoughHits))
+        );
+
+        client.take_shot(&game_id, &player1, &9, &9, &home, &None);
+        assert_eq!(
+            client.try_can_report_result(&game_id, &player1),
+            Err(Ok(Error::WrongPlayerReporting))
+        );
+        client.can_report_result(&game_id, &player2);
+    }
+
+    #[test]
+    fn test_cancel_game() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+
+        let game_id = client.new_game(&player1, &None);
+        client.cancel_game(&game_id, &player1);
         let game = client.get_game(&game_id);
-        assert_eq!(game.p1_hits, 1);
-        assert_eq!(game.turn, 2); // Now player 2's turn
-        assert!(!game.awaiting_report);
+        assert_eq!(game.phase, Phase::Cancelled);
+        assert_eq!(game.status, 3);
+        assert_eq!(game.winner, None);
+        assert_eq!(game.end_reason, EndReason::Cancelled);
+    }
 
-        // Player 2 shoots
-        client.take_shot(&game_id, &player2, &5, &6);
+    #[test]
+    fn test_hub_end_game() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // Player 1 reports miss
-        client.report_result(&game_id, &player1, &false, &empty_proof);
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHub, ());
+        let hub_client = MockHubClient::new(&env, &hub);
+        client.initialize(&hub, &Address::generate(&env));
+
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(hub_client.session(&game_id), Some(false));
+        // A hub without the probe is legacy
+        assert_eq!(client.get_hub_interface_version(), 1);
+
+        client.hub_end_game(&game_id, &Winner::Player2);
+        assert_eq!(env.auths()[0].0, hub);
         let game = client.get_game(&game_id);
-        assert_eq!(game.p2_hits, 0);
-        assert_eq!(game.turn, 1); // Back to player 1
+        assert_eq!(game.phase, Phase::Completed(Winner::Player2));
+        assert_eq!(game.winner, Some(player2));
+        assert_eq!(game.end_reason, EndReason::HubSettled);
+        // The hub settled it, so it is not called back
+        assert_eq!(hub_client.session(&game_id), Some(false));
     }
 
     #[test]
-    fn test_full_game_to_victory() {
+    fn test_hub_v2_start_info() {
         let env = Env::default();
         env.mock_all_auths();
 
         let (contract_id, player1, player2, game_id) = setup_game(&env);
         let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHubV2, ());
+        let hub_client = MockHubV2Client::new(&env, &hub);
+        client.initialize(&hub, &Address::generate(&env));
+        // Probed from the hub
+        assert_eq!(client.get_hub_interface_version(), 2);
+        assert!(client.try_set_hub_interface_version(&4).is_err());
+        client.set_hub_interface_version(&1);
+        assert_eq!(client.get_hub_interface_version(), 1);
+        assert_eq!(client.refresh_hub_interface_version(), 2);
 
-        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
-        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
-        client.commit_board(&game_id, &player1, &hash1);
-        client.commit_board(&game_id, &player2, &hash2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
 
-        // Simulate 17 hits by player 1 (all ships sunk)
-        let empty_proof = Bytes::new(&env);
+        let info = hub_client.start_info(&game_id).unwrap();
+        let game = client.get_game(&game_id);
+        assert_eq!(info.config_digest, game.rules_digest);
+        assert_eq!(game.rules_digest, game.config.digest(&env));
+        assert_eq!(info.first_mover, Some(player1));
+    }
+
+    #[test]
+    fn test_hub_outcomes_v3() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHubV3, ());
+        let hub_client = MockHubV3Client::new(&env, &hub);
+        let admin = Address::generate(&env);
+        client.initialize(&hub, &admin);
+        assert_eq!(client.get_hub_interface_version(), 3);
+
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let start = |game_id: u32| {
+            client.join_game(&game_id, &player2);
+            client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+            client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+            client.get_game(&game_id).session_id
+        };
+
+        // A void by the admin reaches the hub
+        let voided = client.new_game(&player1, &None);
+        let session_id = start(voided);
+        let reason = soroban_sdk::String::from_str(&env, "collusion");
+        client.void_game(&admin, &voided, &reason);
+        assert_eq!(hub_client.outcome(&session_id), Some(HubOutcome::Voided));
+
+        // Wins are reported as outcomes too
+        let won = client.new_game(&player1, &None);
+        let session_id = start(won);
+        let proof = Bytes::new(&env);
         for i in 0..17u32 {
-            // Player 1 shoots
-            client.take_shot(&game_id, &player1, &(i % 10), &(i / 10));
-            // Player 2 reports hit
-            client.report_result(&game_id, &player2, &true, &empty_proof);
+            client.take_shot(&won, &player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&won, &player2, &true, &proof, &None);
+            client.take_shot(&won, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&won, &player1, &false, &proof, &None);
+        }
+        client.claim_victory(&won, &player1);
+        assert_eq!(hub_client.outcome(&session_id), Some(HubOutcome::Player1Won));
+    }
 
-            // Player 2 shoots (misses)
-            client.take_shot(&game_id, &player2, &9, &9);
-            // Player 1 reports miss
-            client.report_result(&game_id, &player1, &false, &empty_proof);
+    #[test]
+    fn test_open_game_cap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        client.set_creation_limits(&CreationLimits { max_open_games: 2, min_ledger_gap: 0 });
+        let player1 = Address::generate(&env);
+
+        let first = client.new_game(&player1, &None);
+        let second = client.new_game(&player1, &None);
+        assert_eq!(client.open_games(&player1), 2);
+        assert!(client.try_new_game(&player1, &None).is_err());
+
+        // Joined and cancelled games free their slots
+        client.join_game(&first, &Address::generate(&env));
+        client.cancel_game(&second, &player1);
+        assert_eq!(client.open_games(&player1), 0);
+        client.new_game(&player1, &None);
+        client.new_game(&player1, &None);
+    }
+
+    #[test]
+    fn test_creation_ledger_gap_and_exemption() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        client.set_creation_limits(&CreationLimits { max_open_games: 5, min_ledger_gap: 10 });
+        let player1 = Address::generate(&env);
+
+        client.new_game(&player1, &None);
+        assert!(client.try_new_game(&player1, &None).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 10);
+        client.new_game(&player1, &None);
+
+        client.set_rate_limit_exempt(&player1, &true);
+        client.new_game(&player1, &None);
+        client.new_game(&player1, &None);
+    }
+
+    #[test]
+    fn test_listing_deposit_refunded_or_forfeited() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let balance = soroban_sdk::token::Client::new(&env, &token);
+        client.set_listing_deposit(&ListingDeposit { token: token.clone(), amount: 10 });
+
+        let player1 = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&player1, &20);
+
+        // Joined: deposit comes back
+        let joined = client.new_game(&player1, &None);
+        client.list_game(&joined, &player1);
+        assert_eq!(client.open_game_index(), soroban_sdk::vec![&env, joined]);
+        assert_eq!(balance.balance(&player1), 10);
+        let summary = client.lobby_games(&0, &10).get(0).unwrap();
+        assert_eq!((summary.id, summary.player2), (joined, None));
+        client.join_game(&joined, &Address::generate(&env));
+        assert_eq!(balance.balance(&player1), 20);
+        assert_eq!(client.open_game_index().len(), 0);
+
+        // Left to go stale: anyone can expire it and the deposit is kept
+        let stale = client.new_game(&player1, &None);
+        client.list_game(&stale, &player1);
+        let act_by = env.ledger().sequence() + lobby::LISTING_TTL_LEDGERS - 1;
+        assert_eq!(client.deadlines(&stale), Deadlines { act_by, ..Default::default() });
+        assert!(client.try_expire_listing(&stale).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += lobby::LISTING_TTL_LEDGERS);
+        client.expire_listing(&stale);
+        assert_eq!(client.get_game(&stale).phase, Phase::Cancelled);
+        assert_eq!(client.get_listing(&stale), None);
+        assert_eq!(balance.balance(&player1), 10);
+        assert_eq!(balance.balance(&contract_id), 10);
+    }
+
+    #[test]
+    fn test_gc_removes_dead_games_for_bounty() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        client.set_listing_deposit(&ListingDeposit { token: token.clone(), amount: 50 });
+        let player1 = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&player1, &50);
+
+        let stale = client.new_game(&player1, &None);
+        client.list_game(&stale, &player1);
+        let cancelled = client.new_game(&player1, &None);
+        client.cancel_game(&cancelled, &player1);
+        let live = client.new_game(&player1, &None);
+        env.ledger().with_mut(|l| l.sequence_number += lobby::LISTING_TTL_LEDGERS);
+
+        let sweeper = Address::generate(&env);
+        let removed = client.gc(&sweeper, &soroban_sdk::vec![&env, stale, cancelled, live]);
+        assert_eq!(removed, 2);
+        assert!(client.try_get_game(&stale).is_err());
+        assert!(client.try_get_game(&cancelled).is_err());
+        assert_eq!(client.get_game(&live).phase, Phase::Setup);
+        assert_eq!(client.open_game_index().len(), 0);
+        // 50 / 10 for the expired listing only; the unlisted cancelled game paid nothing in
+        let balance = soroban_sdk::token::Client::new(&env, &token);
+        assert_eq!(balance.balance(&sweeper), 5);
+        assert_eq!(balance.balance(&contract_id), 45);
+
+        // Nothing keyed by a removed game survives it
+        let stale_hash = game_hash::game_hash_for(&env, &contract_id, &player1, 0);
+        assert_eq!(client.game_hash(&stale), None);
+        assert_eq!(client.game_id_by_hash(&stale_hash), None);
+        assert!(client.try_get_game_by_hash(&stale_hash).is_err());
+        let created = env.as_contract(&contract_id, || {
+            (game_hash::created_with(&env, &player1, 0), game_hash::created_with(&env, &player1, 1))
+        });
+        assert_eq!(created, (None, None));
+        assert_eq!(client.action_count(&cancelled), 0);
+
+        // Cancelling unlisted games can't drain the treasury
+        let mut ids = soroban_sdk::Vec::new(&env);
+        for _ in 0..3 {
+            let game_id = client.new_game(&player1, &None);
+            client.cancel_game(&game_id, &player1);
+            ids.push_back(game_id);
         }
+        assert_eq!(client.gc(&sweeper, &ids), 3);
+        assert_eq!(balance.balance(&sweeper), 5);
+        assert_eq!(client.treasury_balance(&token), 45);
+    }
 
-        let game = client.get_game(&game_id);
-        assert_eq!(game.p1_hits, 17);
+    #[test]
+    fn test_treasury_withdrawals() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // Player 1 claims victory
-        client.claim_victory(&game_id, &player1);
-        let game = client.get_game(&game_id);
-        assert_eq!(game.status, 2);
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&Address::generate(&env), &admin);
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        client.set_listing_deposit(&ListingDeposit { token: token.clone(), amount: 50 });
+        let player1 = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&player1, &50);
+
+        // A forfeited deposit is itemized as it enters the treasury
+        let stale = client.new_game(&player1, &None);
+        client.list_game(&stale, &player1);
+        env.ledger().with_mut(|l| l.sequence_number += lobby::LISTING_TTL_LEDGERS);
+        client.expire_listing(&stale);
+        let inflow = TreasuryInflow {
+            token: token.clone(),
+            source: Inflow::ForfeitedDeposit,
+            amount: 50,
+        };
+        assert!(env.events().all().events().contains(&inflow.to_xdr(&env, &contract_id)));
+        assert_eq!(client.treasury_balance(&token), 50);
+
+        let to = Address::generate(&env);
+        assert!(client.try_request_withdrawal(&token, &to, &60).is_err());
+        let cancelled = client.request_withdrawal(&token, &to, &20);
+        assert_eq!(env.auths()[0].0, admin);
+        client.cancel_withdrawal(&cancelled);
+        assert_eq!(client.pending_withdrawal(&cancelled), None);
+
+        // Withdrawals wait out the delay, in full view
+        let withdrawal_id = client.request_withdrawal(&token, &to, &30);
+        let pending = client.pending_withdrawal(&withdrawal_id).unwrap();
+        let executable_ledger = env.ledger().sequence() + WITHDRAWAL_DELAY_LEDGERS;
+        assert_eq!(pending.executable_ledger, executable_ledger);
+        assert!(client.try_execute_withdrawal(&withdrawal_id).is_err());
+        env.ledger().with_mut(|l| l.sequence_number = executable_ledger);
+        client.execute_withdrawal(&withdrawal_id);
+        assert_eq!(soroban_sdk::token::Client::new(&env, &token).balance(&to), 30);
+        assert_eq!(client.treasury_balance(&token), 20);
+        assert!(client.try_execute_withdrawal(&withdrawal_id).is_err());
     }
 
     #[test]
-    #[should_panic(expected = "not your turn")]
-    fn test_wrong_turn() {
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "withdrawal not found"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #47)"))]
+    fn test_execute_unknown_withdrawal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        client.execute_withdrawal(&1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "game not open"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #39)"))]
+    fn test_list_joined_game() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, _, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.list_game(&game_id, &player1);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "no relayer configured"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #61)"))]
+    fn test_relay_without_relayer() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, _, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let commit = SignedAction::CommitBoard(BytesN::from_array(&env, &[1u8; 32]));
+        let signature = BytesN::from_array(&env, &[0u8; 64]);
+        client.relay_action(&player1, &game_id, &commit, &signature);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "waiting for sonar report"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #13)"))]
+    fn test_shot_while_sonar_pending() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -560,14 +2429,14 @@ mod test {
         let hash2 = BytesN::from_array(&env, &[2u8; 32]);
         client.commit_board(&game_id, &player1, &hash1);
         client.commit_board(&game_id, &player2, &hash2);
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
 
-        // Player 2 tries to shoot on player 1's turn
-        client.take_shot(&game_id, &player2, &0, &0);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
     }
 
     #[test]
-    #[should_panic(expected = "not enough hits")]
-    fn test_premature_victory_claim() {
+    fn test_commit_boards() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -576,33 +2445,40 @@ mod test {
 
         let hash1 = BytesN::from_array(&env, &[1u8; 32]);
         let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+
         client.commit_board(&game_id, &player1, &hash1);
-        client.commit_board(&game_id, &player2, &hash2);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.boards_committed, 1);
+        assert_eq!(game.status, 0);
 
-        // Try to claim victory with 0 hits
-        client.claim_victory(&game_id, &player1);
+        // Second board commit - no hub set, so it just updates status
+        client.commit_board(&game_id, &player2, &hash2);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.boards_committed, 2);
+        assert_eq!(game.status, 1);
     }
 
-    fn start_game_and_play_turns(
-        env: &Env,
-        client: &BattleshipContractClient,
-        game_id: &u32,
-        player1: &Address,
-        player2: &Address,
-        turns: u32,
-    ) {
-        let empty_proof = Bytes::new(env);
-        // Play `turns` rounds (each round = p1 shoots + p2 shoots)
-        for i in 0..turns {
-            client.take_shot(game_id, player1, &(i % 10), &(i / 10));
-            client.report_result(game_id, player2, &false, &empty_proof);
-            client.take_shot(game_id, player2, &(i % 10), &(i / 10));
-            client.report_result(game_id, player1, &false, &empty_proof);
-        }
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "board hash is zero"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #33)"))]
+    fn test_commit_rejects_zero_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, _player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        // The zero hash is what an uncommitted board holds
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[0u8; 32]));
     }
 
     #[test]
-    fn test_sonar_available_after_3_turns() {
+    #[cfg_attr(
+        feature = "panic-messages",
+        should_panic(expected = "board hash matches the opponent's")
+    )]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #34)"))]
+    fn test_commit_rejects_opponents_hash() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -610,22 +2486,81 @@ mod test {
         let client = BattleshipContractClient::new(&env, &contract_id);
 
         let hash1 = BytesN::from_array(&env, &[1u8; 32]);
-        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
         client.commit_board(&game_id, &player1, &hash1);
-        client.commit_board(&game_id, &player2, &hash2);
+        client.commit_board(&game_id, &player2, &hash1);
+    }
 
-        // Sonar not available at turn 0
-        assert!(!client.sonar_available(&game_id, &player1));
+    #[test]
+    fn test_creator_commits_before_join() {
+        let env = Env::default();
+        env.mock_all_auths();
 
-        // Play 3 rounds
-        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
 
-        // Now it's player 1's turn with 3 turns taken → sonar available
-        assert!(client.sonar_available(&game_id, &player1));
+        // The order the frontend uses: create, commit, then the opponent joins and commits
+        let game_id = client.new_game(&player1, &None);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::Setup);
+        assert_eq!(
+            client.try_can_commit_board(&game_id, &player2),
+            Err(Ok(Error::OpponentNotJoined))
+        );
+        assert_eq!(
+            client.try_can_commit_board(&game_id, &player1),
+            Err(Ok(Error::BoardAlreadyCommitted))
+        );
+
+        client.join_game(&game_id, &player2);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingCommit);
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::AwaitingShot);
+        assert_eq!(game.board_hash1, BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(game.board_hash2, BytesN::from_array(&env, &[2u8; 32]));
     }
 
     #[test]
-    fn test_sonar_full_flow() {
+    fn test_hunt_fleet_commit_waits_for_hunter() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let fleet = Address::generate(&env);
+        let hunter = Address::generate(&env);
+        let config = GameConfig {
+            mode: GameMode::Hunt(HuntRules {
+                hunter_is_player1: false,
+                shots_per_turn: 2,
+                sonar_recharge_turns: 1,
+                turn_limit: 3,
+                fleet_cells: 2,
+            }),
+            ..Default::default()
+        };
+
+        // The fleet's commitment is the only one, but nobody is there to hunt it yet
+        let game_id = client.new_game_with_config(&fleet, &config);
+        client.commit_board(&game_id, &fleet, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::Setup);
+
+        client.join_game(&game_id, &hunter);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.player2, hunter);
+        assert_eq!(game.phase, Phase::AwaitingShot);
+        assert_eq!(game.turn, 2);
+        assert_eq!(
+            client.try_can_commit_board(&game_id, &hunter),
+            Err(Ok(Error::NotInSetup))
+        );
+    }
+
+    #[test]
+    fn test_take_shot_and_report() {
         let env = Env::default();
         env.mock_all_auths();
 
@@ -637,28 +2572,3092 @@ mod test {
         client.commit_board(&game_id, &player1, &hash1);
         client.commit_board(&game_id, &player2, &hash2);
 
-        // Play 3 rounds so p1 has 3 turns
-        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
-
-        // Player 1 uses sonar
-        client.use_sonar(&game_id, &player1, &5, &5);
+        // Player 1 shoots
+        client.take_shot(&game_id, &player1, &3, &4, &FleetGrid::Home, &None);
         let game = client.get_game(&game_id);
-        assert!(game.awaiting_sonar);
-        assert_eq!(game.sonar_center_x, 5);
-        assert_eq!(game.sonar_center_y, 5);
+        assert_eq!(game.last_shot_x, 3);
+        assert_eq!(game.last_shot_y, 4);
+        assert_eq!(game.phase, Phase::AwaitingReport);
 
-        // Player 2 reports sonar count
+        // Player 2 reports hit
         let empty_proof = Bytes::new(&env);
-        client.report_sonar(&game_id, &player2, &3, &empty_proof);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
         let game = client.get_game(&game_id);
-        assert!(!game.awaiting_sonar);
-        assert_eq!(game.last_sonar_count, 3);
-        assert_eq!(game.turn, 2); // Turn swapped to player 2
-        assert!(game.p1_sonar_used); // Can't use again
+        assert_eq!(game.p1_hits, 1);
+        assert_eq!(game.turn, 2); // Now player 2's turn
+        assert_eq!(game.phase, Phase::AwaitingShot);
+
+        // Player 2 shoots
+        client.take_shot(&game_id, &player2, &5, &6, &FleetGrid::Home, &None);
+
+        // Player 1 reports miss
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p2_hits, 0);
+        assert_eq!(game.turn, 1); // Back to player 1
+
+        assert_eq!(game.p1_shots, 1);
+        assert_eq!(game.p2_shots, 1);
+        assert_eq!(client.accuracy(&game_id, &player1), 10_000);
+        assert_eq!(client.accuracy(&game_id, &player2), 0);
+    }
+
+    /// Run `call` authorized only by `wrong` and expect it to fail, then only by
+    /// `right` and expect it to succeed with exactly the `fn_name(args)` auth entry.
+    fn assert_requires_auth(
+        env: &Env,
+        contract_id: &Address,
+        right: &Address,
+        wrong: &Address,
+        fn_name: &str,
+        args: soroban_sdk::Vec<soroban_sdk::Val>,
+        call: impl Fn() -> bool,
+    ) {
+        use soroban_sdk::testutils::{AuthorizedFunction, AuthorizedInvocation};
+        use soroban_sdk::testutils::{MockAuth, MockAuthInvoke};
+
+        for (address, ok) in [(wrong, false), (right, true)] {
+            let invoke = MockAuthInvoke {
+                contract: contract_id,
+                fn_name,
+                args: args.clone(),
+                sub_invokes: &[],
+            };
+            env.mock_auths(&[MockAuth { address, invoke: &invoke }]);
+            assert_eq!(call(), ok, "{fn_name} authorized by the wrong address: {}", !ok);
+        }
+        let function = (contract_id.clone(), soroban_sdk::Symbol::new(env, fn_name), args);
+        let expected = AuthorizedInvocation {
+            function: AuthorizedFunction::Contract(function),
+            sub_invocations: std::vec![],
+        };
+        assert_eq!(env.auths(), std::vec![(right.clone(), expected)]);
+    }
+
+    #[test]
+    fn test_entry_points_require_the_right_auth() {
+        use soroban_sdk::IntoVal;
+
+        let env = Env::default();
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+        let check = |right: &Address, wrong: &Address, fn_name, args, call: &dyn Fn() -> bool| {
+            assert_requires_auth(&env, &contract_id, right, wrong, fn_name, args, call)
+        };
+
+        let args = (player1.clone(), None::<u32>).into_val(&env);
+        let create = || client.try_new_game(&player1, &None).is_ok();
+        check(&player1, &player2, "new_game", args, &create);
+        let game_id = client.game_count();
+        let args = (game_id, player2.clone()).into_val(&env);
+        let join = || client.try_join_game(&game_id, &player2).is_ok();
+        check(&player2, &outsider, "join_game", args, &join);
+
+        for (player, byte) in [(&player1, 1u8), (&player2, 2u8)] {
+            let hash = BytesN::from_array(&env, &[byte; 32]);
+            let args = (game_id, player.clone(), hash.clone()).into_val(&env);
+            let commit = || client.try_commit_board(&game_id, player, &hash).is_ok();
+            check(player, &outsider, "commit_board", args, &commit);
+        }
+
+        let no_id: Option<BytesN<16>> = None;
+        let args = (game_id, player1.clone(), 4u32, 7u32, FleetGrid::Home, no_id.clone());
+        let shot = || client.try_take_shot(&game_id, &player1, &4, &7, &FleetGrid::Home, &None);
+        check(&player1, &player2, "take_shot", args.into_val(&env), &|| shot().is_ok());
+        let proof = Bytes::new(&env);
+        let args = (game_id, player2.clone(), false, proof.clone(), no_id).into_val(&env);
+        let report = || client.try_report_result(&game_id, &player2, &false, &proof, &None);
+        check(&player2, &player1, "report_result", args, &|| report().is_ok());
+
+        // Views restricted to a role authenticate the viewer too
+        let args = (game_id, player1.clone(), player1.clone()).into_val(&env);
+        let score = || client.try_player_score(&game_id, &player1, &player1).is_ok();
+        check(&player1, &player2, "player_score", args, &score);
+
+        let args = (game_id, outsider.clone()).into_val(&env);
+        let watch = || client.try_watch_game(&game_id, &outsider).is_ok();
+        check(&outsider, &player1, "watch_game", args, &watch);
+        let args = (240u32,).into_val(&env);
+        let set_max = || client.try_set_max_pause_ledgers(&240).is_ok();
+        check(&admin, &player1, "set_max_pause_ledgers", args, &set_max);
+
+        env.mock_all_auths();
+        let open_id = client.new_game(&player1, &None);
+        let args = (open_id, player1.clone()).into_val(&env);
+        let cancel = || client.try_cancel_game(&open_id, &player1).is_ok();
+        check(&player1, &player2, "cancel_game", args, &cancel);
+    }
+
+    /// Run `call` with no authorization at all and expect it to fail, then with every
+    /// auth mocked and expect it to succeed, having asked exactly `signers`, in order,
+    /// to authorize `fn_name`. Leaves every auth mocked for the setup that follows.
+    fn assert_signed_by(env: &Env, fn_name: &str, signers: &[&Address], call: &dyn Fn() -> bool) {
+        use soroban_sdk::testutils::AuthorizedFunction;
+
+        env.set_auths(&[]);
+        assert!(!call(), "{fn_name} ran without authorization");
+        env.mock_all_auths();
+        assert!(call(), "{fn_name} failed once authorized");
+        let auths = env.auths();
+        let signed: std::vec::Vec<&Address> = auths.iter().map(|(address, _)| address).collect();
+        assert_eq!(signed, signers, "{fn_name} asked the wrong addresses");
+        for (_, invocation) in &auths {
+            let AuthorizedFunction::Contract((_, name, _)) = &invocation.function else {
+                panic!("{fn_name} asked for a non-contract authorization");
+            };
+            assert_eq!(name, &soroban_sdk::Symbol::new(env, fn_name));
+        }
+    }
+
+    /// Entry points that ask for an authorization themselves, read from the contract
+    /// source so a new one cannot be left out of `test_every_entry_point_requires_auth`.
+    fn entry_points_requiring_auth() -> std::collections::BTreeSet<std::string::String> {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut names = std::collections::BTreeSet::new();
+        for file in std::fs::read_dir(src).unwrap() {
+            let source = std::fs::read_to_string(file.unwrap().path()).unwrap();
+            let mut current = None;
+            for line in source.lines().take_while(|line| !line.contains("#[cfg(test)]")) {
+                if let Some(rest) = line.strip_prefix("    pub fn ") {
+                    current = rest.split('(').next().map(std::string::String::from);
+                } else if line.starts_with("    fn ")
+                    || line.starts_with("    pub(crate) fn ")
+                    || !line.starts_with(' ')
+                {
+                    current = None;
+                }
+                let auth = ["require_auth()", "require_admin(&env)", "require_viewer("];
+                if auth.iter().any(|call| line.contains(call)) {
+                    names.extend(current.clone());
+                }
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn test_every_entry_point_requires_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let day = 20_000;
+        env.ledger().set_timestamp(day as u64 * 86_400 + 3_600);
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHub, ());
+        let admin = Address::generate(&env);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        client.initialize(&hub, &admin);
+        let checked = core::cell::RefCell::new(std::collections::BTreeSet::new());
+        let check = |fn_name: &'static str, signers: &[&Address], call: &dyn Fn() -> bool| {
+            checked.borrow_mut().insert(fn_name);
+            assert_signed_by(&env, fn_name, signers, call);
+        };
+        let hash = |byte| BytesN::from_array(&env, &[byte; 32]);
+        let proof = Bytes::new(&env);
+        let paired = |config: &GameConfig| {
+            let game_id = client.new_game_with_config(&player1, config);
+            client.join_game(&game_id, &player2);
+            game_id
+        };
+        let started = |config: &GameConfig| {
+            let game_id = paired(config);
+            client.commit_board(&game_id, &player1, &hash(1));
+            client.commit_board(&game_id, &player2, &hash(2));
+            game_id
+        };
+        let classic = GameConfig::default();
+
+        // Admin settings
+        let limits = CreationLimits { max_open_games: 100, min_ledger_gap: 0 };
+        check("set_creation_limits", &[&admin], &|| {
+            client.try_set_creation_limits(&limits).is_ok()
+        });
+        check("set_rate_limit_exempt", &[&admin], &|| {
+            client.try_set_rate_limit_exempt(&player1, &true).is_ok()
+        });
+        check("set_max_pause_ledgers", &[&admin], &|| {
+            client.try_set_max_pause_ledgers(&240).is_ok()
+        });
+        check("set_default_config", &[&admin], &|| {
+            client.try_set_default_config(&classic).is_ok()
+        });
+        check("set_disabled_features", &[&admin], &|| {
+            client.try_set_disabled_features(&0).is_ok()
+        });
+        let version = client.get_hub_interface_version();
+        check("set_hub_interface_version", &[&admin], &|| {
+            client.try_set_hub_interface_version(&version).is_ok()
+        });
+        check("set_pause_level", &[&admin], &|| client.try_set_pause_level(&0).is_ok());
+        check("post_upgrade_check", &[&admin], &|| client.try_post_upgrade_check().is_ok());
+        let policy = Some(StrikePolicy { max_strikes: 3, ban_ledgers: 100 });
+        check("set_strike_policy", &[&admin], &|| {
+            client.try_set_strike_policy(&policy).is_ok()
+        });
+        let action = AdminAction::MaxPauseLedgers(240);
+        check("propose_admin_action", &[&admin], &|| {
+            client.try_propose_admin_action(&action).is_ok()
+        });
+        let action_id = client.propose_admin_action(&action);
+        check("execute_admin_action", &[&admin], &|| {
+            client.try_execute_admin_action(&action_id).is_ok()
+        });
+        let action_id = client.propose_admin_action(&action);
+        check("cancel_admin_action", &[&admin], &|| {
+            client.try_cancel_admin_action(&action_id).is_ok()
+        });
+        let keeper = Address::generate(&env);
+        check("register_keeper", &[&keeper], &|| client.try_register_keeper(&keeper).is_ok());
+        check("set_away", &[&player1], &|| client.try_set_away(&player1, &None).is_ok());
+
+        // Game lifecycle
+        check("new_game", &[&player1], &|| client.try_new_game(&player1, &None).is_ok());
+        check("new_game_with_config", &[&player1], &|| {
+            client.try_new_game_with_config(&player1, &classic).is_ok()
+        });
+        check("new_tutorial_game", &[&player1], &|| {
+            client.try_new_tutorial_game(&player1).is_ok()
+        });
+        let open = client.new_game(&player1, &None);
+        check("cancel_game", &[&player1], &|| client.try_cancel_game(&open, &player1).is_ok());
+        let game_id = client.new_game(&player1, &None);
+        check("join_game", &[&player2], &|| client.try_join_game(&game_id, &player2).is_ok());
+        for (player, byte) in [(&player1, 1u8), (&player2, 2u8)] {
+            check("commit_board", &[player], &|| {
+                client.try_commit_board(&game_id, player, &hash(byte)).is_ok()
+            });
+        }
+        check("watch_game", &[&outsider], &|| {
+            client.try_watch_game(&game_id, &outsider).is_ok()
+        });
+        check("unwatch_game", &[&outsider], &|| {
+            client.try_unwatch_game(&game_id, &outsider).is_ok()
+        });
+        let coach = Address::generate(&env);
+        check("set_coach", &[&player2], &|| {
+            client.try_set_coach(&game_id, &player2, &Some(coach.clone())).is_ok()
+        });
+        let emote = soroban_sdk::Symbol::new(&env, "salute");
+        check("coach_emote", &[&coach], &|| {
+            client.try_coach_emote(&game_id, &coach, &player2, &emote).is_ok()
+        });
+        check("take_shot", &[&player1], &|| {
+            client.try_take_shot(&game_id, &player1, &4, &7, &FleetGrid::Home, &None).is_ok()
+        });
+        check("incoming_shot", &[&coach], &|| {
+            client.try_incoming_shot(&game_id, &player2, &coach).is_ok()
+        });
+        check("report_result", &[&player2], &|| {
+            client.try_report_result(&game_id, &player2, &true, &proof, &None).is_ok()
+        });
+        check("player_score", &[&player1], &|| {
+            client.try_player_score(&game_id, &player1, &player1).is_ok()
+        });
+        check("request_pause", &[&player2], &|| {
+            client.try_request_pause(&game_id, &player2).is_ok()
+        });
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        check("accept_pause", &[&player1], &|| {
+            client.try_accept_pause(&game_id, &player1).is_ok()
+        });
+        check("resume", &[&player2], &|| client.try_resume(&game_id, &player2).is_ok());
+        client.take_shot(&game_id, &player2, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        check("use_sonar", &[&player1], &|| {
+            client.try_use_sonar(&game_id, &player1, &5, &5, &None).is_ok()
+        });
+        check("report_sonar", &[&player2], &|| {
+            client.try_report_sonar(&game_id, &player2, &2, &proof, &None).is_ok()
+        });
+        check("void_game", &[&admin], &|| {
+            let reason = soroban_sdk::String::from_str(&env, "abandoned");
+            client.try_void_game(&admin, &game_id, &reason).is_ok()
+        });
+
+        let game_id = started(&classic);
+        check("hub_end_game", &[&hub], &|| {
+            client.try_hub_end_game(&game_id, &Winner::Player2).is_ok()
+        });
+
+        // Victory, and what follows from it
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&player1, &100);
+        check("fund_quest_pool", &[&player1], &|| {
+            client.try_fund_quest_pool(&player1, &token, &10).is_ok()
+        });
+        let ends_at = env.ledger().timestamp() + 86_400;
+        let quest_id = client.create_quest(&QuestGoal::Wins, &1, &token, &10, &ends_at);
+        check("create_quest", &[&admin], &|| {
+            client.try_create_quest(&QuestGoal::Wins, &1, &token, &10, &ends_at).is_ok()
+        });
+        let game_id = started(&classic);
+        for i in 0..17u32 {
+            client.take_shot(&game_id, &player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player2, &true, &proof, &None);
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &proof, &None);
+        }
+        check("claim_victory", &[&player1], &|| {
+            client.try_claim_victory(&game_id, &player1).is_ok()
+        });
+        check("claim_quest_reward", &[&player1], &|| {
+            client.try_claim_quest_reward(&quest_id, &player1).is_ok()
+        });
+        check("emit_outcome_event", &[&keeper], &|| {
+            client.try_emit_outcome_event(&keeper, &game_id).is_ok()
+        });
+        check("flag_game", &[&admin], &|| client.try_flag_game(&game_id).is_ok());
+        check("record_strike", &[&admin], &|| {
+            client.try_record_strike(&game_id, &player1).is_ok()
+        });
+        check("appeal_strike", &[&player1], &|| {
+            client.try_appeal_strike(&player1, &game_id, &hash(7)).is_ok()
+        });
+        check("decide_appeal", &[&admin], &|| {
+            client.try_decide_appeal(&player1, &game_id, &true).is_ok()
+        });
+
+        // Game modes
+        let armada = GameConfig { mode: GameMode::Armada, ..Default::default() };
+        let game_id = started(&armada);
+        for (player, byte) in [(&player1, 3u8), (&player2, 4u8)] {
+            check("commit_forward_board", &[player], &|| {
+                client.try_commit_forward_board(&game_id, player, &hash(byte)).is_ok()
+            });
+        }
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        check("report_carrier_sunk", &[&player2], &|| {
+            client.try_report_carrier_sunk(&game_id, &player2, &proof).is_ok()
+        });
+
+        let flagship = GameConfig { mode: GameMode::Flagship, ..Default::default() };
+        let game_id = started(&flagship);
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        check("report_flagship_sunk", &[&player2], &|| {
+            client.try_report_flagship_sunk(&game_id, &player2, &proof).is_ok()
+        });
+
+        let rules = ConvoyRules { cells: 3, points_to_win: 2 };
+        let game_id = started(&GameConfig { mode: GameMode::Convoy(rules), ..Default::default() });
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        check("report_convoy_hit", &[&player2], &|| {
+            client.try_report_convoy_hit(&game_id, &player2, &proof, &None).is_ok()
+        });
+
+        let game_id = started(&classic);
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        client.take_shot(&game_id, &player1, &0, &1, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        let cell = |x, y| Coord { x, y, grid: FleetGrid::Home };
+        let destroyer = soroban_sdk::vec![&env, cell(0, 0), cell(0, 1)];
+        check("report_ship_sunk", &[&player2], &|| {
+            client.try_report_ship_sunk(&game_id, &player2, &destroyer, &proof).is_ok()
+        });
+
+        let game_id = started(&GameConfig { diving_submarine: true, ..Default::default() });
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &false, &proof, &None);
+        check("submerge", &[&player2], &|| client.try_submerge(&game_id, &player2).is_ok());
+
+        let game_id = paired(&GameConfig { loadouts: true, ..Default::default() });
+        let sonar_and_scout = Vec::from_array(&env, [Ability::Sonar, Ability::Scout]);
+        check("commit_board_with_loadout", &[&player1], &|| {
+            client
+                .try_commit_board_with_loadout(&game_id, &player1, &hash(1), &sonar_and_scout)
+                .is_ok()
+        });
+        let line_and_scramble = Vec::from_array(&env, [Ability::LineSonar, Ability::Scramble]);
+        client.commit_board_with_loadout(&game_id, &player2, &hash(2), &line_and_scramble);
+        check("use_scout", &[&player1], &|| {
+            client.try_use_scout(&game_id, &player1, &2, &3, &None).is_ok()
+        });
+        client.report_sonar(&game_id, &player2, &1, &proof, &None);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        check("scramble_sonar", &[&player2], &|| {
+            client.try_scramble_sonar(&game_id, &player2).is_ok()
+        });
+        check("use_line_sonar", &[&player2], &|| {
+            client.try_use_line_sonar(&game_id, &player2, &4, &None).is_ok()
+        });
+
+        let game_id = started(&GameConfig { sonar_commit_window: Some(5), ..Default::default() });
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        let salt = hash(9);
+        let commitment = sonar_answer_hash(&env, 2, &salt);
+        check("commit_sonar", &[&player2], &|| {
+            client.try_commit_sonar(&game_id, &player2, &commitment).is_ok()
+        });
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        check("reveal_sonar", &[&player2], &|| {
+            client.try_reveal_sonar(&game_id, &player2, &2, &salt, &proof).is_ok()
+        });
+        client.use_sonar(&game_id, &player2, &5, &5, &None);
+        env.ledger().with_mut(|l| l.sequence_number += 6);
+        check("claim_sonar_timeout", &[&player2], &|| {
+            client.try_claim_sonar_timeout(&game_id, &player2).is_ok()
+        });
+
+        // Scheduled matches
+        let start = env.ledger().sequence() + 100;
+        check("propose_match", &[&player1], &|| {
+            client.try_propose_match(&player1, &player2, &start, &classic).is_ok()
+        });
+        let game_id = client.propose_match(&player1, &player2, &start, &classic);
+        check("confirm_match", &[&player2], &|| {
+            client.try_confirm_match(&game_id, &player2).is_ok()
+        });
+        let game_id = client.propose_match(&player1, &player2, &start, &classic);
+        check("withdraw_match", &[&player2], &|| {
+            client.try_withdraw_match(&game_id, &player2).is_ok()
+        });
+
+        // Clubs
+        let home_admin = Address::generate(&env);
+        let away_admin = Address::generate(&env);
+        let name = soroban_sdk::String::from_str(&env, "Harbor");
+        check("create_club", &[&home_admin], &|| {
+            client.try_create_club(&name, &home_admin).is_ok()
+        });
+        let home = client.create_club(&name, &home_admin);
+        let away = client.create_club(&name, &away_admin);
+        check("add_club_member", &[&home_admin], &|| {
+            client.try_add_club_member(&home, &player1).is_ok()
+        });
+        for member in [&player2, &outsider] {
+            client.add_club_member(&home, member);
+        }
+        check("remove_club_member", &[&outsider], &|| {
+            client.try_remove_club_member(&home, &outsider, &outsider).is_ok()
+        });
+        let game_id = paired(&classic);
+        check("tag_club_game", &[&player1], &|| {
+            client.try_tag_club_game(&game_id, &player1, &home).is_ok()
+        });
+        check("create_club_match", &[&home_admin, &away_admin], &|| {
+            client.try_create_club_match(&home, &away, &1).is_ok()
+        });
+        let match_id = client.create_club_match(&home, &away, &1);
+        let game_id = client.new_game(&player1, &None);
+        check("enter_match_game", &[&player1], &|| {
+            client.try_enter_match_game(&match_id, &game_id, &player1).is_ok()
+        });
+
+        // Daily challenge
+        let oracle = Address::generate(&env);
+        check("post_challenge", &[&admin], &|| {
+            client.try_post_challenge(&day, &hash(9), &20, &oracle).is_ok()
+        });
+        check("challenge_shot", &[&player1], &|| {
+            client.try_challenge_shot(&player1, &0, &0).is_ok()
+        });
+        check("report_challenge_shot", &[&oracle], &|| {
+            client.try_report_challenge_shot(&day, &player1, &false).is_ok()
+        });
+
+        // Relayed actions
+        let key = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let public = Some(BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+        check("set_gameplay_key", &[&player1], &|| {
+            client.try_set_gameplay_key(&player1, &public).is_ok()
+        });
+        let relayer = Some(Address::generate(&env));
+        check("set_relayer", &[&admin], &|| client.try_set_relayer(&relayer).is_ok());
+        let game_id = paired(&classic);
+        let commit = SignedAction::CommitBoard(hash(1));
+        let sig = sign_action(&env, &contract_id, &key, game_id, 0, commit.clone());
+        check("relay_action", &[relayer.as_ref().unwrap()], &|| {
+            client.try_relay_action(&player1, &game_id, &commit, &sig).is_ok()
+        });
+
+        // Deposits, keepers and the treasury
+        let deposit = ListingDeposit { token: token.clone(), amount: 50 };
+        check("set_listing_deposit", &[&admin], &|| {
+            client.try_set_listing_deposit(&deposit).is_ok()
+        });
+        let game_id = client.new_game(&player1, &None);
+        check("list_game", &[&player1], &|| client.try_list_game(&game_id, &player1).is_ok());
+        env.ledger().with_mut(|l| l.sequence_number += lobby::LISTING_TTL_LEDGERS);
+        let ids = soroban_sdk::vec![&env, game_id];
+        check("sweep_timeouts", &[&keeper], &|| {
+            client.try_sweep_timeouts(&keeper, &ids).is_ok()
+        });
+        check("gc", &[&keeper], &|| client.try_gc(&keeper, &ids).is_ok());
+        check("request_withdrawal", &[&admin], &|| {
+            client.try_request_withdrawal(&token, &outsider, &10).is_ok()
+        });
+        let withdrawal_id = client.request_withdrawal(&token, &outsider, &10);
+        check("cancel_withdrawal", &[&admin], &|| {
+            client.try_cancel_withdrawal(&withdrawal_id).is_ok()
+        });
+        let withdrawal_id = client.request_withdrawal(&token, &outsider, &10);
+        env.ledger().with_mut(|l| l.sequence_number += WITHDRAWAL_DELAY_LEDGERS);
+        check("execute_withdrawal", &[&admin], &|| {
+            client.try_execute_withdrawal(&withdrawal_id).is_ok()
+        });
+
+        let checked = checked.into_inner();
+        for name in entry_points_requiring_auth() {
+            assert!(checked.contains(name.as_str()), "{name} missing from this test");
+        }
+    }
+
+    /// Resources of the last call must stay within (instructions, memory bytes,
+    /// ledger entries written). The limits leave about 40% headroom over current
+    /// costs; raise them deliberately when a change is meant to cost more.
+    fn assert_cost_within(env: &Env, call: &str, limits: (i64, i64, u32)) {
+        let used = env.cost_estimate().resources();
+        let used = (used.instructions, used.mem_bytes, used.write_entries);
+        assert!(
+            used.0 <= limits.0 && used.1 <= limits.1 && used.2 <= limits.2,
+            "{call} cost {used:?}, over the {limits:?} budget"
+        );
+    }
+
+    #[test]
+    fn test_turn_costs() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_cost_within(&env, "commit_board", (500_000, 80_000, 4));
+        // The second commit starts the game
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_cost_within(&env, "commit_board", (500_000, 80_000, 4));
+
+        client.take_shot(&game_id, &player1, &4, &7, &FleetGrid::Home, &None);
+        assert_cost_within(&env, "take_shot", (500_000, 80_000, 4));
+        client.report_result(&game_id, &player2, &true, &Bytes::new(&env), &None);
+        assert_cost_within(&env, "report_result", (700_000, 110_000, 5));
+    }
+
+    #[test]
+    fn test_incoming_shot() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(client.incoming_shot(&game_id, &player2, &player2), None);
+
+        client.take_shot(&game_id, &player1, &4, &7, &FleetGrid::Home, &None);
+        let shot = Coord { x: 4, y: 7, grid: FleetGrid::Home };
+        assert_eq!(client.incoming_shot(&game_id, &player2, &player2), Some(shot));
+        assert_eq!(client.incoming_shot(&game_id, &player1, &player1), None);
+
+        client.report_result(&game_id, &player2, &false, &Bytes::new(&env), &None);
+        assert_eq!(client.incoming_shot(&game_id, &player2, &player2), None);
+    }
+
+    #[test]
+    fn test_coach() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let coach = Address::generate(&env);
+        assert!(client.try_set_coach(&game_id, &player2, &Some(player1.clone())).is_err());
+        client.set_coach(&game_id, &player2, &Some(coach.clone()));
+        assert_eq!(client.game_coach(&game_id, &player2), Some(coach.clone()));
+        let club_id = client.create_club(&soroban_sdk::String::from_str(&env, "Harbor"), &player1);
+        client.add_club_member(&club_id, &player2);
+        assert!(client.try_tag_club_game(&game_id, &player1, &club_id).is_err());
+        client.set_coach(&game_id, &player2, &None);
+        client.tag_club_game(&game_id, &player1, &club_id);
+        assert!(client.try_set_coach(&game_id, &player2, &Some(coach.clone())).is_err());
+        let game_id = client.new_game(&player1, &None);
+        client.join_game(&game_id, &player2);
+        client.set_coach(&game_id, &player2, &Some(coach.clone()));
+
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        client.take_shot(&game_id, &player1, &4, &7, &FleetGrid::Home, &None);
+        let shot = Coord { x: 4, y: 7, grid: FleetGrid::Home };
+        assert_eq!(client.incoming_shot(&game_id, &player2, &coach), Some(shot));
+        assert!(client.try_incoming_shot(&game_id, &player1, &coach).is_err());
+
+        // Advice only: the coach cannot answer for the player
+        let proof = Bytes::new(&env);
+        assert!(client.try_report_result(&game_id, &coach, &false, &proof, &None).is_err());
+        let emote = soroban_sdk::symbol_short!("careful");
+        client.coach_emote(&game_id, &coach, &player2, &emote);
+        let event = CoachEmote { game_id, player: player2.clone(), emote: emote.clone() };
+        assert_eq!(env.events().all(), std::vec![event.to_xdr(&env, &contract_id)]);
+        assert!(client.try_coach_emote(&game_id, &coach, &player1, &emote).is_err());
+    }
+
+    #[test]
+    fn test_hidden_score() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            hidden_score: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+        for i in 0..16u32 {
+            client.take_shot(&game_id, &player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+        assert_eq!(client.get_game(&game_id).p1_hits, 0);
+        assert!(client.try_hits_to_win(&game_id, &player1).is_err());
+        assert!(client.try_accuracy(&game_id, &player1).is_err());
+        let home = FleetGrid::Home;
+        assert!(client.try_public_heatmap(&game_id, &player1, &home).is_err());
+
+        // Nor can the score be rebuilt from the last report, the log or notices
+        let observer = Address::generate(&env);
+        client.watch_game(&game_id, &observer);
+        client.take_shot(&game_id, &player1, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        let notice = GameNotice {
+            observer,
+            game_id,
+            actor: player2.clone(),
+            kind: action_log::ActionKind::ReportHidden,
+        };
+        assert!(env.events().all().events().contains(&notice.to_xdr(&env, &contract_id)));
+        assert!(!client.get_game(&game_id).last_shot_hit);
+        let log = client.game_actions(&game_id, &0, &action_log::MAX_ACTIONS_PER_PAGE);
+        assert!(log.iter().all(|a| !matches!(a.kind, action_log::ActionKind::Report(_))));
+        assert!(log.iter().any(|a| a.kind == action_log::ActionKind::ReportHidden));
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+
+        // The player can still read their own score, with their authorization
+        let score = client.player_score(&game_id, &player1, &player1);
+        assert_eq!((score.hits, score.shots, score.hits_to_win), (17, 17, 0));
+        assert_eq!(env.auths()[0].0, player1);
+        assert!(client.try_player_score(&game_id, &player1, &player2).is_err());
+
+        // Tracked internally, so victory can still be claimed, and revealed afterwards
+        client.claim_victory(&game_id, &player1);
+        assert_eq!(client.get_game(&game_id).p1_hits, 17);
+        assert_eq!(client.accuracy(&game_id, &player1), 10_000);
+        let log = client.game_actions(&game_id, &0, &action_log::MAX_ACTIONS_PER_PAGE);
+        assert!(log.contains(&GameAction {
+            actor: player2,
+            kind: action_log::ActionKind::Report(true),
+            ledger: env.ledger().sequence(),
+        }));
+    }
+
+    fn sign_action(
+        env: &Env,
+        contract_id: &Address,
+        key: &ed25519_dalek::SigningKey,
+        game_id: u32,
+        seq: u32,
+        action: SignedAction,
+    ) -> BytesN<64> {
+        use ed25519_dalek::Signer;
+        let payload = ActionPayload {
+            contract: contract_id.clone(),
+            game_id,
+            seq,
+            action,
+        };
+        let msg = payload.to_xdr(env);
+        let mut buf = std::vec![0u8; msg.len() as usize];
+        msg.copy_into_slice(&mut buf);
+        BytesN::from_array(env, &key.sign(&buf).to_bytes())
+    }
+
+    #[test]
+    fn test_relayed_actions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let relayer = Address::generate(&env);
+
+        let key1 = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let key2 = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        for (player, key) in [(&player1, &key1), (&player2, &key2)] {
+            let public = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+            client.set_gameplay_key(player, &Some(public));
+        }
+
+        let commit = SignedAction::CommitBoard(BytesN::from_array(&env, &[1u8; 32]));
+        let sig = sign_action(&env, &contract_id, &key1, game_id, 0, commit.clone());
+        // Nobody may relay until a relayer is configured
+        assert!(client.try_relay_action(&player1, &game_id, &commit, &sig).is_err());
+        client.set_relayer(&Some(relayer.clone()));
+        client.relay_action(&player1, &game_id, &commit, &sig);
+        assert_eq!(env.auths()[0].0, relayer);
+        assert_eq!(client.action_nonce(&player1), 1);
+
+        let commit = SignedAction::CommitBoard(BytesN::from_array(&env, &[2u8; 32]));
+        let sig = sign_action(&env, &contract_id, &key2, game_id, 0, commit.clone());
+        client.relay_action(&player2, &game_id, &commit, &sig);
+
+        let shot = SignedAction::Shot(3, 4, FleetGrid::Home);
+        let sig = sign_action(&env, &contract_id, &key1, game_id, 1, shot.clone());
+        // Signed by the wrong key
+        let forged = sign_action(&env, &contract_id, &key2, game_id, 1, shot.clone());
+        assert!(client.try_relay_action(&player1, &game_id, &shot, &forged).is_err());
+        client.relay_action(&player1, &game_id, &shot, &sig);
+        let game = client.get_game(&game_id);
+        assert_eq!((game.phase, game.last_shot_x, game.last_shot_y), (Phase::AwaitingReport, 3, 4));
+
+        // A signature is only good once
+        assert!(client.try_relay_action(&player1, &game_id, &shot, &sig).is_err());
+
+        let report = SignedAction::Report(true, Bytes::new(&env));
+        let sig = sign_action(&env, &contract_id, &key2, game_id, 1, report.clone());
+        client.relay_action(&player2, &game_id, &report, &sig);
+        assert_eq!(client.get_game(&game_id).p1_hits, 1);
+    }
+
+    #[test]
+    fn test_gameplay_key_signed_calls() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+
+        let key1 = ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]);
+        let key2 = ed25519_dalek::SigningKey::from_bytes(&[2u8; 32]);
+        let shot = SignedAction::Shot(5, 6, FleetGrid::Home);
+        let sig = sign_action(&env, &contract_id, &key1, game_id, 0, shot);
+        // No gameplay key registered yet
+        let home = FleetGrid::Home;
+        assert!(client.try_take_shot_signed(&game_id, &player1, &5, &6, &home, &sig).is_err());
+
+        for (player, key) in [(&player1, &key1), (&player2, &key2)] {
+            let public = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+            client.set_gameplay_key(player, &Some(public));
+        }
+        // The signature covers the coordinates
+        assert!(client.try_take_shot_signed(&game_id, &player1, &5, &7, &home, &sig).is_err());
+        client.take_shot_signed(&game_id, &player1, &5, &6, &home, &sig);
+        // No player authorization was needed
+        assert!(env.auths().is_empty());
+
+        let proof = Bytes::new(&env);
+        let report = SignedAction::Report(false, proof.clone());
+        let sig = sign_action(&env, &contract_id, &key2, game_id, 0, report);
+        client.report_result_signed(&game_id, &player2, &false, &proof, &sig);
+        let game = client.get_game(&game_id);
+        assert_eq!((game.phase, game.turn, game.p1_shots), (Phase::AwaitingShot, 2, 1));
+    }
+
+    #[test]
+    fn test_sonar_commit_reveal() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            sonar_commit_window: Some(5),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        let proof = Bytes::new(&env);
+        assert!(client.try_report_sonar(&game_id, &player2, &2, &proof, &None).is_err());
+
+        let now = env.ledger().sequence();
+        assert_eq!(client.deadlines(&game_id).commit_by, now + 5);
+        let salt = BytesN::from_array(&env, &[9u8; 32]);
+        client.commit_sonar(&game_id, &player2, &sonar_answer_hash(&env, 2, &salt));
+        let deadlines = client.deadlines(&game_id);
+        assert_eq!((deadlines.commit_by, deadlines.report_by), (0, now + 5));
+        // Not in the same ledger as the commit
+        assert!(client.try_reveal_sonar(&game_id, &player2, &2, &salt, &proof).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        assert!(client.try_reveal_sonar(&game_id, &player2, &3, &salt, &proof).is_err());
+        client.reveal_sonar(&game_id, &player2, &2, &salt, &proof);
+
+        let game = client.get_game(&game_id);
+        assert_eq!((game.phase, game.turn, game.last_sonar_count), (Phase::AwaitingShot, 2, 2));
+        assert_eq!(client.sonar_commitment(&game_id), None);
+        // Turns and shot reports have no time limit to count down
+        assert_eq!(client.deadlines(&game_id), Deadlines::default());
+        client.take_shot(&game_id, &player2, &0, &9, &FleetGrid::Home, &None);
+        assert_eq!(client.deadlines(&game_id), Deadlines::default());
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        client.take_shot(&game_id, &player1, &1, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &false, &proof, &None);
+
+        // Player 2 pings and player 1 never commits
+        client.use_sonar(&game_id, &player2, &5, &5, &None);
+        assert!(client.try_claim_sonar_timeout(&game_id, &player2).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 6);
+        assert!(client.try_claim_sonar_timeout(&game_id, &player1).is_err());
+        client.claim_sonar_timeout(&game_id, &player2);
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::Completed(Winner::Player2));
+        assert_eq!(game.end_reason, EndReason::SonarTimeout);
+    }
+
+    #[test]
+    fn test_sweep_timeouts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let balance = soroban_sdk::token::Client::new(&env, &token);
+        client.set_listing_deposit(&ListingDeposit { token: token.clone(), amount: 10 });
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&player1, &10);
+
+        let stale = client.new_game(&player1, &None);
+        client.list_game(&stale, &player1);
+
+        let config = GameConfig {
+            sonar_commit_window: Some(5),
+            ..Default::default()
+        };
+        let pinged = client.new_game_with_config(&player1, &config);
+        client.join_game(&pinged, &player2);
+        client.commit_board(&pinged, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&pinged, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &pinged, &player1, &player2, 3);
+        client.use_sonar(&pinged, &player1, &5, &5, &None);
+
+        let active = client.new_game(&player1, &None);
+        client.join_game(&active, &player2);
+        let keeper = Address::generate(&env);
+        client.register_keeper(&keeper);
+        assert!(client.try_register_keeper(&keeper).is_err());
+        let ids = soroban_sdk::vec![&env, stale, pinged, active, 999];
+
+        // Nothing due yet
+        assert_eq!(client.sweep_timeouts(&keeper, &ids), 0);
+
+        env.ledger().with_mut(|l| l.sequence_number += lobby::LISTING_TTL_LEDGERS);
+        assert_eq!(client.sweep_timeouts(&keeper, &ids), 2);
+        assert_eq!(client.get_game(&stale).phase, Phase::Cancelled);
+        let game = client.get_game(&pinged);
+        assert_eq!(game.phase, Phase::Completed(Winner::Player1));
+        assert_eq!(game.end_reason, EndReason::SonarTimeout);
+        // Only the expired listing forfeited a deposit to pay a bounty out of
+        assert_eq!(balance.balance(&keeper), 1);
+
+        // Already resolved
+        assert_eq!(client.sweep_timeouts(&keeper, &ids), 0);
+
+        // The stale game's bounty went to the sweep, so removing it pays nothing more
+        assert_eq!(client.gc(&keeper, &ids), 1);
+        let stats = client.keeper_stats(&keeper).unwrap();
+        assert_eq!((stats.games_removed, stats.timeouts_swept), (1, 2));
+        assert_eq!(client.keeper_bounties(&keeper, &token), 1);
+        assert_eq!(client.keeper_bounties(&keeper, &Address::generate(&env)), 0);
+        assert_eq!(client.keeper_stats(&player1), None);
+    }
+
+    #[test]
+    fn test_pause_freezes_deadlines() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            sonar_commit_window: Some(5),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        let commit_by = client.deadlines(&game_id).commit_by;
+
+        client.request_pause(&game_id, &player1);
+        assert!(client.try_accept_pause(&game_id, &player1).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 2);
+        client.accept_pause(&game_id, &player2);
+
+        // Paused for 100 ledgers: the deadline moves with it
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert_eq!(client.deadlines(&game_id).commit_by, commit_by + 100);
+        assert!(client.try_claim_sonar_timeout(&game_id, &player1).is_err());
+        client.resume(&game_id, &player1);
+        assert_eq!(client.pause_state(&game_id), None);
+        assert_eq!(client.deadlines(&game_id).commit_by, commit_by + 100);
+
+        // Running again after the resume, with the 3 ledgers that were left
+        env.ledger().with_mut(|l| l.sequence_number += 3);
+        assert!(client.try_claim_sonar_timeout(&game_id, &player1).is_err());
+        env.ledger().with_mut(|l| l.sequence_number += 1);
+        client.claim_sonar_timeout(&game_id, &player1);
+    }
+
+    #[test]
+    fn test_away_flag() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        client.set_listing_deposit(&ListingDeposit { token, amount: 0 });
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            sonar_commit_window: Some(5),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.list_game(&game_id, &player1);
+
+        // Away creators drop out of the lobby until they come back
+        let now = env.ledger().sequence();
+        assert!(client.try_set_away(&player1, &Some(now + away::MAX_AWAY_LEDGERS + 1)).is_err());
+        client.set_away(&player1, &Some(now + 10));
+        assert_eq!(client.away_until(&player1), Some(now + 10));
+        assert_eq!(client.lobby_games(&0, &10).len(), 0);
+        client.set_away(&player1, &None);
+        assert_eq!(client.lobby_games(&0, &10).len(), 1);
+
+        // An away defender gets twice the sonar window, and the opponent can see why
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        let until = env.ledger().sequence() + 100;
+        client.set_away(&player2, &Some(until));
+        let summary = client.game_summaries(&soroban_sdk::vec![&env, game_id]).get(0).unwrap();
+        assert_eq!((summary.player1_away_until, summary.player2_away_until), (None, Some(until)));
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        assert_eq!(client.deadlines(&game_id).commit_by, env.ledger().sequence() + 10);
+
+        // The flag lapses on its own
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        assert_eq!(client.away_until(&player2), None);
+    }
+
+    #[test]
+    fn test_watch_game() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let coach = Address::generate(&env);
+        client.watch_game(&game_id, &coach);
+        client.watch_game(&game_id, &coach);
+        assert_eq!(client.game_observers(&game_id), soroban_sdk::vec![&env, coach.clone()]);
+        for _ in 1..watch::MAX_OBSERVERS {
+            client.watch_game(&game_id, &Address::generate(&env));
+        }
+        assert!(client.try_watch_game(&game_id, &Address::generate(&env)).is_err());
+
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        let notice = GameNotice {
+            observer: coach.clone(),
+            game_id,
+            actor: player1.clone(),
+            kind: action_log::ActionKind::CommitBoard,
+        };
+        let events = env.events().all();
+        let events = events.events();
+        assert_eq!(events.len(), watch::MAX_OBSERVERS as usize);
+        assert_eq!(events[0], notice.to_xdr(&env, &contract_id));
+
+        for observer in client.game_observers(&game_id).iter().skip(1) {
+            client.unwatch_game(&game_id, &observer);
+        }
+        client.unwatch_game(&game_id, &coach);
+        client.unwatch_game(&game_id, &coach);
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(env.events().all().events().len(), 1);
+    }
+
+    #[test]
+    fn test_ability_loadouts() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            loadouts: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        assert!(client.try_commit_board(&game_id, &player1, &hash1).is_err());
+        let doubled = Vec::from_array(&env, [Ability::Sonar, Ability::Sonar]);
+        let result = client.try_commit_board_with_loadout(&game_id, &player1, &hash1, &doubled);
+        assert!(result.is_err());
+        let sonar = Vec::from_array(&env, [Ability::Sonar]);
+        client.commit_board_with_loadout(&game_id, &player1, &hash1, &sonar);
+        client.commit_board_with_loadout(&game_id, &player2, &hash2, &Vec::new(&env));
+        assert_eq!(client.get_game(&game_id).p1_loadout, sonar);
+
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.report_sonar(&game_id, &player2, &0, &Bytes::new(&env), &None);
+        assert_eq!(
+            client.try_can_use_sonar(&game_id, &player2, &5, &5),
+            Err(Ok(Error::AbilityNotInLoadout))
+        );
+    }
+
+    #[test]
+    fn test_scramble_sonar() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            loadouts: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        let sonar_only = Vec::from_array(&env, [Ability::Sonar]);
+        let with_scramble = Vec::from_array(&env, [Ability::Sonar, Ability::Scramble]);
+        client.commit_board_with_loadout(&game_id, &player1, &hash1, &sonar_only);
+        client.commit_board_with_loadout(&game_id, &player2, &hash2, &with_scramble);
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.scramble_sonar(&game_id, &player2);
+        let expected_event = SonarScrambled {
+            game_id,
+            defender: player2.clone(),
+        };
+        assert_eq!(env.events().all(), std::vec![expected_event.to_xdr(&env, &contract_id)]);
+        let game = client.get_game(&game_id);
+        assert!(game.last_sonar_scrambled && game.p2_scramble_used);
+        assert_eq!((game.phase, game.turn), (Phase::AwaitingShot, 2));
+
+        // Player 1 did not pick scramble
+        client.use_sonar(&game_id, &player2, &5, &5, &None);
+        assert!(client.try_scramble_sonar(&game_id, &player1).is_err());
+        client.report_sonar(&game_id, &player1, &1, &Bytes::new(&env), &None);
+        assert!(!client.get_game(&game_id).last_sonar_scrambled);
+    }
+
+    #[test]
+    fn test_line_sonar() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            loadouts: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        let scans = Vec::from_array(&env, [Ability::Sonar, Ability::LineSonar]);
+        let sonar_only = Vec::from_array(&env, [Ability::Sonar]);
+        client.commit_board_with_loadout(&game_id, &player1, &hash1, &scans);
+        client.commit_board_with_loadout(&game_id, &player2, &hash2, &sonar_only);
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        assert!(client.try_use_line_sonar(&game_id, &player1, &10, &None).is_err());
+        client.use_line_sonar(&game_id, &player1, &4, &None);
+        let game = client.get_game(&game_id);
+        assert!(game.awaiting_line_sonar);
+        assert_eq!((game.phase, game.sonar_center_x), (Phase::AwaitingSonarReport, 4));
+
+        // A column holds up to 10 cells, more than the 3x3 area
+        let proof = Bytes::new(&env);
+        assert!(client.try_report_sonar(&game_id, &player2, &11, &proof, &None).is_err());
+        client.report_sonar(&game_id, &player2, &10, &proof, &None);
+        let game = client.get_game(&game_id);
+        assert!(!game.awaiting_line_sonar);
+        assert_eq!(game.last_sonar_count, 10);
+
+        assert_eq!(
+            client.try_can_use_line_sonar(&game_id, &player2, &4),
+            Err(Ok(Error::AbilityNotInLoadout))
+        );
+
+        // The charge is shared with the 3x3 sonar
+        client.take_shot(&game_id, &player2, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        assert_eq!(
+            client.try_can_use_sonar(&game_id, &player1, &5, &5),
+            Err(Ok(Error::SonarAlreadyUsed))
+        );
+
+        assert_eq!(Grid::Hex(2).column_cells(0), 3);
+        assert_eq!(Grid::Hex(2).column_cells(2), 5);
+        assert_eq!(Grid::Hex(2).column_cells(5), 0);
+    }
+
+    #[test]
+    fn test_public_heatmap() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let proof = Bytes::new(&env);
+        client.take_shot(&game_id, &player1, &3, &2, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        client.take_shot(&game_id, &player2, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        // Pending shots are not resolved yet
+        client.take_shot(&game_id, &player1, &4, &2, &FleetGrid::Home, &None);
+
+        let map = client.public_heatmap(&game_id, &player1, &FleetGrid::Home);
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.get_unchecked(23), heatmap::CELL_HIT);
+        assert_eq!(map.get_unchecked(24), heatmap::CELL_UNKNOWN);
+        assert_eq!(map.iter().filter(|c| *c != heatmap::CELL_UNKNOWN).count(), 1);
+
+        let map = client.public_heatmap(&game_id, &player2, &FleetGrid::Home);
+        assert_eq!(map.get_unchecked(0), heatmap::CELL_MISS);
+    }
+
+    #[test]
+    fn test_full_game_to_victory() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+        let started = GameStarted {
+            game_id,
+            player1: player1.clone(),
+            player2: player2.clone(),
+            first_mover: player1.clone(),
+            rules_digest: GameConfig::default().digest(&env),
+        };
+        assert_eq!(env.events().all(), std::vec![started.to_xdr(&env, &contract_id)]);
+
+        // Simulate 17 hits by player 1 (all ships sunk)
+        let empty_proof = Bytes::new(&env);
+        for i in 0..17u32 {
+            // Player 1 shoots
+            client.take_shot(&game_id, &player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            // Player 2 reports hit
+            client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+
+            // Player 2 shoots (misses)
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            // Player 1 reports miss
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_hits, 17);
+        assert_eq!(client.hits_to_win(&game_id, &player1), 0);
+        assert_eq!(client.hits_to_win(&game_id, &player2), 17);
+
+        // Player 1 claims victory
+        client.claim_victory(&game_id, &player1);
+        let expected_event = GameEnded {
+            game_id,
+            winner: Some(player1.clone()),
+            end_reason: EndReason::AllShipsSunk,
+        };
+        assert_eq!(
+            env.events().all(),
+            std::vec![expected_event.to_xdr(&env, &contract_id)]
+        );
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.status, 2);
+        assert_eq!(game.winner, Some(player1));
+        assert_eq!(game.end_reason, EndReason::AllShipsSunk);
+    }
+
+    #[test]
+    fn test_event_schema_round_trip() {
+        use soroban_sdk::xdr::ContractEventBody;
+        use soroban_sdk::{Map, Symbol, TryFromVal, Val};
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+
+        // Decode the emitted event back into its topics and data fields
+        let decode = |event: &soroban_sdk::xdr::ContractEvent| {
+            let ContractEventBody::V0(body) = &event.body;
+            let topics: std::vec::Vec<Val> = body
+                .topics
+                .iter()
+                .map(|topic| Val::try_from_val(&env, topic).unwrap())
+                .collect();
+            let data = Map::<Symbol, Val>::try_from_val(&env, &body.data).unwrap();
+            (topics, data)
+        };
+        let version = Symbol::new(&env, EVENT_SCHEMA_VERSION);
+
+        let events = env.events().all();
+        let (topics, data) = decode(&events.events()[0]);
+        assert_eq!(topics.len(), 3);
+        assert_eq!(Symbol::try_from_val(&env, &topics[0]).unwrap(), Symbol::new(&env, "started"));
+        assert_eq!(Symbol::try_from_val(&env, &topics[1]).unwrap(), version);
+        assert_eq!(u32::try_from_val(&env, &topics[2]).unwrap(), game_id);
+        let field = |name: &str| data.get(Symbol::new(&env, name)).unwrap();
+        assert_eq!(Address::try_from_val(&env, &field("player1")).unwrap(), player1);
+        assert_eq!(Address::try_from_val(&env, &field("player2")).unwrap(), player2);
+        assert_eq!(Address::try_from_val(&env, &field("first_mover")).unwrap(), player1);
+        assert_eq!(
+            BytesN::<32>::try_from_val(&env, &field("rules_digest")).unwrap(),
+            client.get_game(&game_id).rules_digest
+        );
+        assert_eq!(data.len(), 4);
+
+        let cancelled_id = client.new_game(&player1, &None);
+        client.cancel_game(&cancelled_id, &player1);
+        let events = env.events().all();
+        let (topics, data) = decode(&events.events()[0]);
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics[0]).unwrap(),
+            Symbol::new(&env, "game_ended")
+        );
+        assert_eq!(Symbol::try_from_val(&env, &topics[1]).unwrap(), version);
+        assert_eq!(u32::try_from_val(&env, &topics[2]).unwrap(), cancelled_id);
+        let winner = data.get(Symbol::new(&env, "winner")).unwrap();
+        assert_eq!(Option::<Address>::try_from_val(&env, &winner).unwrap(), None);
+        let end_reason = data.get(Symbol::new(&env, "end_reason")).unwrap();
+        assert_eq!(EndReason::try_from_val(&env, &end_reason).unwrap(), EndReason::Cancelled);
+    }
+
+    #[test]
+    fn test_emit_outcome_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&Address::generate(&env), &admin);
+        let player1 = Address::generate(&env);
+        let game_id = client.new_game(&player1, &None);
+        client.cancel_game(&game_id, &player1);
+
+        client.emit_outcome_event(&admin, &game_id);
+        let replayed = GameEndedReplayed {
+            game_id,
+            winner: None,
+            end_reason: EndReason::Cancelled,
+        };
+        assert_eq!(env.events().all(), std::vec![replayed.to_xdr(&env, &contract_id)]);
+
+        // Keepers may replay too, as often as they like
+        let keeper = Address::generate(&env);
+        client.register_keeper(&keeper);
+        client.emit_outcome_event(&keeper, &game_id);
+        client.emit_outcome_event(&keeper, &game_id);
+        assert_eq!(env.events().all(), std::vec![replayed.to_xdr(&env, &contract_id)]);
+        assert_eq!(client.get_game(&game_id).end_reason, EndReason::Cancelled);
+
+        assert!(client.try_emit_outcome_event(&player1, &game_id).is_err());
+        let open_id = client.new_game(&player1, &None);
+        assert!(client.try_emit_outcome_event(&admin, &open_id).is_err());
+    }
+
+    #[test]
+    fn test_retried_actions_are_ignored() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        let empty_proof = Bytes::new(&env);
+        let shot_id = Some(BytesN::from_array(&env, &[7u8; 16]));
+        let report_id = Some(BytesN::from_array(&env, &[8u8; 16]));
+
+        client.take_shot(&game_id, &player1, &3, &4, &FleetGrid::Home, &shot_id);
+        // Wallet resubmits the same shot — must not panic or count a second turn
+        client.take_shot(&game_id, &player1, &3, &4, &FleetGrid::Home, &shot_id);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_shots_taken, 1);
+
+        client.report_result(&game_id, &player2, &true, &empty_proof, &report_id);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &report_id);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_hits, 1);
+        assert_eq!(game.turn, 2);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "not your turn"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #18)"))]
+    fn test_wrong_turn() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        // Player 2 tries to shoot on player 1's turn
+        client.take_shot(&game_id, &player2, &0, &0, &FleetGrid::Home, &None);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "not enough hits"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #26)"))]
+    fn test_premature_victory_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        // Try to claim victory with 0 hits
+        client.claim_victory(&game_id, &player1);
+    }
+
+    fn start_game_and_play_turns(
+        env: &Env,
+        client: &BattleshipContractClient,
+        game_id: &u32,
+        player1: &Address,
+        player2: &Address,
+        turns: u32,
+    ) {
+        let empty_proof = Bytes::new(env);
+        // Play `turns` rounds (each round = p1 shoots + p2 shoots)
+        for i in 0..turns {
+            client.take_shot(game_id, player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(game_id, player2, &false, &empty_proof, &None);
+            client.take_shot(game_id, player2, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(game_id, player1, &false, &empty_proof, &None);
+        }
+    }
+
+    #[test]
+    fn test_sonar_available_after_3_turns() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        // Sonar not available at turn 0
+        assert!(!client.sonar_available(&game_id, &player1));
+
+        // Play 3 rounds
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        // Now it's player 1's turn with 3 turns taken → sonar available
+        assert!(client.sonar_available(&game_id, &player1));
+    }
+
+    #[test]
+    fn test_free_sonar_after_turn() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            free_sonar_after_turn: Some(5),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+        let empty_proof = Bytes::new(&env);
+
+        // Four turns played: the single charge is spent
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.report_sonar(&game_id, &player2, &0, &empty_proof, &None);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        assert!(!client.sonar_available(&game_id, &player1));
+
+        // From the fifth turn on, sonar is free
+        client.take_shot(&game_id, &player1, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &false, &empty_proof, &None);
+        client.take_shot(&game_id, &player2, &8, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        assert!(client.sonar_available(&game_id, &player1));
+        client.use_sonar(&game_id, &player1, &2, &2, &None);
+    }
+
+    #[test]
+    fn test_sonar_full_flow() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        // Play 3 rounds so p1 has 3 turns
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        // Player 1 uses sonar
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::AwaitingSonarReport);
+        assert_eq!(game.sonar_center_x, 5);
+        assert_eq!(game.sonar_center_y, 5);
+
+        // Player 2 reports sonar count
+        let empty_proof = Bytes::new(&env);
+        client.report_sonar(&game_id, &player2, &3, &empty_proof, &None);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::AwaitingShot);
+        assert_eq!(game.last_sonar_count, 3);
+        assert_eq!(game.turn, 2); // Turn swapped to player 2
+        assert!(game.p1_sonar_used); // Can't use again
+        assert_eq!(game.p1_shots_taken, 3); // Sonar is not a shot
+        assert_eq!(game.p1_abilities_used, 1);
+    }
+
+    #[test]
+    fn test_legacy_turn_counting_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+
+        let config = GameConfig {
+            legacy_turn_counting: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        assert_eq!(client.get_game(&game_id).config, config);
+
+        let default_id = client.new_game(&player1, &None);
+        assert!(!client.get_game(&default_id).config.legacy_turn_counting);
+    }
+
+    fn setup_armada_game(env: &Env) -> (BattleshipContractClient<'_>, Address, Address, u32) {
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(env, &contract_id);
+
+        let player1 = Address::generate(env);
+        let player2 = Address::generate(env);
+        let config = GameConfig {
+            mode: GameMode::Armada,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+
+        client.commit_board(&game_id, &player1, &BytesN::from_array(env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(env, &[2u8; 32]));
+        // Home boards alone do not start an armada game
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingCommit);
+        client.commit_forward_board(&game_id, &player1, &BytesN::from_array(env, &[3u8; 32]));
+        client.commit_forward_board(&game_id, &player2, &BytesN::from_array(env, &[4u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingShot);
+
+        (client, player1, player2, game_id)
+    }
+
+    #[test]
+    fn test_armada_victory_by_carriers() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, player1, player2, game_id) = setup_armada_game(&env);
+        let empty_proof = Bytes::new(&env);
+
+        for grid in [FleetGrid::Home, FleetGrid::Forward] {
+            client.take_shot(&game_id, &player1, &0, &0, &grid, &None);
+            client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+            // Defender admits the carrier on that grid went down
+            client.report_carrier_sunk(&game_id, &player2, &empty_proof);
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_hits, 1);
+        assert_eq!(game.p1_forward_hits, 1);
+        assert_eq!(game.p1_carriers_sunk, 0b11);
+
+        client.claim_victory(&game_id, &player1);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.winner, Some(player1));
+        assert_eq!(game.end_reason, EndReason::CarriersSunk);
+    }
+
+    #[test]
+    fn test_flagship_victory() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            mode: GameMode::Flagship,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        // Only the defender of the last hit may declare it
+        assert!(client.try_report_flagship_sunk(&game_id, &player1, &empty_proof).is_err());
+        assert!(client.try_claim_victory(&game_id, &player1).is_err());
+        client.report_flagship_sunk(&game_id, &player2, &empty_proof);
+
+        client.claim_victory(&game_id, &player1);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.winner, Some(player1));
+        assert_eq!(game.end_reason, EndReason::FlagshipSunk);
+    }
+
+    #[test]
+    fn test_report_ship_sunk() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+        let cell = |x, y| Coord { x, y, grid: FleetGrid::Home };
+        let destroyer = soroban_sdk::vec![&env, cell(0, 0), cell(0, 1)];
+
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        client.take_shot(&game_id, &player2, &5, &5, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        // Past the window: the defender has acted since the hit
+        assert!(client
+            .try_report_ship_sunk(&game_id, &player2, &destroyer, &empty_proof)
+            .is_err());
+
+        client.take_shot(&game_id, &player1, &0, &1, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        assert!(client
+            .try_report_ship_sunk(&game_id, &player1, &destroyer, &empty_proof)
+            .is_err());
+        // The disclosed ship must contain the hit, once per cell, on the board
+        let misses = soroban_sdk::vec![&env, cell(3, 3), cell(3, 4)];
+        let doubled = soroban_sdk::vec![&env, cell(0, 1), cell(0, 1)];
+        let off_board = soroban_sdk::vec![&env, cell(0, 1), cell(0, 10)];
+        let gapped = soroban_sdk::vec![&env, cell(0, 0), cell(0, 1), cell(0, 3)];
+        let diagonal = soroban_sdk::vec![&env, cell(0, 1), cell(1, 2)];
+        for bad in [misses, doubled, off_board, gapped, diagonal] {
+            assert!(client
+                .try_report_ship_sunk(&game_id, &player2, &bad, &empty_proof)
+                .is_err());
+        }
+        client.report_ship_sunk(&game_id, &player2, &destroyer, &empty_proof);
+        assert!(client
+            .try_report_ship_sunk(&game_id, &player2, &destroyer, &empty_proof)
+            .is_err());
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_sunk_ships, soroban_sdk::vec![&env, destroyer]);
+        assert!(game.p2_sunk_ships.is_empty());
+        // The defender still takes their turn as usual
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+    }
+
+    #[test]
+    fn test_scout_plane() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            loadouts: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        let scout = Vec::from_array(&env, [Ability::Scout]);
+        client.commit_board_with_loadout(&game_id, &player1, &hash1, &scout);
+        client.commit_board_with_loadout(&game_id, &player2, &hash2, &scout);
+        let proof = Bytes::new(&env);
+
+        // The 2x2 area must lie on the board
+        assert!(client.try_use_scout(&game_id, &player1, &9, &0, &None).is_err());
+        client.use_scout(&game_id, &player1, &2, &3, &None);
+        let game = client.get_game(&game_id);
+        assert!(game.awaiting_scout && game.p1_scout_used);
+        assert_eq!(
+            (game.phase, game.sonar_center_x, game.sonar_center_y),
+            (Phase::AwaitingSonarReport, 2, 3)
+        );
+
+        // The answer is presence only, and cannot be scrambled
+        assert!(client.try_report_sonar(&game_id, &player2, &2, &proof, &None).is_err());
+        assert!(client.try_scramble_sonar(&game_id, &player2).is_err());
+        client.report_sonar(&game_id, &player2, &1, &proof, &None);
+        let game = client.get_game(&game_id);
+        assert!(!game.awaiting_scout);
+        assert_eq!((game.last_sonar_count, game.turn), (1, 2));
+
+        // Player 2's plane flies whatever they have disclosed about their own fleet
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &proof, &None);
+        let cell = |x, y| Coord { x, y, grid: FleetGrid::Home };
+        let carrier = Vec::from_array(&env, [0, 1, 2, 3, 4].map(|x| cell(x, 0)));
+        client.report_ship_sunk(&game_id, &player2, &carrier, &proof);
+        let action_id = Some(BytesN::from_array(&env, &[7u8; 16]));
+        client.use_scout(&game_id, &player2, &5, &5, &action_id);
+        // A resubmitted flight is ignored rather than rejected
+        client.use_scout(&game_id, &player2, &5, &5, &action_id);
+        assert!(client.get_game(&game_id).p2_scout_used);
+        client.report_sonar(&game_id, &player1, &0, &proof, &None);
+
+        // And it flies once per game
+        assert!(client.try_use_scout(&game_id, &player1, &5, &5, &None).is_err());
+    }
+
+    /// Play a chaos game to round 10 with the PRNG seeded from `seed`, and check that
+    /// `weather` was drawn and announced. Player 1 spent their sonar charge in round 9.
+    fn play_to_weather_round(
+        env: &Env,
+        seed: [u8; 32],
+        weather: Weather,
+    ) -> (BattleshipContractClient<'_>, Address, Address, u32) {
+        env.host().set_base_prng_seed(seed).unwrap();
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(env, &contract_id);
+        let player1 = Address::generate(env);
+        let player2 = Address::generate(env);
+        let config = GameConfig {
+            chaos_weather: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(env, &[2u8; 32]));
+        let proof = Bytes::new(env);
+
+        start_game_and_play_turns(env, &client, &game_id, &player1, &player2, 8);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.report_sonar(&game_id, &player2, &0, &proof, &None);
+        assert_eq!(client.get_game(&game_id).weather, Weather::Calm);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &proof, &None);
+
+        let event = WeatherChanged {
+            game_id,
+            round: WEATHER_INTERVAL,
+            weather,
+        };
+        assert_eq!(env.events().all().events(), std::vec![event.to_xdr(env, &contract_id)]);
+        assert_eq!(client.get_game(&game_id).weather, weather);
+        (client, player1, player2, game_id)
+    }
+
+    #[test]
+    fn test_chaos_weather_storm() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // A seed that draws a storm
+        let (client, player1, player2, game_id) =
+            play_to_weather_round(&env, [0; 32], Weather::Storm);
+        // The storm turns a reported hit into a miss
+        client.take_shot(&game_id, &player1, &0, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &Bytes::new(&env), &None);
+        let game = client.get_game(&game_id);
+        assert!(!game.last_shot_hit);
+        assert_eq!(game.p1_hits, 0);
+    }
+
+    #[test]
+    fn test_chaos_weather_clear_skies() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        // A seed that draws clear skies
+        let (client, player1, _, game_id) =
+            play_to_weather_round(&env, [1; 32], Weather::ClearSkies);
+        // Sonar is free again under clear skies
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingSonarReport);
+    }
+
+    #[test]
+    fn test_no_touching_sunk_ships() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            no_touching: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+        let cell = |x, y| Coord { x, y, grid: FleetGrid::Home };
+
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        let destroyer = soroban_sdk::vec![&env, cell(0, 0), cell(1, 0)];
+        client.report_ship_sunk(&game_id, &player2, &destroyer, &empty_proof);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+
+        client.take_shot(&game_id, &player1, &3, &1, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        // (2, 1) is diagonal to the destroyer's (1, 0)
+        let touching = soroban_sdk::vec![&env, cell(2, 1), cell(3, 1)];
+        assert!(client
+            .try_report_ship_sunk(&game_id, &player2, &touching, &empty_proof)
+            .is_err());
+        let apart = soroban_sdk::vec![&env, cell(3, 1), cell(4, 1)];
+        client.report_ship_sunk(&game_id, &player2, &apart, &empty_proof);
+        assert_eq!(client.get_game(&game_id).p1_sunk_ships.len(), 2);
+    }
+
+    #[test]
+    fn test_diagonal_sunk_ships_cannot_cross() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            diagonal_ships: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+        let cell = |x, y| Coord { x, y, grid: FleetGrid::Home };
+
+        client.take_shot(&game_id, &player1, &1, &1, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        let destroyer = soroban_sdk::vec![&env, cell(1, 1), cell(2, 2)];
+        client.report_ship_sunk(&game_id, &player2, &destroyer, &empty_proof);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+
+        client.take_shot(&game_id, &player1, &2, &1, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+        // Shares no cell with the destroyer but passes through it between (1, 1) and (2, 2)
+        let crossing = soroban_sdk::vec![&env, cell(1, 2), cell(2, 1)];
+        assert!(client
+            .try_report_ship_sunk(&game_id, &player2, &crossing, &empty_proof)
+            .is_err());
+        // Touching is fine without the no-touching rule
+        let alongside = soroban_sdk::vec![&env, cell(2, 1), cell(3, 2)];
+        client.report_ship_sunk(&game_id, &player2, &alongside, &empty_proof);
+        assert_eq!(client.get_game(&game_id).p1_sunk_ships.len(), 2);
+    }
+
+    #[test]
+    fn test_submarine_dive() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let config = GameConfig {
+            diving_submarine: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        assert!(client.try_submerge(&game_id, &player2).is_err());
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &false, &empty_proof, &None);
+        // Diving is free: player 2 still has the move
+        client.submerge(&game_id, &player2);
+        assert!(client.try_submerge(&game_id, &player2).is_err());
+        assert_eq!(
+            client.dive(&game_id, &player2),
+            Some(Dive { first_shot: 2, last_shot: 1 + DIVE_SHOTS })
+        );
+        assert_eq!(client.dive(&game_id, &player1), None);
+
+        let mut submerged = std::vec::Vec::new();
+        for x in 1..=DIVE_SHOTS + 1 {
+            client.take_shot(&game_id, &player2, &x, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+            client.take_shot(&game_id, &player1, &x, &0, &FleetGrid::Home, &None);
+            submerged.push(client.shot_submerged(&game_id, &player2));
+            client.report_result(&game_id, &player2, &false, &empty_proof, &None);
+        }
+        assert_eq!(submerged, [true, true, true, false]);
+        assert!(!client.shot_submerged(&game_id, &player1));
+
+        // Any hit on player 1's fleet may have struck the submarine, so it can't dive
+        client.take_shot(&game_id, &player2, &5, &5, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &true, &empty_proof, &None);
+        assert_eq!(client.get_game(&game_id).p2_hits, 1);
+        assert!(client.try_submerge(&game_id, &player1).is_err());
+        assert_eq!(client.dive(&game_id, &player1), None);
+
+        // Classic games have no diving submarines; hunt fleets have no submarine at all
+        let (contract_id, player1, _player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        assert!(client.try_submerge(&game_id, &player1).is_err());
+        let hunt = GameConfig {
+            mode: GameMode::Hunt(HuntRules {
+                hunter_is_player1: false,
+                shots_per_turn: 2,
+                sonar_recharge_turns: 1,
+                turn_limit: 3,
+                fleet_cells: 2,
+            }),
+            diving_submarine: true,
+            ..Default::default()
+        };
+        assert!(client.try_new_game_with_config(&player1, &hunt).is_err());
+    }
+
+    #[test]
+    fn test_ship_lines() {
+        let square = [(2, 3), (3, 2), (1, 4)];
+        assert!(!is_ship_line(Grid::Square, false, &square));
+        assert!(is_ship_line(Grid::Square, true, &square));
+        assert!(is_ship_line(Grid::Square, false, &[(4, 4)]));
+        assert!(!is_ship_line(Grid::Square, true, &[(0, 0), (2, 2)]));
+        assert!(!is_ship_line(Grid::Square, true, &[]));
+        // Hex boards have three straight axes and no diagonals
+        assert!(is_ship_line(Grid::Hex(3), false, &square));
+        assert!(!is_ship_line(Grid::Hex(3), true, &[(1, 1), (2, 2)]));
+        assert_eq!(ship_cell((0, 0), (1, -1), 1), None);
+    }
+
+    #[test]
+    fn test_convoy_victory() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let bad = GameConfig {
+            mode: GameMode::Convoy(ConvoyRules { cells: 2, points_to_win: 3 }),
+            ..Default::default()
+        };
+        assert!(client.try_new_game_with_config(&player1, &bad).is_err());
+        let config = GameConfig {
+            mode: GameMode::Convoy(ConvoyRules { cells: 3, points_to_win: 2 }),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player1, &config);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        let empty_proof = Bytes::new(&env);
+
+        for x in 0..2 {
+            assert!(client.try_claim_victory(&game_id, &player1).is_err());
+            client.take_shot(&game_id, &player1, &x, &0, &FleetGrid::Home, &None);
+            client.report_convoy_hit(&game_id, &player2, &empty_proof, &None);
+            client.take_shot(&game_id, &player2, &x, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+
+        let game = client.get_game(&game_id);
+        assert_eq!((game.p1_hits, game.p1_shots, game.p1_convoy_points), (0, 2, 2));
+        client.claim_victory(&game_id, &player1);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.winner, Some(player1));
+        assert_eq!(game.end_reason, EndReason::ConvoyCaptured);
+    }
+
+    #[test]
+    #[should_panic(expected = "carrier already sunk")]
+    fn test_armada_carrier_reported_twice() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, player1, player2, game_id) = setup_armada_game(&env);
+        let empty_proof = Bytes::new(&env);
+
+        for _ in 0..2 {
+            client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Forward, &None);
+            client.report_result(&game_id, &player2, &true, &empty_proof, &None);
+            client.report_carrier_sunk(&game_id, &player2, &empty_proof);
+            client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player1, &false, &empty_proof, &None);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "not an armada game"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #16)"))]
+    fn test_forward_shot_requires_armada() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (contract_id, player1, player2, game_id) = setup_game(&env);
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let hash1 = BytesN::from_array(&env, &[1u8; 32]);
+        let hash2 = BytesN::from_array(&env, &[2u8; 32]);
+        client.commit_board(&game_id, &player1, &hash1);
+        client.commit_board(&game_id, &player2, &hash2);
+
+        client.take_shot(&game_id, &player1, &0, &0, &FleetGrid::Forward, &None);
+    }
+
+    #[test]
+    fn test_hex_grid_bounds() {
+        let radius = 3;
+        let hex = Grid::Hex(radius);
+        // Center and the six corners are on the board
+        assert!(hex.contains(3, 3));
+        for (q, r) in [(3, 0), (3, -3), (0, -3), (-3, 0), (-3, 3), (0, 3)] {
+            assert!(hex.contains((q + 3) as u32, (r + 3) as u32));
+        }
+        // Corners of the bounding square are not
+        assert!(!hex.contains(0, 0));
+        assert!(!hex.contains(6, 6));
+        assert!(!hex.contains(7, 3));
+        assert_eq!(hex.sonar_area_cells(), 7);
+        assert!(!Grid::Hex(0).is_valid());
+        // Too small for the fleet
+        assert!(!Grid::Hex(1).is_valid());
+        assert!(Grid::Hex(MIN_HEX_RADIUS).is_valid());
+    }
+
+    #[test]
+    fn test_grids_without_circuits_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let hunt = GameMode::Hunt(HuntRules {
+            hunter_is_player1: false,
+            shots_per_turn: 2,
+            sonar_recharge_turns: 1,
+            turn_limit: 3,
+            fleet_cells: 2,
+        });
+
+        // The circuits only prove the 10x10 board, whatever the mode
+        for (grid, mode) in [
+            (Grid::Hex(4), GameMode::Classic),
+            (Grid::Large, GameMode::Classic),
+            (Grid::Large, hunt.clone()),
+        ] {
+            let config = GameConfig { grid, mode, ..Default::default() };
+            assert!(client.try_new_game_with_config(&player1, &config).is_err());
+        }
+        let config = GameConfig { mode: hunt, ..Default::default() };
+        client.new_game_with_config(&player1, &config);
+    }
+
+    /// Hunt game where player 2 hunts with 2 shots per turn against a 2-cell fleet.
+    fn setup_hunt_game(env: &Env) -> (BattleshipContractClient<'_>, Address, Address, u32) {
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(env, &contract_id);
+
+        let fleet = Address::generate(env);
+        let hunter = Address::generate(env);
+        let config = GameConfig {
+            mode: GameMode::Hunt(HuntRules {
+                hunter_is_player1: false,
+                shots_per_turn: 2,
+                sonar_recharge_turns: 1,
+                turn_limit: 3,
+                fleet_cells: 2,
+            }),
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&fleet, &config);
+        client.join_game(&game_id, &hunter);
+        // Only the fleet commits; that alone starts the game with the hunter to move
+        client.commit_board(&game_id, &fleet, &BytesN::from_array(env, &[1u8; 32]));
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::AwaitingShot);
+        assert_eq!(game.turn, 2);
+
+        (client, fleet, hunter, game_id)
+    }
+
+    #[test]
+    fn test_hunt_hunter_sinks_fleet() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, fleet, hunter, game_id) = setup_hunt_game(&env);
+        let empty_proof = Bytes::new(&env);
+
+        client.take_shot(&game_id, &hunter, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &fleet, &true, &empty_proof, &None);
+        // Still the hunter's turn: second shot of the turn
+        assert_eq!(client.get_game(&game_id).turn, 2);
+        client.take_shot(&game_id, &hunter, &9, &8, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &fleet, &true, &empty_proof, &None);
+        assert_eq!(client.get_game(&game_id).hunt_turns, 1);
+
+        client.claim_victory(&game_id, &hunter);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.winner, Some(hunter));
+        assert_eq!(game.end_reason, EndReason::AllShipsSunk);
+    }
+
+    #[test]
+    fn test_hunt_fleet_survives_with_recharging_sonar() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, fleet, hunter, game_id) = setup_hunt_game(&env);
+        let empty_proof = Bytes::new(&env);
+        assert!(!client.sonar_available(&game_id, &hunter));
+        assert!(!client.sonar_available(&game_id, &fleet));
+
+        for turn in 0..3 {
+            if turn > 0 {
+                // Recharges every turn, so a ping is available at the start of each later turn
+                assert!(client.sonar_available(&game_id, &hunter));
+                client.use_sonar(&game_id, &hunter, &5, &5, &None);
+                client.report_sonar(&game_id, &fleet, &0, &empty_proof, &None);
+                assert!(!client.sonar_available(&game_id, &hunter));
+            } else {
+                client.take_shot(&game_id, &hunter, &0, &0, &FleetGrid::Home, &None);
+                client.report_result(&game_id, &fleet, &false, &empty_proof, &None);
+            }
+            client.take_shot(&game_id, &hunter, &1, &1, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &fleet, &false, &empty_proof, &None);
+        }
+
+        client.claim_victory(&game_id, &fleet);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.winner, Some(fleet));
+        assert_eq!(game.end_reason, EndReason::Survived);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "hunter still has turns left"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #27)"))]
+    fn test_hunt_fleet_early_claim() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, fleet, _hunter, game_id) = setup_hunt_game(&env);
+        client.claim_victory(&game_id, &fleet);
+    }
+
+    fn setup_challenge(env: &Env) -> (BattleshipContractClient<'_>, Address, u32) {
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(env, &contract_id);
+        let hub = Address::generate(env);
+        let admin = Address::generate(env);
+        let oracle = Address::generate(env);
+        client.initialize(&hub, &admin);
+
+        let day = 20_000;
+        env.ledger().set_timestamp(day as u64 * 86_400 + 3_600);
+        client.post_challenge(&day, &BytesN::from_array(env, &[9u8; 32]), &20, &oracle);
+        (client, oracle, day)
+    }
+
+    #[test]
+    fn test_daily_challenge_leaderboard() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _oracle, day) = setup_challenge(&env);
+        let fast = Address::generate(&env);
+        let slow = Address::generate(&env);
+
+        // `slow` misses twice before sinking everything, `fast` never misses
+        for (player, misses) in [(&slow, 2), (&fast, 0)] {
+            for i in 0..(17 + misses) {
+                client.challenge_shot(player, &(i % 10), &(i / 10));
+                client.report_challenge_shot(&day, player, &(i >= misses));
+            }
+        }
+
+        let run = client.challenge_run(&day, &fast).unwrap();
+        assert!(run.completed);
+        assert_eq!(run.shots, 17);
+
+        let board = client.challenge_leaderboard(&day);
+        assert_eq!(board.len(), 2);
+        assert_eq!(board.get(0).unwrap().player, fast);
+        assert_eq!(board.get(1).unwrap().shots, 19);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of shots")]
+    fn test_daily_challenge_shot_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _oracle, day) = setup_challenge(&env);
+        let player = Address::generate(&env);
+        for i in 0..21 {
+            client.challenge_shot(&player, &(i % 10), &(i / 10));
+            client.report_challenge_shot(&day, &player, &false);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cell already fired at")]
+    fn test_daily_challenge_repeat_cell() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _oracle, day) = setup_challenge(&env);
+        let player = Address::generate(&env);
+        client.challenge_shot(&player, &4, &4);
+        client.report_challenge_shot(&day, &player, &true);
+        client.challenge_shot(&player, &4, &4);
+    }
+
+    #[test]
+    #[should_panic(expected = "no challenge for this day")]
+    fn test_daily_challenge_expires() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let (client, _oracle, _day) = setup_challenge(&env);
+        env.ledger().set_timestamp(20_001 * 86_400);
+        client.challenge_shot(&Address::generate(&env), &0, &0);
+    }
+
+    #[test]
+    fn test_practice_game_plays_both_sides() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        // A hub that is not a contract: any notification would fail the call
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+
+        let player = Address::generate(&env);
+        let config = GameConfig {
+            practice: true,
+            ..Default::default()
+        };
+        let game_id = client.new_game_with_config(&player, &config);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingCommit);
+
+        client.commit_board(&game_id, &player, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingShot);
+
+        // Side 2 wins: side 1 always misses
+        let empty_proof = Bytes::new(&env);
+        for i in 0..17u32 {
+            client.take_shot(&game_id, &player, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player, &false, &empty_proof, &None);
+            client.take_shot(&game_id, &player, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&game_id, &player, &true, &empty_proof, &None);
+        }
+
+        client.claim_victory(&game_id, &player);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.phase, Phase::Completed(Winner::Player2));
+    }
+
+    #[test]
+    fn test_tutorial_game_against_script() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        // A hub that is not a contract: any notification would fail the call
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+
+        let player = Address::generate(&env);
+        let game_id = client.new_tutorial_game(&player);
+        let game = client.get_game(&game_id);
+        assert_eq!(game.player2, contract_id);
+        assert_eq!(game.phase, Phase::AwaitingCommit);
+
+        client.commit_board(&game_id, &player, &BytesN::from_array(&env, &[1u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingShot);
+
+        // The script resolves the shot and fires back straight away
+        let empty_proof = Bytes::new(&env);
+        client.take_shot(&game_id, &player, &0, &0, &FleetGrid::Home, &None);
+        let game = client.get_game(&game_id);
+        assert_eq!((game.p1_shots, game.p1_hits), (1, 0));
+        assert_eq!(game.phase, Phase::AwaitingReport);
+        assert_eq!((game.turn, game.last_shot_x, game.last_shot_y), (2, 0, 0));
+        client.report_result(&game_id, &player, &false, &empty_proof, &None);
+
+        let fleet = [
+            (1, 1), (2, 1), (3, 1), (4, 1), (5, 1), (8, 2), (8, 3), (8, 4), (8, 5),
+            (2, 4), (3, 4), (4, 4), (5, 6), (5, 7), (5, 8), (0, 8), (1, 8),
+        ];
+        for (x, y) in fleet {
+            client.take_shot(&game_id, &player, &x, &y, &FleetGrid::Home, &None);
+            if client.get_game(&game_id).phase == Phase::AwaitingReport {
+                client.report_result(&game_id, &player, &false, &empty_proof, &None);
+            }
+        }
+
+        let game = client.get_game(&game_id);
+        assert_eq!(game.p1_hits, 17);
+        assert_eq!(game.phase, Phase::Completed(Winner::Player1));
+        assert_eq!(game.end_reason, EndReason::AllShipsSunk);
+    }
+
+    #[test]
+    fn test_club_game_updates_standings() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+
+        let club_id = client.create_club(&soroban_sdk::String::from_str(&env, "Harbor"), &player1);
+        client.add_club_member(&club_id, &player2);
+
+        let game_id = client.new_game(&player1, &None);
+        client.tag_club_game(&game_id, &player1, &club_id);
+        client.join_game(&game_id, &player2);
+        assert_eq!(client.game_club(&game_id), Some(club_id));
+
+        play_to_player1_win(&env, &client, game_id, &player1, &player2);
+
+        assert_eq!(client.get_club(&club_id).games_completed, 1);
+        let standings = client.club_leaderboard(&club_id);
+        assert_eq!(standings.len(), 2);
+        assert_eq!(
+            standings.get(0).unwrap(),
+            ClubStanding { player: player1, wins: 1, losses: 0 }
+        );
+        assert_eq!(
+            standings.get(1).unwrap(),
+            ClubStanding { player: player2, wins: 0, losses: 1 }
+        );
+    }
+
+    /// Commit both boards and play a game out with player 1 sinking player 2's fleet.
+    fn play_to_player1_win(
+        env: &Env,
+        client: &BattleshipContractClient,
+        game_id: u32,
+        player1: &Address,
+        player2: &Address,
+    ) {
+        client.commit_board(&game_id, player1, &BytesN::from_array(env, &[1u8; 32]));
+        client.commit_board(&game_id, player2, &BytesN::from_array(env, &[2u8; 32]));
+        let empty_proof = Bytes::new(env);
+        for i in 0..17u32 {
+            client.take_shot(&game_id, player1, &(i % 10), &(i / 10), &FleetGrid::Home, &None);
+            client.report_result(&game_id, player2, &true, &empty_proof, &None);
+            client.take_shot(&game_id, player2, &9, &9, &FleetGrid::Home, &None);
+            client.report_result(&game_id, player1, &false, &empty_proof, &None);
+        }
+        client.claim_victory(&game_id, player1);
+    }
+
+    #[test]
+    fn test_flag_game_revokes_rewards() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let token = Address::generate(&env);
+        let quest_id = client.create_quest(&QuestGoal::Wins, &3, &token, &200, &86_400);
+        let club_id = client.create_club(&soroban_sdk::String::from_str(&env, "Harbor"), &player1);
+        client.add_club_member(&club_id, &player2);
+
+        let game_id = client.new_game(&player1, &None);
+        client.tag_club_game(&game_id, &player1, &club_id);
+        client.join_game(&game_id, &player2);
+        assert!(client.try_flag_game(&game_id).is_err());
+        play_to_player1_win(&env, &client, game_id, &player1, &player2);
+        assert_eq!(client.quest_progress(&quest_id, &player1), 1);
+
+        client.flag_game(&game_id);
+        assert!(client.is_game_flagged(&game_id));
+        assert!(client.try_flag_game(&game_id).is_err());
+        assert_eq!(client.quest_progress(&quest_id, &player1), 0);
+        assert_eq!(client.get_club(&club_id).games_completed, 0);
+        let standing = client.club_leaderboard(&club_id).get(0).unwrap();
+        assert_eq!((standing.wins, standing.losses), (0, 0));
+        // The result itself stands
+        assert_eq!(client.get_game(&game_id).winner, Some(player1));
+    }
+
+    #[test]
+    fn test_void_game() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHub, ());
+        let admin = Address::generate(&env);
+        client.initialize(&hub, &admin);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let reason = soroban_sdk::String::from_str(&env, "collusion");
+
+        // In progress: stopped without a winner
+        let live = client.new_game(&player1, &None);
+        client.join_game(&live, &player2);
+        client.commit_board(&live, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&live, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert!(client.try_void_game(&player1, &live, &reason).is_err());
+        client.void_game(&hub, &live, &reason);
+        let game = client.get_game(&live);
+        assert_eq!((game.phase, game.end_reason), (Phase::Cancelled, EndReason::Voided));
+        assert_eq!(game.winner, None);
+        assert_eq!(client.void_reason(&live), Some(reason.clone()));
+
+        // Completed: rewards revoked, result kept on record
+        let token = Address::generate(&env);
+        let quest_id = client.create_quest(&QuestGoal::Wins, &3, &token, &200, &86_400);
+        let done = client.new_game(&player1, &None);
+        client.join_game(&done, &player2);
+        play_to_player1_win(&env, &client, done, &player1, &player2);
+        client.void_game(&admin, &done, &reason);
+        let game = client.get_game(&done);
+        assert_eq!((game.end_reason, game.winner), (EndReason::Voided, Some(player1.clone())));
+        assert_eq!(client.quest_progress(&quest_id, &player1), 0);
+        assert!(client.try_void_game(&admin, &done, &reason).is_err());
+
+        // A voided game is cleaned up with its reason
+        assert_eq!(client.gc(&admin, &soroban_sdk::vec![&env, live, done]), 1);
+        assert!(client.try_get_game(&live).is_err());
+        assert_eq!(client.void_reason(&live), None);
+        assert_eq!(client.game_hash(&live), None);
+    }
+
+    #[test]
+    fn test_cheating_strikes() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let reason = soroban_sdk::String::from_str(&env, "forged proofs");
+        client.set_strike_policy(&Some(StrikePolicy {
+            max_strikes: 2,
+            ban_ledgers: 100,
+        }));
+
+        // Only games that moderation has acted on can back a strike
+        let cheated = |client: &BattleshipContractClient| {
+            let game_id = client.new_game(&player1, &None);
+            client.join_game(&game_id, &player2);
+            play_to_player1_win(&env, client, game_id, &player1, &player2);
+            game_id
+        };
+        let first = cheated(&client);
+        assert!(client.try_record_strike(&first, &player1).is_err());
+        client.flag_game(&first);
+        assert!(client.try_record_strike(&first, &Address::generate(&env)).is_err());
+        client.record_strike(&first, &player1);
+        assert!(client.try_record_strike(&first, &player1).is_err());
+        assert!(!client.is_player_barred(&player1));
+
+        let second = cheated(&client);
+        client.void_game(&admin, &second, &reason);
+        client.record_strike(&second, &player1);
+        assert_eq!(client.player_strikes(&player1).len(), 2);
+        assert!(client.is_player_barred(&player1));
+
+        // Barred from new games and from joining, but not from practice
+        let open = client.new_game(&player2, &None);
+        assert_eq!(
+            client.try_can_join_game(&open, &player1),
+            Err(Ok(Error::PlayerBarred))
+        );
+        assert!(client.try_new_game(&player1, &None).is_err());
+        let practice = GameConfig {
+            practice: true,
+            ..Default::default()
+        };
+        client.new_game_with_config(&player1, &practice);
+
+        // The bar runs out
+        env.ledger().with_mut(|l| l.sequence_number += 100);
+        assert!(!client.is_player_barred(&player1));
+        client.join_game(&open, &player1);
+    }
+
+    #[test]
+    fn test_strike_appeals() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let evidence = BytesN::from_array(&env, &[7u8; 32]);
+        let strike = |player: &Address| {
+            let game_id = client.new_game(&player1, &None);
+            client.join_game(&game_id, &player2);
+            play_to_player1_win(&env, &client, game_id, &player1, &player2);
+            client.flag_game(&game_id);
+            client.record_strike(&game_id, player);
+            game_id
+        };
+        let rescinded = strike(&player1);
+        let dismissed = strike(&player1);
+        let lapsed = strike(&player2);
+
+        // Only the struck player's own strikes, and each only once
+        assert!(client.try_appeal_strike(&player2, &rescinded, &evidence).is_err());
+        client.appeal_strike(&player1, &rescinded, &evidence);
+        let event = StrikeAppealed {
+            player: player1.clone(),
+            game_id: rescinded,
+            evidence_hash: evidence.clone(),
+            deadline_ledger: env.ledger().sequence() + APPEAL_WINDOW_LEDGERS,
+        };
+        assert_eq!(env.events().all(), std::vec![event.to_xdr(&env, &contract_id)]);
+        assert!(client.try_appeal_strike(&player1, &rescinded, &evidence).is_err());
+
+        client.decide_appeal(&player1, &rescinded, &true);
+        client.appeal_strike(&player1, &dismissed, &evidence);
+        client.decide_appeal(&player1, &dismissed, &false);
+        assert!(client.try_decide_appeal(&player1, &dismissed, &true).is_err());
+        let strikes = client.player_strikes(&player1);
+        assert_eq!(strikes.len(), 1);
+        assert_eq!(strikes.get_unchecked(0).game_id, dismissed);
+        assert_eq!(
+            client.strike_appeal(&player1, &rescinded).unwrap().status,
+            AppealStatus::Rescinded
+        );
+        // A rescinded strike is not recorded again
+        assert!(client.try_record_strike(&rescinded, &player1).is_err());
+
+        // Without a ruling in the window the strike stands
+        client.appeal_strike(&player2, &lapsed, &evidence);
+        env.ledger()
+            .with_mut(|l| l.sequence_number += APPEAL_WINDOW_LEDGERS + 1);
+        assert!(client.try_decide_appeal(&player2, &lapsed, &true).is_err());
+        assert_eq!(client.player_strikes(&player2).len(), 1);
+    }
+
+    #[test]
+    fn test_post_upgrade_check() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player = Address::generate(&env);
+        for _ in 0..3 {
+            client.new_game(&player, &None);
+        }
+        let healthy = SchemaCheck {
+            checked: 3,
+            incompatible: 0,
+        };
+        assert_eq!(client.post_upgrade_check(), healthy);
+
+        use soroban_sdk::{IntoVal, Map, Symbol, TryFromVal, Val};
+        env.as_contract(&contract_id, || {
+            let storage = env.storage().persistent();
+            let sorted = |fields: &[&str]| {
+                let mut names: std::vec::Vec<Symbol> =
+                    fields.iter().map(|field| Symbol::new(&env, field)).collect();
+                names.sort();
+                names
+            };
+            let keys = |map: &Map<Symbol, Val>| map.keys().iter().collect::<std::vec::Vec<_>>();
+
+            // The field lists match what is actually stored
+            let mut game: Map<Symbol, Val> = storage.get(&DataKey::Game(1)).unwrap();
+            let config_key = Symbol::new(&env, "config");
+            let config = game.get_unchecked(config_key.clone());
+            let mut config = Map::<Symbol, Val>::try_from_val(&env, &config).unwrap();
+            assert_eq!(keys(&game), sorted(schema::GAME_FIELDS));
+            assert_eq!(keys(&config), sorted(schema::CONFIG_FIELDS));
+
+            // A record from before a config option was added, and a cleaned-up id
+            config.remove(Symbol::new(&env, "chaos_weather"));
+            game.set(config_key, config.into_val(&env));
+            storage.set(&DataKey::Game(2), &game);
+            storage.remove(&DataKey::Game(3));
+        });
+        let check = client.post_upgrade_check();
+        assert_eq!((check.checked, check.incompatible), (2, 1));
+    }
+
+    #[test]
+    fn test_default_config() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+        let player = Address::generate(&env);
+        assert_eq!(client.default_config(), GameConfig::default());
+
+        // A league's house rules apply to every plain new_game
+        let house_rules = GameConfig {
+            no_touching: true,
+            hidden_score: true,
+            ..Default::default()
+        };
+        client.set_default_config(&house_rules);
+        assert_eq!(env.auths()[0].0, admin);
+        let game_id = client.new_game(&player, &None);
+        assert_eq!(client.get_game(&game_id).config, house_rules);
+    }
+
+    #[test]
+    fn test_instance_stats() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+
+        let cancelled = client.new_game(&player1, &None);
+        let played = client.new_game(&player1, &None);
+        client.join_game(&played, &player2);
+        client.new_game(&player2, &None);
+        client.cancel_game(&cancelled, &player1);
+        assert_eq!(
+            client.instance_stats(),
+            InstanceStats {
+                game_count: 3,
+                active_games: 2,
+            }
+        );
+
+        client.commit_board(&played, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&played, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        client.hub_end_game(&played, &Winner::Player2);
+        assert_eq!(client.instance_stats().active_games, 1);
+    }
+
+    #[test]
+    fn test_game_hash_ids() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+
+        // The creator can derive their next game's id before submitting
+        assert_eq!(client.creator_nonce(&player1), 0);
+        let expected = game_hash_for(&env, &contract_id, &player1, 0);
+        let first = client.new_game(&player1, &None);
+        let other = client.new_game(&player2, &None);
+        let second = client.new_game(&player1, &None);
+        assert_eq!(client.creator_nonce(&player1), 2);
+        assert_eq!(client.game_hash(&first), Some(expected.clone()));
+        assert_eq!(client.game_id_by_hash(&expected), Some(first));
+        assert_eq!(
+            client.game_hash(&second),
+            Some(game_hash_for(&env, &contract_id, &player1, 1))
+        );
+        assert_eq!(
+            client.game_hash(&other),
+            Some(game_hash_for(&env, &contract_id, &player2, 0))
+        );
+        assert_eq!(client.get_game_by_hash(&expected), client.get_game(&first));
+
+        // The same creator and nonce on another instance give another id
+        let elsewhere = env.register(BattleshipContract, ());
+        assert_ne!(game_hash_for(&env, &elsewhere, &player1, 0), expected);
+        let unknown = BytesN::from_array(&env, &[0u8; 32]);
+        assert_eq!(client.game_id_by_hash(&unknown), None);
+        assert!(client.try_get_game_by_hash(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_creation_nonce_retries() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let first = client.new_game(&player1, &None);
+
+        // A retried creation returns the game its nonce already made
+        let retried = client.new_game(&player1, &Some(1));
+        assert_eq!(client.new_game(&player1, &Some(1)), retried);
+        assert_eq!(client.new_game(&player1, &Some(0)), first);
+        assert_eq!(client.creator_nonce(&player1), 2);
+        assert!(client.try_new_game(&player1, &Some(3)).is_err());
+
+        // Once that game is gone, a late retry neither returns it nor makes another
+        client.cancel_game(&retried, &player1);
+        assert_eq!(client.gc(&player1, &soroban_sdk::vec![&env, retried]), 1);
+        assert!(client.try_new_game(&player1, &Some(1)).is_err());
+        assert_eq!(client.creator_nonce(&player1), 2);
+        assert_eq!(client.new_game(&player1, &Some(0)), first);
+    }
+
+    #[test]
+    fn test_time_locked_admin_actions() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+
+        // With no delay yet, a proposal can be executed at once
+        let delay = 17_280;
+        let set_delay = client.propose_admin_action(&AdminAction::AdminDelay(delay));
+        assert_eq!(env.auths()[0].0, admin);
+        client.execute_admin_action(&set_delay);
+        assert_eq!(client.admin_delay(), delay);
+        assert!(client.try_set_max_pause_ledgers(&100).is_err());
+
+        // Player-facing changes now wait out the delay in view
+        let new_hub = env.register(MockHub, ());
+        let rotate = client.propose_admin_action(&AdminAction::Hub(new_hub.clone()));
+        let lower_pause = client.propose_admin_action(&AdminAction::MaxPauseLedgers(100));
+        let executable_ledger = env.ledger().sequence() + delay;
+        assert_eq!(
+            client.pending_admin_actions(),
+            soroban_sdk::vec![
+                &env,
+                PendingAdminAction {
+                    id: rotate,
+                    action: AdminAction::Hub(new_hub.clone()),
+                    executable_ledger,
+                },
+                PendingAdminAction {
+                    id: lower_pause,
+                    action: AdminAction::MaxPauseLedgers(100),
+                    executable_ledger,
+                },
+            ]
+        );
+        assert!(client.try_execute_admin_action(&rotate).is_err());
+        client.cancel_admin_action(&lower_pause);
+        assert!(client.try_execute_admin_action(&lower_pause).is_err());
+
+        env.ledger().with_mut(|l| l.sequence_number = executable_ledger);
+        client.execute_admin_action(&rotate);
+        assert!(client.pending_admin_actions().is_empty());
+
+        // Games created from now on report to the new hub
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let game_id = client.new_game(&player1, &None);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(MockHubClient::new(&env, &new_hub).session(&game_id), Some(false));
+    }
+
+    #[test]
+    fn test_admin_handover_to_multisig() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+
+        // Any contract address can hold the role, e.g. a threshold account contract
+        let multisig = env.register(MockHub, ());
+        let handover = client.propose_admin_action(&AdminAction::Admin(multisig.clone()));
+        client.execute_admin_action(&handover);
+        assert_eq!(client.admin(), multisig);
+
+        client.set_relayer(&Some(Address::generate(&env)));
+        assert_eq!(env.auths()[0].0, multisig);
+    }
+
+    #[test]
+    fn test_feature_switches() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&env.register(MockHub, ()), &admin);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let game_id = client.new_game(&player1, &None);
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
+
+        client.set_disabled_features(&(FEATURE_SONAR | FEATURE_LOBBY));
+        assert_eq!(env.auths()[0].0, admin);
+        assert_eq!(client.disabled_features(), FEATURE_SONAR | FEATURE_LOBBY);
+        assert_eq!(
+            client.try_can_use_sonar(&game_id, &player1, &5, &5),
+            Err(Ok(Error::FeatureDisabled))
+        );
+        assert!(client.try_use_sonar(&game_id, &player1, &5, &5, &None).is_err());
+        let open = client.new_game(&player1, &None);
+        assert!(client.try_list_game(&open, &player1).is_err());
+        // Shots are not switched off with sonar
+        client.take_shot(&game_id, &player1, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player2, &false, &Bytes::new(&env), &None);
+        client.take_shot(&game_id, &player2, &9, &9, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &Bytes::new(&env), &None);
+
+        client.set_disabled_features(&FEATURE_LOBBY);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingSonarReport);
+    }
+
+    #[test]
+    fn test_pause_levels() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let playing = client.new_game(&player1, &None);
+        client.join_game(&playing, &player2);
+        let waiting = client.new_game(&player1, &None);
+
+        // Level 1: no new games or joins, but games under way go on
+        client.set_pause_level(&PAUSE_NEW_GAMES);
+        assert_eq!(client.pause_level(), PAUSE_NEW_GAMES);
+        assert!(client.try_new_game(&player2, &None).is_err());
+        assert_eq!(
+            client.try_can_join_game(&waiting, &player2),
+            Err(Ok(Error::ContractPaused))
+        );
+        client.commit_board(&playing, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+
+        // Level 3: gameplay freezes, cancelling still works
+        client.set_pause_level(&PAUSE_ALL);
+        assert_eq!(
+            client.try_can_commit_board(&playing, &player2),
+            Err(Ok(Error::ContractPaused))
+        );
+        let board = BytesN::from_array(&env, &[2u8; 32]);
+        assert!(client.try_commit_board(&playing, &player2, &board).is_err());
+        client.cancel_game(&waiting, &player1);
+        assert_eq!(client.get_game(&waiting).phase, Phase::Cancelled);
+
+        client.set_pause_level(&0);
+        client.commit_board(&playing, &player2, &board);
+        assert!(client.try_set_pause_level(&4).is_err());
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let env = Env::default();
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+
+        let caps = client.capabilities();
+        assert_eq!(caps.rules_version, soroban_sdk::String::from_str(&env, "1"));
+        // The embedded lists name every option and ability this build has
+        let options: std::vec::Vec<_> = (schema::CONFIG_FIELDS.iter())
+            .map(|field| soroban_sdk::Symbol::new(&env, field))
+            .collect();
+        assert_eq!(caps.game_options.iter().collect::<std::vec::Vec<_>>(), options);
+        let abilities: std::vec::Vec<_> =
+            caps.abilities.iter().map(|ability| std::format!("{ability:?}")).collect();
+        assert_eq!(abilities.join(","), capabilities::ABILITIES);
+    }
+
+    #[test]
+    fn test_scheduled_match() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+        let player2 = Address::generate(&env);
+        let start = env.ledger().sequence() + 100;
+        let config = GameConfig::default();
+
+        // Withdrawn before confirmation
+        let withdrawn = client.propose_match(&player1, &player2, &start, &config);
+        client.withdraw_match(&withdrawn, &player2);
+        assert_eq!(client.get_game(&withdrawn).phase, Phase::Cancelled);
+
+        let game_id = client.propose_match(&player1, &player2, &start, &config);
+        assert_eq!(client.try_can_join_game(&game_id, &player2), Err(Ok(Error::MatchNotConfirmed)));
+        let stranger = Address::generate(&env);
+        assert!(client.try_confirm_match(&game_id, &stranger).is_err());
+        client.confirm_match(&game_id, &player2);
+        assert!(client.try_withdraw_match(&game_id, &player1).is_err());
+        assert_eq!(
+            client.try_can_cancel_game(&game_id, &player1),
+            Err(Ok(Error::MatchAlreadyConfirmed))
+        );
+
+        // Nothing opens before the start ledger
+        assert_eq!(client.try_can_join_game(&game_id, &player2), Err(Ok(Error::MatchNotStarted)));
+        assert_eq!(
+            client.try_can_commit_board(&game_id, &player1),
+            Err(Ok(Error::MatchNotStarted))
+        );
+        env.ledger().with_mut(|l| l.sequence_number = start + 1);
+        assert_eq!(
+            client.try_can_join_game(&game_id, &stranger),
+            Err(Ok(Error::NotScheduledOpponent))
+        );
+        client.join_game(&game_id, &player2);
+        client.commit_board(&game_id, &player1, &BytesN::from_array(&env, &[1u8; 32]));
+        client.commit_board(&game_id, &player2, &BytesN::from_array(&env, &[2u8; 32]));
+        assert_eq!(client.get_game(&game_id).phase, Phase::AwaitingShot);
+    }
+
+    #[test]
+    fn test_club_match_settles_through_hub() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let hub = env.register(MockHub, ());
+        let hub_client = MockHubClient::new(&env, &hub);
+        client.initialize(&hub, &Address::generate(&env));
+
+        let home_admin = Address::generate(&env);
+        let away_admin = Address::generate(&env);
+        let home = client.create_club(&soroban_sdk::String::from_str(&env, "Harbor"), &home_admin);
+        let away = client.create_club(&soroban_sdk::String::from_str(&env, "Reef"), &away_admin);
+        let away_player = Address::generate(&env);
+        client.add_club_member(&away, &away_player);
+
+        let match_id = client.create_club_match(&home, &away, &1);
+        let session_id = client.get_club_match(&match_id).session_id;
+        assert_eq!(hub_client.session(&session_id), Some(false));
+        assert_eq!(client.club_matches(&home), soroban_sdk::vec![&env, match_id]);
+
+        // The away player creates the board and wins it
+        let game_id = client.new_game(&away_player, &None);
+        assert!(game_id != session_id);
+        client.enter_match_game(&match_id, &game_id, &away_player);
+        client.join_game(&game_id, &home_admin);
+        play_to_player1_win(&env, &client, game_id, &away_player, &home_admin);
+        let club_match = client.get_club_match(&match_id);
+        assert_eq!((club_match.home_score, club_match.away_score), (0, 1));
+
+        client.settle_club_match(&match_id);
+        assert_eq!(hub_client.session(&session_id), Some(true));
+        assert_eq!(client.get_club(&away).matches_won, 1);
+        assert_eq!(client.get_club(&home).matches_lost, 1);
+        assert_eq!(client.club_matches(&home).len(), 0);
+    }
+
+    #[test]
+    fn test_quest_reward_paid_from_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        client.initialize(&env.register(MockHub, ()), &Address::generate(&env));
+        let (player1, player2) = (Address::generate(&env), Address::generate(&env));
+
+        let token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        let funder = Address::generate(&env);
+        soroban_sdk::token::StellarAssetClient::new(&env, &token).mint(&funder, &500);
+        client.fund_quest_pool(&funder, &token, &500);
+
+        let quest_id = client.create_quest(&QuestGoal::Wins, &1, &token, &200, &86_400);
+        let sonar_quest =
+            client.create_quest(&QuestGoal::WinsUsingSonar, &1, &token, &200, &86_400);
+
+        let game_id = client.new_game(&player1, &None);
+        client.join_game(&game_id, &player2);
+        play_to_player1_win(&env, &client, game_id, &player1, &player2);
+        assert_eq!(client.quest_progress(&quest_id, &player1), 1);
+        assert_eq!(client.quest_progress(&sonar_quest, &player1), 0);
+        assert_eq!(client.quest_progress(&quest_id, &player2), 0);
+
+        client.claim_quest_reward(&quest_id, &player1);
+        let balance = soroban_sdk::token::Client::new(&env, &token).balance(&player1);
+        assert_eq!(balance, 200);
+        assert_eq!(client.quest_pool(&token), 300);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "not a club member"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #5)"))]
+    fn test_club_game_rejects_outsider() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let player1 = Address::generate(&env);
+
+        let club_id = client.create_club(&soroban_sdk::String::from_str(&env, "Harbor"), &player1);
+        let game_id = client.new_game(&player1, &None);
+        client.tag_club_game(&game_id, &player1, &club_id);
+        client.join_game(&game_id, &Address::generate(&env));
     }
 
     #[test]
-    #[should_panic(expected = "sonar not available this turn")]
+    #[cfg_attr(
+        feature = "panic-messages",
+        should_panic(expected = "sonar not available this turn")
+    )]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #25)"))]
     fn test_sonar_too_early() {
         let env = Env::default();
         env.mock_all_auths();
@@ -672,11 +5671,12 @@ mod test {
         client.commit_board(&game_id, &player2, &hash2);
 
         // Try sonar at turn 0 — should fail
-        client.use_sonar(&game_id, &player1, &5, &5);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
     }
 
     #[test]
-    #[should_panic(expected = "sonar already used")]
+    #[cfg_attr(feature = "panic-messages", should_panic(expected = "sonar already used"))]
+    #[cfg_attr(not(feature = "panic-messages"), should_panic(expected = "Error(Contract, #24)"))]
     fn test_sonar_double_use() {
         let env = Env::default();
         env.mock_all_auths();
@@ -693,16 +5693,16 @@ mod test {
         start_game_and_play_turns(&env, &client, &game_id, &player1, &player2, 3);
         // p1_turns=3, turn=1 → sonar available
         let empty_proof = Bytes::new(&env);
-        client.use_sonar(&game_id, &player1, &5, &5);
-        client.report_sonar(&game_id, &player2, &2, &empty_proof);
+        client.use_sonar(&game_id, &player1, &5, &5, &None);
+        client.report_sonar(&game_id, &player2, &2, &empty_proof, &None);
         // p1_turns=4, p1_sonar_used=true, turn=2
 
         // Get back to p1's turn: p2 shoots, p1 reports
-        client.take_shot(&game_id, &player2, &8, &8);
-        client.report_result(&game_id, &player1, &false, &empty_proof);
+        client.take_shot(&game_id, &player2, &8, &8, &FleetGrid::Home, &None);
+        client.report_result(&game_id, &player1, &false, &empty_proof, &None);
         // turn=1, p1_turns=4
 
         // Try sonar again — should fail with "sonar already used"
-        client.use_sonar(&game_id, &player1, &3, &3);
+        client.use_sonar(&game_id, &player1, &3, &3, &None);
     }
 }
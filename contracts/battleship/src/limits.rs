@@ -0,0 +1,107 @@
+//! Game-creation rate limits. Each creator may have a bounded number of games waiting
+//! for an opponent and must leave a minimum ledger gap between creations. The admin
+//! configures both and can exempt addresses (operators, tournament bots).
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::{require_admin, DataKey};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Unjoined games a creator may have open when no limits have been configured.
+pub const DEFAULT_MAX_OPEN_GAMES: u32 = 5;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreationLimits {
+    /// Games still waiting for an opponent that one creator may have at once
+    pub max_open_games: u32,
+    /// Ledgers that must pass between two games created by the same address
+    pub min_ledger_gap: u32,
+}
+
+fn creation_limits(env: &Env) -> CreationLimits {
+    env.storage()
+        .instance()
+        .get(&DataKey::CreationLimits)
+        .unwrap_or(CreationLimits {
+            max_open_games: DEFAULT_MAX_OPEN_GAMES,
+            min_ledger_gap: 0,
+        })
+}
+
+fn is_exempt(env: &Env, creator: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::RateLimitExempt(creator.clone()))
+}
+
+/// Enforce the creation limits for `creator`, then record the new game. `open` is
+/// whether the game will wait in setup for an opponent.
+pub(crate) fn record_creation(env: &Env, creator: &Address, open: bool) {
+    if is_exempt(env, creator) {
+        return;
+    }
+    let limits = creation_limits(env);
+    let now = env.ledger().sequence();
+
+    let last_key = DataKey::LastCreated(creator.clone());
+    if let Some(last) = env.storage().persistent().get::<_, u32>(&last_key) {
+        assert!(
+            now >= last + limits.min_ledger_gap,
+            "creating games too quickly"
+        );
+    }
+    env.storage().persistent().set(&last_key, &now);
+
+    if open {
+        let open_key = DataKey::OpenGames(creator.clone());
+        let count: u32 = env.storage().persistent().get(&open_key).unwrap_or(0);
+        assert!(count < limits.max_open_games, "too many open games");
+        env.storage().persistent().set(&open_key, &(count + 1));
+    }
+}
+
+/// A game of `creator`'s left setup without an opponent joining (joined or cancelled).
+pub(crate) fn release_open_game(env: &Env, creator: &Address) {
+    let open_key = DataKey::OpenGames(creator.clone());
+    let count: u32 = env.storage().persistent().get(&open_key).unwrap_or(0);
+    if count > 0 {
+        env.storage().persistent().set(&open_key, &(count - 1));
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets the per-creator limits on game creation.
+    pub fn set_creation_limits(env: Env, limits: CreationLimits) {
+        require_admin(&env);
+        assert!(limits.max_open_games > 0, "invalid limits");
+        env.storage()
+            .instance()
+            .set(&DataKey::CreationLimits, &limits);
+    }
+
+    /// Admin exempts an address from the creation limits, or removes the exemption.
+    pub fn set_rate_limit_exempt(env: Env, creator: Address, exempt: bool) {
+        require_admin(&env);
+        let key = DataKey::RateLimitExempt(creator);
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    /// Current creation limits (view function)
+    pub fn get_creation_limits(env: Env) -> CreationLimits {
+        creation_limits(&env)
+    }
+
+    /// Games created by `creator` that are still waiting for an opponent.
+    pub fn open_games(env: Env, creator: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::OpenGames(creator))
+            .unwrap_or(0)
+    }
+}
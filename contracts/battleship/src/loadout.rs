@@ -0,0 +1,55 @@
+//! Ability loadouts (`GameConfig::loadouts`). Each player picks up to
+//! `LOADOUT_SIZE` abilities when committing their board, and may only use the
+//! abilities they picked.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::apply_commit_board;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most abilities one player can bring into a game.
+pub const LOADOUT_SIZE: u32 = 2;
+
+/// Abilities a player can spend a turn (or a report) on instead of a plain action.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ability {
+    /// Count ship cells around a target cell (`use_sonar`)
+    Sonar,
+    /// Once per game, answer a sonar ping as scrambled instead of with a count
+    /// (`scramble_sonar`)
+    Scramble,
+    /// Count ship cells in a whole column (`use_line_sonar`); shares sonar's charge
+    LineSonar,
+    /// Once per game while the carrier is afloat, learn whether a 2x2 area holds any
+    /// ship cell (`use_scout`)
+    Scout,
+}
+
+/// Panic unless `loadout` is a valid pick: at most `LOADOUT_SIZE` distinct abilities.
+pub(crate) fn assert_valid(loadout: &Vec<Ability>) {
+    assert!(loadout.len() <= LOADOUT_SIZE, "loadout too large");
+    for (i, ability) in loadout.iter().enumerate() {
+        assert!(
+            loadout.first_index_of(ability) == Some(i as u32),
+            "duplicate ability in loadout"
+        );
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// `commit_board` for loadout games, choosing the player's abilities at the
+    /// same time. The loadout is fixed once committed.
+    pub fn commit_board_with_loadout(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        board_hash: BytesN<32>,
+        loadout: Vec<Ability>,
+    ) {
+        player.require_auth();
+
+        apply_commit_board(&env, game_id, &player, board_hash, Some(loadout));
+    }
+}
@@ -0,0 +1,277 @@
+//! Public lobby: creators may list games that are waiting for an opponent in an
+//! on-chain open-game index. Listing takes a small refundable deposit, returned when
+//! the game is joined or cancelled by its creator and forfeited when a stale listing
+//! is expired by someone else. Forfeited deposits go to the treasury, and a tenth
+//! of each is owed as a bounty to whoever cleans up the game it came from, so games
+//! that never paid a deposit earn nothing.
+
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, Map, Vec};
+
+use crate::error::{ensure, Error, OrPanic};
+use crate::features::{self, FEATURE_LOBBY};
+use crate::pause_level::{self, PAUSE_DEPOSITS};
+use crate::summary::{summarize, GameSummary, MAX_SUMMARIES};
+use crate::timelock;
+use crate::treasury::{self, Inflow, Outflow};
+use crate::{away, end_game, keeper, limits, purge_game_keys, require_admin};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, EndReason, Game, Phase};
+
+/// Ledgers a listing stays valid (about a day at 5s per ledger).
+pub const LISTING_TTL_LEDGERS: u32 = 17_280;
+
+/// The bounty for cleaning up a game whose listing expired is its forfeited deposit
+/// divided by this.
+pub const GC_BOUNTY_DIVISOR: i128 = 10;
+
+/// Deposit charged for each listing.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ListingDeposit {
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// A game in the open-game index.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Listing {
+    pub creator: Address,
+    /// Deposit held for this listing, in the token configured when it was listed
+    pub deposit: ListingDeposit,
+    /// Ledger sequence from which anyone may expire the listing
+    pub expires_ledger: u32,
+}
+
+fn open_index(env: &Env) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OpenIndex)
+        .unwrap_or(Vec::new(env))
+}
+
+fn remove_listing(env: &Env, game_id: u32) -> Option<Listing> {
+    let key = DataKey::Listing(game_id);
+    let listing: Listing = env.storage().persistent().get(&key)?;
+    env.storage().persistent().remove(&key);
+
+    let mut index = open_index(env);
+    if let Some(pos) = index.first_index_of(game_id) {
+        index.remove(pos);
+    }
+    env.storage().persistent().set(&DataKey::OpenIndex, &index);
+    Some(listing)
+}
+
+/// Cancel a listed game whose listing has run out, forfeiting its deposit.
+pub(crate) fn expire(env: &Env, game_id: u32, listing: Listing) {
+    let mut game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    remove_listing(env, game_id);
+    limits::release_open_game(env, &game.player1);
+    end_game(env, game_id, &mut game, None, EndReason::Cancelled);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), &game);
+
+    if listing.deposit.amount > 0 {
+        treasury::credit(
+            env,
+            &listing.deposit.token,
+            Inflow::ForfeitedDeposit,
+            listing.deposit.amount,
+        );
+        let bounty = ListingDeposit {
+            token: listing.deposit.token,
+            amount: listing.deposit.amount / GC_BOUNTY_DIVISOR,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::GameBounty(game_id), &bounty);
+    }
+}
+
+/// Take the bounty still owed for cleaning up `game_id` into `owed`, per token. Only
+/// games whose listing deposit was forfeited owe one, and only once.
+pub(crate) fn take_bounty(env: &Env, game_id: u32, owed: &mut Map<Address, i128>) {
+    let key = DataKey::GameBounty(game_id);
+    let bounty: Option<ListingDeposit> = env.storage().persistent().get(&key);
+    if let Some(bounty) = bounty {
+        env.storage().persistent().remove(&key);
+        let total = owed.get(bounty.token.clone()).unwrap_or(0) + bounty.amount;
+        owed.set(bounty.token, total);
+    }
+}
+
+/// Take a game out of the lobby because it was joined or cancelled by its creator,
+/// refunding the deposit. No-op for unlisted games.
+pub(crate) fn close_listing(env: &Env, game_id: u32) {
+    if let Some(listing) = remove_listing(env, game_id) {
+        if listing.deposit.amount > 0 {
+            token::Client::new(env, &listing.deposit.token).transfer(
+                &env.current_contract_address(),
+                &listing.creator,
+                &listing.deposit.amount,
+            );
+        }
+    }
+}
+
+/// Pay `caller` the bounties in `owed`, each capped by the treasury balance in its
+/// token. Returns the amounts paid per token.
+pub(crate) fn pay_bounty(
+    env: &Env,
+    caller: &Address,
+    owed: Map<Address, i128>,
+) -> Map<Address, i128> {
+    let mut paid = Map::new(env);
+    for (token, amount) in owed.iter() {
+        let bounty = amount.min(treasury::balance(env, &token));
+        if bounty > 0 {
+            treasury::pay(env, &token, caller, Outflow::Bounty, bounty);
+            paid.set(token, bounty);
+        }
+    }
+    paid
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets the deposit charged for new listings. Existing listings keep theirs.
+    pub fn set_listing_deposit(env: Env, deposit: ListingDeposit) {
+        require_admin(&env);
+        timelock::require_no_delay(&env);
+        ensure(&env, deposit.amount >= 0, Error::InvalidDeposit);
+        env.storage()
+            .instance()
+            .set(&DataKey::ListingDeposit, &deposit);
+    }
+
+    /// List a game that is waiting for an opponent in the public lobby, paying the
+    /// listing deposit. Only the creator may list it.
+    pub fn list_game(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+        features::require(&env, FEATURE_LOBBY);
+        pause_level::require(&env, PAUSE_DEPOSITS);
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .ok_or(Error::GameNotFound)
+            .or_panic(&env);
+        ensure(&env, game.phase == Phase::Setup, Error::GameNotOpen);
+        ensure(&env, player == game.player1, Error::OnlyCreatorCanList);
+        let listed = env.storage().persistent().has(&DataKey::Listing(game_id));
+        ensure(&env, !listed, Error::AlreadyListed);
+
+        let deposit: ListingDeposit = env
+            .storage()
+            .instance()
+            .get(&DataKey::ListingDeposit)
+            .ok_or(Error::ListingNotConfigured)
+            .or_panic(&env);
+        if deposit.amount > 0 {
+            token::Client::new(&env, &deposit.token).transfer(
+                &player,
+                env.current_contract_address(),
+                &deposit.amount,
+            );
+        }
+
+        let listing = Listing {
+            creator: player,
+            deposit,
+            expires_ledger: env.ledger().sequence() + LISTING_TTL_LEDGERS,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Listing(game_id), &listing);
+        let mut index = open_index(&env);
+        index.push_back(game_id);
+        env.storage().persistent().set(&DataKey::OpenIndex, &index);
+    }
+
+    /// Expire a stale listing: cancels the game and forfeits the deposit.
+    /// Anyone may call this once the listing has passed its expiry ledger.
+    pub fn expire_listing(env: Env, game_id: u32) {
+        let listing: Listing = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Listing(game_id))
+            .ok_or(Error::NotListed)
+            .or_panic(&env);
+        let expired = env.ledger().sequence() >= listing.expires_ledger;
+        ensure(&env, expired, Error::ListingNotExpired);
+        expire(&env, game_id, listing);
+    }
+
+    /// Clean up after dead games: expires stale listings, then deletes cancelled games
+    /// and their secondary storage. Anyone may call this; the caller earns the bounty
+    /// owed for each removed game whose listing deposit was forfeited. Games that are
+    /// not dead are skipped. Returns the number of games removed.
+    pub fn gc(env: Env, caller: Address, game_ids: Vec<u32>) -> u32 {
+        caller.require_auth();
+
+        let now = env.ledger().sequence();
+        let mut removed = 0;
+        let mut owed = Map::new(&env);
+        for game_id in game_ids.iter() {
+            let listing: Option<Listing> =
+                env.storage().persistent().get(&DataKey::Listing(game_id));
+            if let Some(listing) = listing {
+                if now < listing.expires_ledger {
+                    continue;
+                }
+                expire(&env, game_id, listing);
+            }
+
+            let game: Option<Game> = env.storage().persistent().get(&DataKey::Game(game_id));
+            let Some(game) = game.filter(|g| g.phase == Phase::Cancelled) else {
+                continue;
+            };
+            take_bounty(&env, game_id, &mut owed);
+            purge_game_keys(&env, game_id, &game);
+            removed += 1;
+        }
+
+        let paid = pay_bounty(&env, &caller, owed);
+        keeper::record(&env, &caller, removed, 0, paid);
+        removed
+    }
+
+    /// IDs of games listed in the lobby, oldest first.
+    pub fn open_game_index(env: Env) -> Vec<u32> {
+        open_index(&env)
+    }
+
+    /// Summaries of up to `limit` listed games, starting at position `start` in the index.
+    /// Games whose creator is away are left out, so a page can come back short.
+    pub fn lobby_games(env: Env, start: u32, limit: u32) -> Vec<GameSummary> {
+        let index = open_index(&env);
+        let end = index
+            .len()
+            .min(start.saturating_add(limit.min(MAX_SUMMARIES)));
+        let mut summaries = Vec::new(&env);
+        for pos in start..end {
+            let game_id = index.get_unchecked(pos);
+            let game: Game = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Game(game_id))
+                .unwrap();
+            if !away::is_away(&env, &game.player1) {
+                summaries.push_back(summarize(&env, game_id, &game));
+            }
+        }
+        summaries
+    }
+
+    /// A game's lobby listing, if it is listed.
+    pub fn get_listing(env: Env, game_id: u32) -> Option<Listing> {
+        env.storage().persistent().get(&DataKey::Listing(game_id))
+    }
+}
@@ -0,0 +1,122 @@
+//! Moderation of games that should not count, such as self-play wash games between
+//! one owner's addresses farming quest progress. A flagged game keeps its result,
+//! but its quest credits and club standings are taken back. Voiding goes further:
+//! a game in progress is stopped without a winner, and a completed one is also
+//! marked `Voided`.
+
+use soroban_sdk::{contractimpl, Address, Env, String};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::{ensure, Error, OrPanic};
+use crate::hub::{notify_hub_end, HubOutcome};
+use crate::{
+    club, club_match, end_game, events, hub_for, quest, require_admin, DataKey, EndReason, Game,
+};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Longest void reason accepted, in bytes.
+pub const MAX_VOID_REASON_LEN: u32 = 64;
+
+pub(crate) fn is_flagged(env: &Env, game_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::FlaggedGame(game_id))
+}
+
+fn load_game(env: &Env, game_id: u32) -> Game {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .ok_or(Error::GameNotFound)
+        .or_panic(env)
+}
+
+/// Revoke a completed game's quest credits, club standings and club match point.
+fn revoke_rewards(env: &Env, game_id: u32, game: &Game) {
+    quest::revoke_credits(env, game_id);
+    club::revoke_result(env, game_id, game);
+    club_match::revoke_result(env, game_id, game);
+    env.storage()
+        .persistent()
+        .set(&DataKey::FlaggedGame(game_id), &true);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin flags a completed game after review: the quest progress it earned and
+    /// its club standings and club match point are revoked. Quest rewards already
+    /// claimed and settled club matches are not reversed.
+    pub fn flag_game(env: Env, game_id: u32) {
+        require_admin(&env);
+
+        let game = load_game(&env, game_id);
+        ensure(&env, game.phase.status() == 2, Error::GameNotCompleted);
+        ensure(&env, !is_flagged(&env, game_id), Error::AlreadyFlagged);
+
+        revoke_rewards(&env, game_id, &game);
+        events::game_flagged(&env, game_id);
+    }
+
+    /// Void a game: one in progress ends without a winner, one already completed
+    /// keeps its winner on record but has its reward effects revoked as by
+    /// `flag_game`. Either way its end reason becomes `Voided` and `reason` is kept.
+    /// `caller` must be the admin or the configured hub. When the admin voids a game
+    /// in progress, a version 3 hub is told the session was voided; older hubs are
+    /// left to close it on their side.
+    pub fn void_game(env: Env, caller: Address, game_id: u32, reason: String) {
+        caller.require_auth();
+        let admin: Option<Address> = env.storage().instance().get(&DataKey::Admin);
+        let hub: Option<Address> = env.storage().instance().get(&DataKey::Hub);
+        let allowed = Some(&caller) == admin.as_ref() || Some(&caller) == hub.as_ref();
+        ensure(&env, allowed, Error::OnlyAdminOrHubCanVoid);
+        ensure(
+            &env,
+            reason.len() <= MAX_VOID_REASON_LEN,
+            Error::VoidReasonTooLong,
+        );
+
+        let mut game = load_game(&env, game_id);
+        ensure(
+            &env,
+            game.end_reason != EndReason::Voided,
+            Error::AlreadyVoided,
+        );
+        if game.phase.is_in_progress() {
+            action_log::log_action(&env, game_id, &caller, ActionKind::Voided);
+            end_game(&env, game_id, &mut game, None, EndReason::Voided);
+            // A hub voiding the game already knows, and calling it back would re-enter it
+            if Some(&caller) != hub.as_ref() {
+                if let Some(hub) = hub_for(&env, &game) {
+                    notify_hub_end(&env, &hub, game.session_id, HubOutcome::Voided);
+                }
+            }
+        } else {
+            ensure(&env, game.phase.status() == 2, Error::GameNotStarted);
+            if !is_flagged(&env, game_id) {
+                revoke_rewards(&env, game_id, &game);
+            }
+            action_log::log_action(&env, game_id, &caller, ActionKind::Voided);
+            game.end_reason = EndReason::Voided;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+        env.storage()
+            .persistent()
+            .set(&DataKey::VoidReason(game_id), &reason);
+
+        events::game_voided(&env, game_id, reason);
+    }
+
+    /// Why a game was voided, if it was.
+    pub fn void_reason(env: Env, game_id: u32) -> Option<String> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VoidReason(game_id))
+    }
+
+    /// Whether a game was flagged with `flag_game`.
+    pub fn is_game_flagged(env: Env, game_id: u32) -> bool {
+        is_flagged(&env, game_id)
+    }
+}
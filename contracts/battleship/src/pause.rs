@@ -0,0 +1,126 @@
+//! Pausing by mutual consent for scheduled breaks in long matches. One player asks,
+//! the other accepts, and either resumes. While a game is paused its deadlines stop
+//! running, for at most `max_pause_ledgers` per pause; past that they run again even
+//! if nobody resumed.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::error::ensure;
+use crate::{require_admin, sonar_commit, timelock, DataKey, Error, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Longest a pause freezes deadlines for unless the admin changes it (about an hour
+/// at 5s per ledger).
+pub const DEFAULT_MAX_PAUSE_LEDGERS: u32 = 720;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PauseState {
+    /// The player who asked for the pause
+    pub requested_by: Address,
+    /// Ledger the opponent accepted in, or `None` while the request is pending
+    pub paused_at: Option<u32>,
+}
+
+fn load(env: &Env, game_id: u32) -> Option<PauseState> {
+    env.storage().persistent().get(&DataKey::Pause(game_id))
+}
+
+fn save(env: &Env, game_id: u32, state: &PauseState) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Pause(game_id), state);
+}
+
+pub(crate) fn clear(env: &Env, game_id: u32) {
+    env.storage().persistent().remove(&DataKey::Pause(game_id));
+}
+
+fn max_pause_ledgers(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::MaxPauseLedgers)
+        .unwrap_or(DEFAULT_MAX_PAUSE_LEDGERS)
+}
+
+/// Ledgers since `since` during which the game's current pause froze its deadlines.
+/// A deadline whose step started at `since` is pushed back by this much.
+pub(crate) fn frozen_ledgers(env: &Env, game_id: u32, since: u32) -> u32 {
+    let Some(paused_at) = load(env, game_id).and_then(|state| state.paused_at) else {
+        return 0;
+    };
+    let start = paused_at.max(since);
+    let end = env
+        .ledger()
+        .sequence()
+        .min(paused_at.saturating_add(max_pause_ledgers(env)));
+    end.saturating_sub(start)
+}
+
+/// Panic unless `player` is in the game and it is in progress.
+fn assert_playing(env: &Env, game_id: u32, player: &Address) {
+    let game: Game = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found");
+    ensure(env, game.phase.is_in_progress(), Error::NotInProgress);
+    ensure(
+        env,
+        *player == game.player1 || *player == game.player2,
+        Error::NotInThisGame,
+    );
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets how long one pause can freeze a game's deadlines.
+    pub fn set_max_pause_ledgers(env: Env, ledgers: u32) {
+        require_admin(&env);
+        timelock::require_no_delay(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxPauseLedgers, &ledgers);
+    }
+
+    /// Ask the opponent to pause an in-progress game.
+    pub fn request_pause(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        assert_playing(&env, game_id, &player);
+        assert!(load(&env, game_id).is_none(), "pause already requested");
+        let state = PauseState {
+            requested_by: player,
+            paused_at: None,
+        };
+        save(&env, game_id, &state);
+    }
+
+    /// Accept the opponent's pause request, freezing the game's deadlines.
+    pub fn accept_pause(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        assert_playing(&env, game_id, &player);
+        let mut state = load(&env, game_id).expect("no pause requested");
+        assert!(state.paused_at.is_none(), "game already paused");
+        assert!(player != state.requested_by, "the opponent must accept");
+        state.paused_at = Some(env.ledger().sequence());
+        save(&env, game_id, &state);
+    }
+
+    /// Either player ends the pause (or withdraws a pending request). Deadlines are
+    /// pushed back by the time they were frozen.
+    pub fn resume(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        assert_playing(&env, game_id, &player);
+        assert!(load(&env, game_id).is_some(), "game not paused");
+        sonar_commit::rebase_deadline(&env, game_id);
+        clear(&env, game_id);
+    }
+
+    /// A game's pause request or pause, if any.
+    pub fn pause_state(env: Env, game_id: u32) -> Option<PauseState> {
+        load(&env, game_id)
+    }
+}
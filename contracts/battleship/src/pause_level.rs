@@ -0,0 +1,76 @@
+//! Contract-wide pause, in levels, for incidents. Each level keeps the restrictions of
+//! the ones below it:
+//!
+//! 1. `PAUSE_NEW_GAMES`: no creating, joining or arranging games; games already under
+//!    way play on.
+//! 2. `PAUSE_DEPOSITS`: also no lobby listings, the only call that takes a player's
+//!    tokens into a game. The contract has no wagers, so this is the whole of level 2.
+//! 3. `PAUSE_ALL`: every game action is frozen too, as are quest reward claims,
+//!    challenge shots and club match settlement. Cancelling unstarted games (which
+//!    refunds listing deposits), treasury withdrawals and admin or hub rulings
+//!    (`void_game`, `hub_end_game`) still go through. Deadlines keep running while
+//!    frozen, so the admin should lift the freeze before a pending sonar deadline
+//!    decides a game.
+//!
+//! This is separate from the per-game pause players agree on (`request_pause`).
+
+use soroban_sdk::{contractimpl, Env};
+
+use crate::action_log::ActionKind;
+use crate::error::{Error, OrPanic};
+use crate::events;
+use crate::{require_admin, DataKey};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Level 1: no new games.
+pub const PAUSE_NEW_GAMES: u32 = 1;
+/// Level 2: no new games or deposits.
+pub const PAUSE_DEPOSITS: u32 = 2;
+/// Level 3: everything frozen except refunds, withdrawals and rulings.
+pub const PAUSE_ALL: u32 = 3;
+
+fn pause_level(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PauseLevel)
+        .unwrap_or(0)
+}
+
+/// `Err(ContractPaused)` if the pause level is `level` or higher.
+pub(crate) fn check(env: &Env, level: u32) -> Result<(), Error> {
+    if pause_level(env) >= level {
+        return Err(Error::ContractPaused);
+    }
+    Ok(())
+}
+
+/// Fail the current entry point if the pause level is `level` or higher.
+pub(crate) fn require(env: &Env, level: u32) {
+    check(env, level).or_panic(env);
+}
+
+/// Every game action passes through the action log, so the full freeze is enforced
+/// there. Cancellations and rulings are let through.
+pub(crate) fn require_action(env: &Env, kind: &ActionKind) {
+    match kind {
+        ActionKind::Cancel | ActionKind::Voided | ActionKind::HubSettled(_) => {}
+        _ => require(env, PAUSE_ALL),
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets the pause level, from 0 (running) to `PAUSE_ALL`. Takes effect at
+    /// once, without the admin delay.
+    pub fn set_pause_level(env: Env, level: u32) {
+        require_admin(&env);
+        assert!(level <= PAUSE_ALL, "invalid pause level");
+        env.storage().instance().set(&DataKey::PauseLevel, &level);
+        events::pause_level_changed(&env, level);
+    }
+
+    /// The current pause level: 0 when running normally.
+    pub fn pause_level(env: Env) -> u32 {
+        pause_level(&env)
+    }
+}
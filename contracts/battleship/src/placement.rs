@@ -0,0 +1,52 @@
+//! Ship shape rules, shared by the contract (checking disclosed sunk ships) and client
+//! crates (checking a layout before proving it), so that both accept exactly the
+//! ships the board proof does. A ship is a run of cells from its first cell along one
+//! heading; which headings are allowed depends on the board and the game's rules.
+
+use crate::Grid;
+
+const STRAIGHT: [(i32, i32); 2] = [(1, 0), (0, 1)];
+const WITH_DIAGONALS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+/// The three axes of a hex board in offset axial coordinates; there are no diagonals.
+const HEX_AXES: [(i32, i32); 3] = [(1, 0), (0, 1), (1, -1)];
+
+/// Steps `(dx, dy)` a ship may extend along from its first cell.
+pub fn headings(grid: Grid, diagonal_ships: bool) -> &'static [(i32, i32)] {
+    match grid {
+        Grid::Hex(_) => &HEX_AXES,
+        Grid::Square | Grid::Large if diagonal_ships => &WITH_DIAGONALS,
+        Grid::Square | Grid::Large => &STRAIGHT,
+    }
+}
+
+/// Cell `i` of a ship starting at `start` along `heading`, or `None` if it would fall
+/// below coordinate zero. Callers still check the cell is on the board.
+pub fn ship_cell(start: (u32, u32), heading: (i32, i32), i: u32) -> Option<(u32, u32)> {
+    let x = start.0 as i64 + heading.0 as i64 * i as i64;
+    let y = start.1 as i64 + heading.1 as i64 * i as i64;
+    Some((u32::try_from(x).ok()?, u32::try_from(y).ok()?))
+}
+
+/// Whether the distinct `cells` form exactly one ship along an allowed heading.
+pub fn is_ship_line(grid: Grid, diagonal_ships: bool, cells: &[(u32, u32)]) -> bool {
+    // Every allowed heading steps x forward, or y forward at a fixed x, so the ship's
+    // first cell is its smallest
+    let Some(&start) = cells.iter().min() else {
+        return false;
+    };
+    headings(grid, diagonal_ships).iter().any(|&heading| {
+        (0..cells.len() as u32)
+            .all(|i| ship_cell(start, heading, i).is_some_and(|cell| cells.contains(&cell)))
+    })
+}
+
+/// Whether ships `a` and `b` cross in an X: a diagonal step of one passes between two
+/// cells of the other, which steps diagonally the other way. They share no cell, but
+/// the board proof rejects them all the same.
+pub fn ships_cross(a: &[(u32, u32)], b: &[(u32, u32)]) -> bool {
+    a.iter().any(|&(x, y)| {
+        a.iter().any(|&(nx, ny)| {
+            nx == x + 1 && ny.abs_diff(y) == 1 && b.contains(&(x, ny)) && b.contains(&(nx, y))
+        })
+    })
+}
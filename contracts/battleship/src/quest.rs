@@ -0,0 +1,236 @@
+//! Seasonal quests: admin-defined goals ("win 3 games using sonar") with progress
+//! tracked per player from gameplay, paid out in tokens from a reward pool that
+//! anyone may fund. Practice and tutorial games never count towards quests.
+
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env, Vec};
+
+use crate::features::{self, FEATURE_QUEST_REWARDS};
+use crate::pause_level::{self, PAUSE_ALL};
+use crate::{require_admin, tutorial, DataKey, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Quests that may be tracking progress at once; every finished game checks each one.
+pub const MAX_ACTIVE_QUESTS: u32 = 5;
+
+/// What a quest counts.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuestGoal {
+    /// Games won
+    Wins,
+    /// Games won in which the winner used sonar
+    WinsUsingSonar,
+    /// Opposing carriers sunk (armada mode)
+    CarriersSunk,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quest {
+    pub goal: QuestGoal,
+    /// Count of `goal` needed to complete the quest
+    pub target: u32,
+    pub reward_token: Address,
+    pub reward: i128,
+    /// Ledger timestamp after which progress no longer counts
+    pub ends_at: u64,
+}
+
+fn load_quest(env: &Env, quest_id: u32) -> Quest {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Quest(quest_id))
+        .expect("quest not found")
+}
+
+fn active_quests(env: &Env) -> Vec<u32> {
+    env.storage()
+        .instance()
+        .get(&DataKey::ActiveQuests)
+        .unwrap_or(Vec::new(env))
+}
+
+fn counts_for_quests(env: &Env, game: &Game) -> bool {
+    !game.config.practice && !tutorial::is_scripted(env, game)
+}
+
+/// Add one to `player`'s progress on every running quest with this goal, noting
+/// each credit against the game so it can be revoked if the game is flagged.
+fn advance(env: &Env, game_id: u32, player: &Address, goal: QuestGoal) {
+    let now = env.ledger().timestamp();
+    for quest_id in active_quests(env).iter() {
+        let quest = load_quest(env, quest_id);
+        if quest.goal != goal || now > quest.ends_at {
+            continue;
+        }
+        let key = DataKey::QuestProgress(quest_id, player.clone());
+        let progress: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        if progress < quest.target {
+            env.storage().persistent().set(&key, &(progress + 1));
+            let mut credits = game_credits(env, game_id);
+            credits.push_back((quest_id, player.clone()));
+            env.storage()
+                .persistent()
+                .set(&DataKey::QuestCredits(game_id), &credits);
+        }
+    }
+}
+
+fn game_credits(env: &Env, game_id: u32) -> Vec<(u32, Address)> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::QuestCredits(game_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Take back the quest progress a game earned. Rewards already claimed stay paid.
+pub(crate) fn revoke_credits(env: &Env, game_id: u32) {
+    for (quest_id, player) in game_credits(env, game_id).iter() {
+        let key = DataKey::QuestProgress(quest_id, player);
+        let progress: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&key, &progress.saturating_sub(1));
+    }
+    env.storage()
+        .persistent()
+        .remove(&DataKey::QuestCredits(game_id));
+}
+
+/// Credit the winner of a finished game towards win quests.
+pub(crate) fn record_win(env: &Env, game_id: u32, game: &Game, winner_num: u32) {
+    if !counts_for_quests(env, game) {
+        return;
+    }
+    let (winner, used_sonar) = if winner_num == 1 {
+        (&game.player1, game.p1_sonar_used)
+    } else {
+        (&game.player2, game.p2_sonar_used)
+    };
+    advance(env, game_id, winner, QuestGoal::Wins);
+    if used_sonar {
+        advance(env, game_id, winner, QuestGoal::WinsUsingSonar);
+    }
+}
+
+/// Credit `shooter` with sinking a carrier.
+pub(crate) fn record_carrier_sunk(env: &Env, game_id: u32, game: &Game, shooter: &Address) {
+    if counts_for_quests(env, game) {
+        advance(env, game_id, shooter, QuestGoal::CarriersSunk);
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin defines a quest that tracks progress until `ends_at`. Returns the quest ID.
+    pub fn create_quest(
+        env: Env,
+        goal: QuestGoal,
+        target: u32,
+        reward_token: Address,
+        reward: i128,
+        ends_at: u64,
+    ) -> u32 {
+        require_admin(&env);
+        assert!(target > 0 && reward > 0, "invalid quest");
+        assert!(ends_at > env.ledger().timestamp(), "quest already over");
+
+        // Finished quests stop being tracked to make room
+        let now = env.ledger().timestamp();
+        let mut active = Vec::new(&env);
+        for quest_id in active_quests(&env).iter() {
+            if load_quest(&env, quest_id).ends_at >= now {
+                active.push_back(quest_id);
+            }
+        }
+        assert!(active.len() < MAX_ACTIVE_QUESTS, "too many active quests");
+
+        let quest_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::QuestCount)
+            .unwrap_or(0)
+            + 1;
+        let quest = Quest {
+            goal,
+            target,
+            reward_token,
+            reward,
+            ends_at,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Quest(quest_id), &quest);
+        env.storage()
+            .instance()
+            .set(&DataKey::QuestCount, &quest_id);
+        active.push_back(quest_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::ActiveQuests, &active);
+
+        quest_id
+    }
+
+    /// Add `amount` of `token` to the quest reward pool.
+    pub fn fund_quest_pool(env: Env, funder: Address, token: Address, amount: i128) {
+        funder.require_auth();
+        assert!(amount > 0, "invalid amount");
+
+        token::Client::new(&env, &token).transfer(&funder, env.current_contract_address(), &amount);
+        let key = DataKey::QuestPool(token);
+        let pool: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(pool + amount));
+    }
+
+    /// Pay a completed quest's reward to the player. Each player can claim a quest once.
+    pub fn claim_quest_reward(env: Env, quest_id: u32, player: Address) {
+        player.require_auth();
+        features::require(&env, FEATURE_QUEST_REWARDS);
+        pause_level::require(&env, PAUSE_ALL);
+
+        let quest = load_quest(&env, quest_id);
+        let progress = Self::quest_progress(env.clone(), quest_id, player.clone());
+        assert!(progress >= quest.target, "quest not completed");
+        let claimed_key = DataKey::QuestClaimed(quest_id, player.clone());
+        assert!(
+            !env.storage().persistent().has(&claimed_key),
+            "reward already claimed"
+        );
+
+        let pool_key = DataKey::QuestPool(quest.reward_token.clone());
+        let pool: i128 = env.storage().persistent().get(&pool_key).unwrap_or(0);
+        assert!(pool >= quest.reward, "reward pool empty");
+
+        env.storage().persistent().set(&claimed_key, &true);
+        env.storage()
+            .persistent()
+            .set(&pool_key, &(pool - quest.reward));
+        token::Client::new(&env, &quest.reward_token).transfer(
+            &env.current_contract_address(),
+            &player,
+            &quest.reward,
+        );
+    }
+
+    /// Get a quest (view function)
+    pub fn get_quest(env: Env, quest_id: u32) -> Quest {
+        load_quest(&env, quest_id)
+    }
+
+    /// A player's progress towards a quest's target.
+    pub fn quest_progress(env: Env, quest_id: u32, player: Address) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::QuestProgress(quest_id, player))
+            .unwrap_or(0)
+    }
+
+    /// Unclaimed reward balance held for quests in `token`.
+    pub fn quest_pool(env: Env, token: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::QuestPool(token))
+            .unwrap_or(0)
+    }
+}
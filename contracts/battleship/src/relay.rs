@@ -0,0 +1,188 @@
+//! Relayed play for players without XLM for fees. A player registers an ed25519
+//! gameplay key once (the relayer can submit and pay for that call too), then signs
+//! each `ActionPayload` off-chain with it and a configured relayer submits it.
+//! Only in-game actions can be relayed; settlement calls (`claim_victory`, hub and
+//! club calls, reward claims) stay direct-auth only.
+//!
+//! Bots and automation servers can also submit shots and reports themselves with
+//! `take_shot_signed`/`report_result_signed`, paying their own fees: the gameplay
+//! key signature stands in for the player's authorization.
+
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env, Vec};
+
+use crate::error::{Error, OrPanic};
+use crate::features::{self, FEATURE_RELAY};
+use crate::{apply_commit_board, apply_line_sonar, apply_report, apply_shot};
+use crate::{apply_sonar, apply_sonar_report};
+use crate::{require_admin, Ability, DataKey, FleetGrid};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// An in-game action a player can sign for someone else to submit.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignedAction {
+    CommitBoard(BytesN<32>),
+    CommitBoardWithLoadout(BytesN<32>, Vec<Ability>),
+    Shot(u32, u32, FleetGrid),
+    Report(bool, Bytes),
+    Sonar(u32, u32),
+    LineSonar(u32),
+    SonarReport(u32, Bytes),
+}
+
+/// What the player signs: the XDR encoding of this struct. `seq` must equal the
+/// player's current `action_nonce`, so each signature is accepted once.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionPayload {
+    pub contract: Address,
+    pub game_id: u32,
+    pub seq: u32,
+    pub action: SignedAction,
+}
+
+fn gameplay_key(env: &Env, player: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GameplayKey(player.clone()))
+}
+
+fn nonce(env: &Env, player: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ActionNonce(player.clone()))
+        .unwrap_or(0)
+}
+
+/// Check `signature` by the player's gameplay key over their next payload for
+/// `action`, then consume the nonce.
+pub(crate) fn verify_signed(
+    env: &Env,
+    player: &Address,
+    game_id: u32,
+    action: &SignedAction,
+    signature: &BytesN<64>,
+) {
+    let key = gameplay_key(env, player)
+        .ok_or(Error::NoGameplayKey)
+        .or_panic(env);
+    let seq = nonce(env, player);
+    let payload = ActionPayload {
+        contract: env.current_contract_address(),
+        game_id,
+        seq,
+        action: action.clone(),
+    };
+    env.crypto()
+        .ed25519_verify(&key, &payload.to_xdr(env), signature);
+    env.storage()
+        .persistent()
+        .set(&DataKey::ActionNonce(player.clone()), &(seq + 1));
+}
+
+/// Apply a verified action as `player`.
+pub(crate) fn dispatch(env: &Env, game_id: u32, player: &Address, action: SignedAction) {
+    match action {
+        SignedAction::CommitBoard(board_hash) => {
+            apply_commit_board(env, game_id, player, board_hash, None)
+        }
+        SignedAction::CommitBoardWithLoadout(board_hash, loadout) => {
+            apply_commit_board(env, game_id, player, board_hash, Some(loadout))
+        }
+        SignedAction::Shot(x, y, grid) => apply_shot(env, game_id, player, x, y, grid),
+        SignedAction::Report(hit, proof) => apply_report(env, game_id, player, hit, proof),
+        SignedAction::Sonar(x, y) => apply_sonar(env, game_id, player, x, y),
+        SignedAction::LineSonar(column) => apply_line_sonar(env, game_id, player, column),
+        SignedAction::SonarReport(count, proof) => {
+            apply_sonar_report(env, game_id, player, count, proof)
+        }
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Set or clear the relayer allowed to submit signed actions (admin only).
+    pub fn set_relayer(env: Env, relayer: Option<Address>) {
+        require_admin(&env);
+        match relayer {
+            Some(relayer) => env.storage().instance().set(&DataKey::Relayer, &relayer),
+            None => env.storage().instance().remove(&DataKey::Relayer),
+        }
+    }
+
+    /// Register (or with `None`, revoke) the ed25519 key whose signatures are
+    /// accepted for `player`'s signed actions.
+    pub fn set_gameplay_key(env: Env, player: Address, key: Option<BytesN<32>>) {
+        player.require_auth();
+        let storage_key = DataKey::GameplayKey(player);
+        match key {
+            Some(key) => env.storage().persistent().set(&storage_key, &key),
+            None => env.storage().persistent().remove(&storage_key),
+        }
+    }
+
+    /// Relayer only: apply an action `player` signed with their gameplay key.
+    pub fn relay_action(
+        env: Env,
+        player: Address,
+        game_id: u32,
+        action: SignedAction,
+        signature: BytesN<64>,
+    ) {
+        let relayer: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Relayer)
+            .ok_or(Error::NoRelayer)
+            .or_panic(&env);
+        relayer.require_auth();
+        features::require(&env, FEATURE_RELAY);
+
+        verify_signed(&env, &player, game_id, &action, &signature);
+        dispatch(&env, game_id, &player, action);
+    }
+
+    /// `take_shot` authorized by a gameplay key signature over `SignedAction::Shot`
+    /// instead of the player's auth. Anyone may submit it.
+    pub fn take_shot_signed(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        x: u32,
+        y: u32,
+        grid: FleetGrid,
+        signature: BytesN<64>,
+    ) {
+        features::require(&env, FEATURE_RELAY);
+        let action = SignedAction::Shot(x, y, grid);
+        verify_signed(&env, &player, game_id, &action, &signature);
+        apply_shot(&env, game_id, &player, x, y, grid);
+    }
+
+    /// `report_result` authorized by a gameplay key signature over
+    /// `SignedAction::Report` instead of the player's auth. Anyone may submit it.
+    pub fn report_result_signed(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        hit: bool,
+        proof: Bytes,
+        signature: BytesN<64>,
+    ) {
+        features::require(&env, FEATURE_RELAY);
+        let action = SignedAction::Report(hit, proof.clone());
+        verify_signed(&env, &player, game_id, &action, &signature);
+        apply_report(&env, game_id, &player, hit, proof);
+    }
+
+    /// The player's registered gameplay key, if any.
+    pub fn get_gameplay_key(env: Env, player: Address) -> Option<BytesN<32>> {
+        gameplay_key(&env, &player)
+    }
+
+    /// Sequence number the player's next signed action must carry.
+    pub fn action_nonce(env: Env, player: Address) -> u32 {
+        nonce(&env, &player)
+    }
+}
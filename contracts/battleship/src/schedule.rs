@@ -0,0 +1,147 @@
+//! Scheduled matches for league fixtures. A creator proposes a game against a named
+//! opponent that starts after a given ledger; once the opponent confirms, neither
+//! side can back out, and the game opens for joining and board commits when the
+//! start ledger has passed. Until the opponent confirms, either player can withdraw.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::ensure;
+use crate::pause_level::{self, PAUSE_NEW_GAMES};
+use crate::{end_game, limits, lobby, DataKey, EndReason, Error, Game, GameConfig, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledMatch {
+    /// The only address that may join the game
+    pub opponent: Address,
+    /// The game opens for joining and commits after this ledger
+    pub start_after_ledger: u32,
+    /// The opponent accepted the fixture; the proposer accepted by proposing
+    pub confirmed: bool,
+}
+
+fn load(env: &Env, game_id: u32) -> Option<ScheduledMatch> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ScheduledMatch(game_id))
+}
+
+/// Checks that a fixture is confirmed and past its start ledger.
+fn check_started(env: &Env, scheduled: &ScheduledMatch) -> Result<(), Error> {
+    if !scheduled.confirmed {
+        return Err(Error::MatchNotConfirmed);
+    }
+    if env.ledger().sequence() <= scheduled.start_after_ledger {
+        return Err(Error::MatchNotStarted);
+    }
+    Ok(())
+}
+
+/// Schedule checks for `join_game`.
+pub(crate) fn check_join(env: &Env, game_id: u32, player2: &Address) -> Result<(), Error> {
+    let Some(scheduled) = load(env, game_id) else {
+        return Ok(());
+    };
+    if *player2 != scheduled.opponent {
+        return Err(Error::NotScheduledOpponent);
+    }
+    check_started(env, &scheduled)
+}
+
+/// Schedule checks for `commit_board`.
+pub(crate) fn check_commit(env: &Env, game_id: u32) -> Result<(), Error> {
+    load(env, game_id).map_or(Ok(()), |scheduled| check_started(env, &scheduled))
+}
+
+/// Schedule checks for `cancel_game`: a confirmed fixture cannot be called off.
+pub(crate) fn check_cancel(env: &Env, game_id: u32) -> Result<(), Error> {
+    match load(env, game_id) {
+        Some(scheduled) if scheduled.confirmed => Err(Error::MatchAlreadyConfirmed),
+        _ => Ok(()),
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// `player1` proposes a game against `player2` that opens after
+    /// `start_after_ledger`. Returns the game ID; `player2` confirms with
+    /// `confirm_match`.
+    pub fn propose_match(
+        env: Env,
+        player1: Address,
+        player2: Address,
+        start_after_ledger: u32,
+        config: GameConfig,
+    ) -> u32 {
+        ensure(&env, player1 != player2, Error::CannotJoinOwnGame);
+        assert!(!config.practice, "practice games cannot be scheduled");
+        let game_id = Self::new_game_with_config(env.clone(), player1, config);
+
+        let scheduled = ScheduledMatch {
+            opponent: player2,
+            start_after_ledger,
+            confirmed: false,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::ScheduledMatch(game_id), &scheduled);
+        game_id
+    }
+
+    /// The scheduled opponent accepts the fixture.
+    pub fn confirm_match(env: Env, game_id: u32, player2: Address) {
+        player2.require_auth();
+        pause_level::require(&env, PAUSE_NEW_GAMES);
+
+        let mut scheduled = load(&env, game_id).expect("not a scheduled match");
+        ensure(
+            &env,
+            player2 == scheduled.opponent,
+            Error::NotScheduledOpponent,
+        );
+        ensure(&env, !scheduled.confirmed, Error::MatchAlreadyConfirmed);
+        scheduled.confirmed = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::ScheduledMatch(game_id), &scheduled);
+    }
+
+    /// Either player withdraws from a fixture the opponent has not confirmed yet,
+    /// cancelling the game.
+    pub fn withdraw_match(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        let scheduled = load(&env, game_id).expect("not a scheduled match");
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        ensure(
+            &env,
+            player == game.player1 || player == scheduled.opponent,
+            Error::NotInThisGame,
+        );
+        ensure(&env, !scheduled.confirmed, Error::MatchAlreadyConfirmed);
+        ensure(&env, game.phase == Phase::Setup, Error::NotInSetup);
+
+        limits::release_open_game(&env, &game.player1);
+        lobby::close_listing(&env, game_id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ScheduledMatch(game_id));
+        action_log::log_action(&env, game_id, &player, ActionKind::Cancel);
+        end_game(&env, game_id, &mut game, None, EndReason::Cancelled);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+
+    /// A game's fixture, if it was created with `propose_match`.
+    pub fn scheduled_match(env: Env, game_id: u32) -> Option<ScheduledMatch> {
+        load(&env, game_id)
+    }
+}
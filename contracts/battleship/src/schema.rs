@@ -0,0 +1,155 @@
+//! Storage layout checks for contract upgrades. Game records are stored as XDR maps of
+//! the `Game` fields, so a new Wasm that changes `Game` may no longer read games saved
+//! by the old one. After upgrading, and before announcing the new version, the admin
+//! runs `post_upgrade_check` to confirm recent games still load.
+
+use soroban_sdk::{contractimpl, contracttype, Env, Map, Symbol, TryFromVal, Val};
+
+use crate::{require_admin, DataKey, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most recent game ids `post_upgrade_check` looks at.
+pub const UPGRADE_CHECK_SAMPLE: u32 = 50;
+
+/// `Game` field names, in declaration order. Keep in step with `Game`.
+pub(crate) const GAME_FIELDS: &[&str] = &[
+    "player1",
+    "player2",
+    "board_hash1",
+    "board_hash2",
+    "boards_committed",
+    "turn",
+    "p1_hits",
+    "p2_hits",
+    "p1_shots",
+    "p2_shots",
+    "status",
+    "phase",
+    "session_id",
+    "last_shot_x",
+    "last_shot_y",
+    "p1_shots_taken",
+    "p2_shots_taken",
+    "p1_abilities_used",
+    "p2_abilities_used",
+    "p1_sonar_used",
+    "p2_sonar_used",
+    "sonar_center_x",
+    "sonar_center_y",
+    "last_sonar_count",
+    "last_shot_proof",
+    "last_sonar_proof",
+    "winner",
+    "end_reason",
+    "config",
+    "rules_digest",
+    "forward_hash1",
+    "forward_hash2",
+    "p1_forward_hits",
+    "p2_forward_hits",
+    "p1_carriers_sunk",
+    "p2_carriers_sunk",
+    "last_shot_grid",
+    "last_shot_hit",
+    "hunt_turns",
+    "hunt_actions_in_turn",
+    "hunt_sonar_ready_turn",
+    "p1_loadout",
+    "p2_loadout",
+    "p1_scramble_used",
+    "p2_scramble_used",
+    "last_sonar_scrambled",
+    "awaiting_line_sonar",
+    "p1_scout_used",
+    "p2_scout_used",
+    "awaiting_scout",
+    "p1_flagship_sunk",
+    "p2_flagship_sunk",
+    "p1_convoy_points",
+    "p2_convoy_points",
+    "p1_sunk_ships",
+    "p2_sunk_ships",
+    "weather",
+];
+
+/// `GameConfig` field names, in declaration order. Keep in step with `GameConfig`.
+pub(crate) const CONFIG_FIELDS: &[&str] = &[
+    "legacy_turn_counting",
+    "mode",
+    "grid",
+    "practice",
+    "sonar_commit_window",
+    "hidden_score",
+    "loadouts",
+    "free_sonar_after_turn",
+    "no_touching",
+    "diagonal_ships",
+    "diving_submarine",
+    "chaos_weather",
+];
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaCheck {
+    /// Stored games that were read; ids with no game (cleaned up) are skipped
+    pub checked: u32,
+    /// Games among them that no longer decode as `Game`
+    pub incompatible: u32,
+}
+
+/// Whether `raw` is a struct map with exactly the given fields.
+fn has_fields(env: &Env, raw: &Val, fields: &[&str]) -> bool {
+    let Ok(map) = Map::<Symbol, Val>::try_from_val(env, raw) else {
+        return false;
+    };
+    map.len() == fields.len() as u32
+        && fields
+            .iter()
+            .all(|field| map.contains_key(Symbol::new(env, field)))
+}
+
+/// Whether a stored game record decodes as the current `Game`. Decoding a struct
+/// whose fields differ traps instead of returning an error, so the fields of `Game`
+/// and its nested `GameConfig`, the struct that changes most, are compared first.
+fn is_compatible(env: &Env, raw: &Val) -> bool {
+    has_fields(env, raw, GAME_FIELDS)
+        && Map::<Symbol, Val>::try_from_val(env, raw)
+            .ok()
+            .and_then(|map| map.get(Symbol::new(env, "config")))
+            .is_some_and(|config| has_fields(env, &config, CONFIG_FIELDS))
+        && Game::try_from_val(env, raw).is_ok()
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin tries to decode the last `UPGRADE_CHECK_SAMPLE` games under the current
+    /// `Game` layout and reports how many fail, so an upgrade that would orphan
+    /// in-flight games is caught before players hit it.
+    pub fn post_upgrade_check(env: Env) -> SchemaCheck {
+        require_admin(&env);
+
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GameCount)
+            .unwrap_or(0);
+        let mut check = SchemaCheck {
+            checked: 0,
+            incompatible: 0,
+        };
+        for game_id in count.saturating_sub(UPGRADE_CHECK_SAMPLE) + 1..=count {
+            let Some(raw) = env
+                .storage()
+                .persistent()
+                .get::<_, Val>(&DataKey::Game(game_id))
+            else {
+                continue;
+            };
+            check.checked += 1;
+            if !is_compatible(&env, &raw) {
+                check.incompatible += 1;
+            }
+        }
+        check
+    }
+}
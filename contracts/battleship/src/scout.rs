@@ -0,0 +1,78 @@
+//! Carrier scout planes, a one-use ability for loadout games (`Ability::Scout`).
+//! The player to move may spend a turn flying over a 2x2 area. The defender answers
+//! through `report_sonar` with 1 if the area holds any ship cell and 0 if not, so a
+//! flight tells presence but never a count. The contract never learns which cells
+//! hold a player's carrier, so sinking it does not ground the plane.
+
+use soroban_sdk::{contractimpl, Address, BytesN, Env};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::{ensure, OrPanic};
+use crate::features::{self, FEATURE_SONAR};
+use crate::{is_retried_action, sonar_commit, Ability, DataKey, Error, Game, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Side length of the square area a scout flight covers.
+pub const SCOUT_AREA: u32 = 2;
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Fly the player's scout plane over the `SCOUT_AREA` square whose top-left cell
+    /// is `(x, y)`, using their turn. Needs `Ability::Scout` in the player's loadout
+    /// and can be used once per game. The defender answers with `report_sonar`, where
+    /// the count is 1 if any ship cell is in the area. A repeated `action_id` is
+    /// treated as a retry and ignored.
+    pub fn use_scout(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        x: u32,
+        y: u32,
+        action_id: Option<BytesN<16>>,
+    ) {
+        player.require_auth();
+        features::require(&env, FEATURE_SONAR);
+        if is_retried_action(&env, game_id, &action_id) {
+            return;
+        }
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        game.check_awaiting_shot().or_panic(&env);
+        game.check_turn(&player).or_panic(&env);
+        let scout = game.turn;
+        ensure(
+            &env,
+            game.has_ability(scout, Ability::Scout),
+            Error::AbilityNotInLoadout,
+        );
+        let grid = game.config.grid;
+        assert!(
+            (0..SCOUT_AREA).all(|dy| (0..SCOUT_AREA).all(|dx| grid.contains(x + dx, y + dy))),
+            "scout area out of bounds"
+        );
+        let (used, abilities_used) = if scout == 1 {
+            (&mut game.p1_scout_used, &mut game.p1_abilities_used)
+        } else {
+            (&mut game.p2_scout_used, &mut game.p2_abilities_used)
+        };
+        assert!(!*used, "scout already used");
+        *used = true;
+        *abilities_used += 1;
+
+        game.sonar_center_x = x;
+        game.sonar_center_y = y;
+        game.awaiting_line_sonar = false;
+        game.awaiting_scout = true;
+        game.set_phase(Phase::AwaitingSonarReport);
+        action_log::log_action(&env, game_id, &player, ActionKind::Scout(x, y));
+        sonar_commit::open(&env, game_id, &game);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+}
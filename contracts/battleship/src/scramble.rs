@@ -0,0 +1,59 @@
+//! Sonar scrambling, a one-use defensive ability for loadout games. Instead of
+//! reporting a count, the defender declares the ping scrambled: the sonar user's
+//! turn is spent and the result is openly marked unreliable rather than being a
+//! false count.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::{ensure, OrPanic};
+use crate::{events, sonar_commit, weather, Ability, DataKey, Error, Game, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Defender answers the pending sonar ping as scrambled. Needs `Ability::Scramble`
+    /// in the defender's loadout and can be used once per game.
+    pub fn scramble_sonar(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(
+            game.phase == Phase::AwaitingSonarReport,
+            "no sonar to report on"
+        );
+        assert!(!game.awaiting_scout, "scout flights cannot be scrambled");
+        game.check_defender(&player).or_panic(&env);
+        let defender = if game.turn == 1 { 2 } else { 1 };
+        ensure(
+            &env,
+            game.has_ability(defender, Ability::Scramble),
+            Error::AbilityNotInLoadout,
+        );
+        let used = if defender == 1 {
+            &mut game.p1_scramble_used
+        } else {
+            &mut game.p2_scramble_used
+        };
+        assert!(!*used, "scramble already used");
+        *used = true;
+
+        sonar_commit::clear(&env, game_id);
+        game.last_sonar_count = 0;
+        game.last_sonar_scrambled = true;
+        game.awaiting_line_sonar = false;
+        game.end_turn();
+        weather::after_turn(&env, game_id, &mut game);
+        action_log::log_action(&env, game_id, &player, ActionKind::SonarScrambled);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+
+        events::sonar_scrambled(&env, game_id, player);
+    }
+}
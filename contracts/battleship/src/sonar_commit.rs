@@ -0,0 +1,222 @@
+//! Commit–reveal sonar answers (`GameConfig::sonar_commit_window`). After a ping the
+//! defender has one window to commit `sha256(count || salt)` and, from the next
+//! ledger, one more window to reveal it via `reveal_sonar`. If either deadline
+//! passes, the pinging player can claim the game.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Bytes, BytesN, Env};
+use turn_engine::{Seat, TurnError};
+
+use crate::action_log::{self, ActionKind};
+use crate::error::{fail, OrPanic};
+use crate::{apply_sonar_report, away, end_game, pause, DataKey, EndReason};
+use crate::{Error, Game, Phase, Winner};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SonarCommitment {
+    /// `None` until the defender commits
+    pub commitment: Option<BytesN<32>>,
+    /// Ledger the commitment was made in; the reveal must come later
+    pub committed_ledger: u32,
+    /// Last ledger for the pending step (commit, then reveal)
+    pub deadline_ledger: u32,
+    /// Ledgers the pending step allows: the game's window, doubled while the
+    /// defender is marked away
+    pub window: u32,
+}
+
+/// Hash a sonar answer the way commitments are checked: sha256 of the count as
+/// 4 big-endian bytes followed by the salt.
+pub fn sonar_answer_hash(env: &Env, count: u32, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &count.to_be_bytes());
+    preimage.append(&Bytes::from(salt.clone()));
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+pub(crate) fn load(env: &Env, game_id: u32) -> Option<SonarCommitment> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SonarCommit(game_id))
+}
+
+fn save(env: &Env, game_id: u32, pending: &SonarCommitment) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SonarCommit(game_id), pending);
+}
+
+pub(crate) fn clear(env: &Env, game_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::SonarCommit(game_id));
+}
+
+/// The window for the defender's next step, doubled while they are away.
+fn step_window(env: &Env, game: &Game) -> u32 {
+    let window = game.config.sonar_commit_window.unwrap_or(0);
+    let defender = game.player(if game.turn == 1 { 2 } else { 1 });
+    if away::is_away(env, &defender) {
+        window * 2
+    } else {
+        window
+    }
+}
+
+/// Open the commit window after a ping in a commit–reveal game.
+pub(crate) fn open(env: &Env, game_id: u32, game: &Game) {
+    if game.config.sonar_commit_window.is_some() {
+        let window = step_window(env, game);
+        let pending = SonarCommitment {
+            commitment: None,
+            committed_ledger: 0,
+            deadline_ledger: env.ledger().sequence() + window,
+            window,
+        };
+        save(env, game_id, &pending);
+    }
+}
+
+/// Panic if the game's sonar answer must go through `reveal_sonar` instead.
+pub(crate) fn assert_not_pending(env: &Env, game_id: u32) {
+    assert!(
+        load(env, game_id).is_none(),
+        "sonar answer must be committed and revealed"
+    );
+}
+
+/// The pending step's deadline, pushed back by any time the game has spent paused.
+pub(crate) fn deadline(env: &Env, game_id: u32, pending: &SonarCommitment) -> u32 {
+    let step_start = pending.deadline_ledger - pending.window;
+    pending.deadline_ledger + pause::frozen_ledgers(env, game_id, step_start)
+}
+
+/// Fold time spent paused into the stored deadline when a pause ends.
+pub(crate) fn rebase_deadline(env: &Env, game_id: u32) {
+    if let Some(mut pending) = load(env, game_id) {
+        pending.deadline_ledger = deadline(env, game_id, &pending);
+        save(env, game_id, &pending);
+    }
+}
+
+/// Whether the game is waiting on a sonar commit or reveal whose deadline has passed.
+pub(crate) fn deadline_missed(env: &Env, game_id: u32, game: &Game) -> bool {
+    game.phase == Phase::AwaitingSonarReport
+        && load(env, game_id).is_some_and(|p| {
+            let turns = game.turns(Some(deadline(env, game_id, &p)));
+            turns.timed_out(env.ledger().sequence())
+        })
+}
+
+/// End a game whose sonar deadline was missed in favour of the pinging player.
+/// `actor` is whoever claimed or swept the timeout.
+pub(crate) fn time_out(env: &Env, game_id: u32, game: &mut Game, actor: &Address) {
+    clear(env, game_id);
+    action_log::log_action(env, game_id, actor, ActionKind::SonarTimeout);
+    let winner = match game.turns(None).to_move {
+        Seat::First => Winner::Player1,
+        Seat::Second => Winner::Player2,
+    };
+    end_game(env, game_id, game, Some(winner), EndReason::SonarTimeout);
+
+    env.storage()
+        .persistent()
+        .set(&DataKey::Game(game_id), game);
+}
+
+fn load_game(env: &Env, game_id: u32) -> Game {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .expect("game not found")
+}
+
+fn load_pending(env: &Env, game_id: u32, game: &Game) -> SonarCommitment {
+    assert!(
+        game.phase == Phase::AwaitingSonarReport,
+        "no sonar to report on"
+    );
+    load(env, game_id).expect("not a commit-reveal game")
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Defender commits to the pending sonar answer: `sonar_answer_hash(count, salt)`.
+    pub fn commit_sonar(env: Env, game_id: u32, player: Address, commitment: BytesN<32>) {
+        player.require_auth();
+
+        let game = load_game(&env, game_id);
+        let mut pending = load_pending(&env, game_id, &game);
+        game.check_defender(&player).or_panic(&env);
+        assert!(
+            pending.commitment.is_none(),
+            "sonar answer already committed"
+        );
+        let now = env.ledger().sequence();
+        assert!(
+            now <= deadline(&env, game_id, &pending),
+            "commit deadline passed"
+        );
+
+        pending.commitment = Some(commitment);
+        pending.committed_ledger = now;
+        pending.window = step_window(&env, &game);
+        pending.deadline_ledger = now + pending.window;
+        save(&env, game_id, &pending);
+        action_log::log_action(&env, game_id, &player, ActionKind::SonarCommit);
+    }
+
+    /// Defender reveals the committed sonar answer, in a later ledger than the commit.
+    pub fn reveal_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        count: u32,
+        salt: BytesN<32>,
+        proof: Bytes,
+    ) {
+        player.require_auth();
+
+        let game = load_game(&env, game_id);
+        let pending = load_pending(&env, game_id, &game);
+        let commitment = pending
+            .commitment
+            .clone()
+            .expect("sonar answer not committed");
+        let now = env.ledger().sequence();
+        assert!(now > pending.committed_ledger, "reveal in a later ledger");
+        assert!(
+            now <= deadline(&env, game_id, &pending),
+            "reveal deadline passed"
+        );
+        assert!(
+            sonar_answer_hash(&env, count, &salt) == commitment,
+            "answer does not match commitment"
+        );
+
+        clear(&env, game_id);
+        apply_sonar_report(&env, game_id, &player, count, proof);
+    }
+
+    /// The pinging player wins if the defender missed the commit or reveal deadline.
+    pub fn claim_sonar_timeout(env: Env, game_id: u32, player: Address) {
+        player.require_auth();
+
+        let mut game = load_game(&env, game_id);
+        let pending = load_pending(&env, game_id, &game);
+        let turns = game.turns(Some(deadline(&env, game_id, &pending)));
+        let seat = game.seat_of(&player, turns.to_move);
+        match turns.claim_forfeit(seat, env.ledger().sequence()) {
+            Ok(_) => {}
+            Err(TurnError::NotYourTurn) => fail(&env, Error::NotYourTurn),
+            Err(_) => panic!("deadline not passed"),
+        }
+
+        time_out(&env, game_id, &mut game, &player);
+    }
+
+    /// The pending commit–reveal state of a game's sonar answer, if any.
+    pub fn sonar_commitment(env: Env, game_id: u32) -> Option<SonarCommitment> {
+        load(&env, game_id)
+    }
+}
@@ -0,0 +1,47 @@
+//! Instance-wide counters for dashboards, kept as games are created and end so that
+//! reading them never scans game records. The factory sums them across instances.
+
+use soroban_sdk::{contractimpl, contracttype, Env};
+
+use crate::DataKey;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceStats {
+    /// Game ids issued so far (`game_count`)
+    pub game_count: u32,
+    /// Games created and not yet completed or cancelled, practice games included
+    pub active_games: u32,
+}
+
+fn active_games(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::ActiveGames)
+        .unwrap_or(0)
+}
+
+/// Count a newly created game as active.
+pub(crate) fn record_created(env: &Env) {
+    let active = active_games(env) + 1;
+    env.storage().instance().set(&DataKey::ActiveGames, &active);
+}
+
+/// Stop counting an ended game as active. Games created before the counter existed
+/// are not in it, so it saturates at zero.
+pub(crate) fn record_ended(env: &Env) {
+    let active = active_games(env).saturating_sub(1);
+    env.storage().instance().set(&DataKey::ActiveGames, &active);
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Game counts for this instance.
+    pub fn instance_stats(env: Env) -> InstanceStats {
+        InstanceStats {
+            game_count: Self::game_count(env.clone()),
+            active_games: active_games(&env),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Cheating strikes. When moderation finds that a player cheated in a game (the game
+//! was flagged or voided), the admin records a strike against them. Under the admin's
+//! `StrikePolicy`, a player with enough strikes is barred from creating and joining
+//! games for a while after their latest strike. Practice games stay open to everyone.
+//!
+//! A struck player may appeal each strike once with `appeal_strike`, which opens an
+//! `APPEAL_WINDOW_LEDGERS` window for the admin to rescind the strike or dismiss the
+//! appeal. An appeal nobody rules on lapses and the strike stands.
+
+use soroban_sdk::{contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+use crate::{events, moderation, require_admin, DataKey, EndReason, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Ledgers an appeal stays open for a ruling (about a week at 5s per ledger).
+pub const APPEAL_WINDOW_LEDGERS: u32 = 120_960;
+
+/// A recorded finding of cheating.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Strike {
+    /// The game the player cheated in
+    pub game_id: u32,
+    /// Ledger the strike was recorded in
+    pub ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrikePolicy {
+    /// Strikes that get a player barred
+    pub max_strikes: u32,
+    /// Ledgers the bar lasts, counted from the latest strike
+    pub ban_ledgers: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AppealStatus {
+    /// Awaiting a ruling until the deadline, after which it has lapsed
+    Open,
+    /// The strike was withdrawn
+    Rescinded,
+    /// The strike stands
+    Dismissed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrikeAppeal {
+    /// Hash of the player's evidence, kept off-chain
+    pub evidence_hash: BytesN<32>,
+    /// Last ledger the admin can rule in
+    pub deadline_ledger: u32,
+    pub status: AppealStatus,
+}
+
+fn load_strikes(env: &Env, player: &Address) -> Vec<Strike> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Strikes(player.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn load_appeal(env: &Env, player: &Address, game_id: u32) -> Option<StrikeAppeal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::StrikeAppeal(player.clone(), game_id))
+}
+
+/// Whether `player` is currently barred under the strike policy. Without a policy
+/// nobody is.
+pub(crate) fn is_barred(env: &Env, player: &Address) -> bool {
+    let Some(policy) = env
+        .storage()
+        .instance()
+        .get::<_, StrikePolicy>(&DataKey::StrikePolicy)
+    else {
+        return false;
+    };
+    let strikes = load_strikes(env, player);
+    match strikes.last() {
+        Some(latest) if strikes.len() >= policy.max_strikes => {
+            env.ledger().sequence() < latest.ledger.saturating_add(policy.ban_ledgers)
+        }
+        _ => false,
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin sets how many strikes bar a player and for how long, or removes the
+    /// policy so strikes bar nobody.
+    pub fn set_strike_policy(env: Env, policy: Option<StrikePolicy>) {
+        require_admin(&env);
+        match policy {
+            Some(policy) => {
+                assert!(policy.max_strikes > 0, "invalid policy");
+                env.storage()
+                    .instance()
+                    .set(&DataKey::StrikePolicy, &policy);
+            }
+            None => env.storage().instance().remove(&DataKey::StrikePolicy),
+        }
+    }
+
+    /// Current strike policy (view function)
+    pub fn get_strike_policy(env: Env) -> Option<StrikePolicy> {
+        env.storage().instance().get(&DataKey::StrikePolicy)
+    }
+
+    /// Admin records that `player` cheated in `game_id`. The game must already have
+    /// been flagged or voided, and a player gets at most one strike per game.
+    pub fn record_strike(env: Env, game_id: u32, player: Address) {
+        require_admin(&env);
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(
+            player == game.player1 || player == game.player2,
+            "not a player"
+        );
+        assert!(
+            moderation::is_flagged(&env, game_id) || game.end_reason == EndReason::Voided,
+            "game not moderated"
+        );
+        let mut strikes = load_strikes(&env, &player);
+        assert!(
+            !strikes.iter().any(|strike| strike.game_id == game_id),
+            "strike already recorded"
+        );
+        assert!(
+            load_appeal(&env, &player, game_id)
+                .is_none_or(|appeal| appeal.status != AppealStatus::Rescinded),
+            "strike was rescinded"
+        );
+
+        strikes.push_back(Strike {
+            game_id,
+            ledger: env.ledger().sequence(),
+        });
+        env.storage()
+            .persistent()
+            .set(&DataKey::Strikes(player.clone()), &strikes);
+
+        events::strike_recorded(&env, player, game_id);
+    }
+
+    /// `player` appeals their strike for `game_id`, submitting the hash of their
+    /// evidence. Each strike can be appealed once.
+    pub fn appeal_strike(env: Env, player: Address, game_id: u32, evidence_hash: BytesN<32>) {
+        player.require_auth();
+
+        assert!(
+            load_strikes(&env, &player)
+                .iter()
+                .any(|strike| strike.game_id == game_id),
+            "no such strike"
+        );
+        let key = DataKey::StrikeAppeal(player.clone(), game_id);
+        assert!(
+            !env.storage().persistent().has(&key),
+            "strike already appealed"
+        );
+
+        let deadline_ledger = env.ledger().sequence() + APPEAL_WINDOW_LEDGERS;
+        let appeal = StrikeAppeal {
+            evidence_hash: evidence_hash.clone(),
+            deadline_ledger,
+            status: AppealStatus::Open,
+        };
+        env.storage().persistent().set(&key, &appeal);
+
+        events::strike_appealed(&env, player, game_id, evidence_hash, deadline_ledger);
+    }
+
+    /// Admin rules on an open appeal before its deadline: `rescind` withdraws the
+    /// strike, otherwise the appeal is dismissed and the strike stands.
+    pub fn decide_appeal(env: Env, player: Address, game_id: u32, rescind: bool) {
+        require_admin(&env);
+
+        let mut appeal = load_appeal(&env, &player, game_id).expect("no appeal");
+        assert!(
+            appeal.status == AppealStatus::Open
+                && env.ledger().sequence() <= appeal.deadline_ledger,
+            "appeal not open"
+        );
+        if rescind {
+            let mut strikes = load_strikes(&env, &player);
+            let index = strikes
+                .iter()
+                .position(|strike| strike.game_id == game_id)
+                .unwrap();
+            strikes.remove(index as u32);
+            env.storage()
+                .persistent()
+                .set(&DataKey::Strikes(player.clone()), &strikes);
+            appeal.status = AppealStatus::Rescinded;
+        } else {
+            appeal.status = AppealStatus::Dismissed;
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::StrikeAppeal(player.clone(), game_id), &appeal);
+
+        events::strike_appeal_decided(&env, player, game_id, appeal.status);
+    }
+
+    /// The appeal of `player`'s strike for `game_id`, if they made one.
+    pub fn strike_appeal(env: Env, player: Address, game_id: u32) -> Option<StrikeAppeal> {
+        load_appeal(&env, &player, game_id)
+    }
+
+    /// Strikes recorded against `player`, oldest first.
+    pub fn player_strikes(env: Env, player: Address) -> Vec<Strike> {
+        load_strikes(&env, &player)
+    }
+
+    /// Whether `player` is currently barred from creating and joining games.
+    pub fn is_player_barred(env: Env, player: Address) -> bool {
+        is_barred(&env, &player)
+    }
+}
@@ -0,0 +1,73 @@
+//! Compact per-game summaries for list views, so lobby and dashboard reads don't pull
+//! full `Game` records with their proofs.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::{away, DataKey, Game, GameAction, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most games summarized by one `game_summaries` call.
+pub const MAX_SUMMARIES: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSummary {
+    pub id: u32,
+    pub player1: Address,
+    /// `None` until an opponent joins
+    pub player2: Option<Address>,
+    pub status: u32,
+    pub phase: Phase,
+    pub turn: u32,
+    /// Ledger of the most recent logged action, or `None` if nothing happened yet
+    pub last_action_ledger: Option<u32>,
+    /// Last ledger each player has marked themselves away for, if they are away
+    pub player1_away_until: Option<u32>,
+    pub player2_away_until: Option<u32>,
+}
+
+/// Summarize a stored game.
+pub(crate) fn summarize(env: &Env, game_id: u32, game: &Game) -> GameSummary {
+    let actions: u32 = env
+        .storage()
+        .persistent()
+        .get(&DataKey::ActionCount(game_id))
+        .unwrap_or(0);
+    let last_action_ledger = actions.checked_sub(1).map(|seq| {
+        let action: GameAction = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Action(game_id, seq))
+            .unwrap();
+        action.ledger
+    });
+
+    let player2 = (game.player2 != game.player1).then(|| game.player2.clone());
+    GameSummary {
+        id: game_id,
+        player1: game.player1.clone(),
+        player1_away_until: away::away_until(env, &game.player1),
+        player2_away_until: player2.as_ref().and_then(|p| away::away_until(env, p)),
+        player2,
+        status: game.status,
+        phase: game.phase,
+        turn: game.turn,
+        last_action_ledger,
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Summaries of the given games, skipping IDs that don't exist.
+    pub fn game_summaries(env: Env, game_ids: Vec<u32>) -> Vec<GameSummary> {
+        assert!(game_ids.len() <= MAX_SUMMARIES, "too many games");
+        let mut summaries = Vec::new(&env);
+        for game_id in game_ids.iter() {
+            let game: Option<Game> = env.storage().persistent().get(&DataKey::Game(game_id));
+            if let Some(game) = game {
+                summaries.push_back(summarize(&env, game_id, &game));
+            }
+        }
+        summaries
+    }
+}
@@ -0,0 +1,123 @@
+//! Sunk-ship disclosure. When a hit sinks a ship, the defender reveals the cells
+//! that made it up, so the shooter's client can mark the water around it and a
+//! finished game can be checked ship by ship rather than only by its final count.
+
+use soroban_sdk::{contractimpl, Address, Bytes, Env, Vec};
+
+use crate::action_log::{self, ActionKind};
+use crate::{placement, Coord, DataKey, Game, Phase};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Cells in the longest ship of any fleet (the carrier).
+pub const MAX_SHIP_CELLS: u32 = 5;
+
+/// Player number (1 or 2) who defended the last shot. Turns have already swapped
+/// after the report, except in hunt mode where the hunter keeps the move.
+fn last_defender(game: &Game) -> u32 {
+    match game.hunter() {
+        Some(hunter) => 3 - hunter,
+        None => game.turn,
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// The defender declares that the hit they just reported sank a ship and
+    /// discloses its `cells`, which must include that hit and lie in one line along a
+    /// heading the rules allow (see `headings`). Accepts a ZK proof that the
+    /// cells form one ship of the committed board, stored for opponent verification.
+    /// Must be called before the shooter's side acts again. In `no_touching` games
+    /// the ship may not touch one disclosed before it, and it may never cross one in
+    /// an X (see `ships_cross`).
+    pub fn report_ship_sunk(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        cells: Vec<Coord>,
+        proof: Bytes,
+    ) {
+        player.require_auth();
+
+        let mut game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(
+            game.phase == Phase::AwaitingShot && game.last_shot_hit,
+            "no hit to report on"
+        );
+        let defender = last_defender(&game);
+        assert!(player == game.player(defender), "wrong player reporting");
+        assert!(
+            !cells.is_empty() && cells.len() <= MAX_SHIP_CELLS,
+            "invalid ship size"
+        );
+
+        let last_shot = Coord {
+            x: game.last_shot_x,
+            y: game.last_shot_y,
+            grid: game.last_shot_grid,
+        };
+        assert!(
+            cells.contains(&last_shot),
+            "ship does not include the last hit"
+        );
+        let grid = game.config.grid;
+        let no_touching = game.config.no_touching;
+        let diagonal_ships = game.config.diagonal_ships;
+        let mut shape = [(0, 0); MAX_SHIP_CELLS as usize];
+        let sunk = if defender == 1 {
+            &mut game.p2_sunk_ships
+        } else {
+            &mut game.p1_sunk_ships
+        };
+        for (i, cell) in cells.iter().enumerate() {
+            assert!(
+                cell.grid == last_shot.grid && grid.contains(cell.x, cell.y),
+                "cell out of bounds"
+            );
+            assert!(
+                cells.first_index_of(&cell) == Some(i as u32),
+                "duplicate cell"
+            );
+            assert!(
+                !sunk.iter().any(|ship| ship.contains(&cell)),
+                "cell already in a sunk ship"
+            );
+            assert!(
+                !no_touching
+                    || !sunk.iter().flatten().any(|other| {
+                        other.grid == cell.grid
+                            && grid.touches((cell.x, cell.y), (other.x, other.y))
+                    }),
+                "ships touch"
+            );
+            shape[i] = (cell.x, cell.y);
+        }
+        let shape = &shape[..cells.len() as usize];
+        assert!(
+            placement::is_ship_line(grid, diagonal_ships, shape),
+            "cells are not one ship"
+        );
+        assert!(
+            !sunk.iter().any(|ship| {
+                let mut other = [(0, 0); MAX_SHIP_CELLS as usize];
+                for (i, cell) in ship.iter().enumerate() {
+                    other[i] = (cell.x, cell.y);
+                }
+                ship.get_unchecked(0).grid == last_shot.grid
+                    && placement::ships_cross(shape, &other[..ship.len() as usize])
+            }),
+            "ships cross"
+        );
+        sunk.push_back(cells);
+
+        game.last_shot_proof = proof;
+        action_log::log_action(&env, game_id, &player, ActionKind::ShipSunk);
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Game(game_id), &game);
+    }
+}
@@ -0,0 +1,59 @@
+//! Batch resolution of timed-out games for keeper bots: stale lobby listings are
+//! expired and games stuck on a missed sonar deadline are awarded to the pinging
+//! player. Expiring a listing earns the caller the bounty owed out of its forfeited
+//! deposit, as `gc` would.
+
+use soroban_sdk::{contractimpl, Address, Env, Map, Vec};
+
+use crate::lobby::{self, Listing};
+use crate::{keeper, sonar_commit, DataKey, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most games one `sweep_timeouts` call looks at.
+pub const MAX_SWEEP: u32 = 50;
+
+/// Resolve the game's timeout if one is due. Returns whether anything happened.
+fn sweep_one(env: &Env, caller: &Address, game_id: u32) -> bool {
+    let listing: Option<Listing> = env.storage().persistent().get(&DataKey::Listing(game_id));
+    if let Some(listing) = listing {
+        if env.ledger().sequence() < listing.expires_ledger {
+            return false;
+        }
+        lobby::expire(env, game_id, listing);
+        return true;
+    }
+
+    let game: Option<Game> = env.storage().persistent().get(&DataKey::Game(game_id));
+    match game {
+        Some(mut game) if sonar_commit::deadline_missed(env, game_id, &game) => {
+            sonar_commit::time_out(env, game_id, &mut game, caller);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Resolve every due timeout among `game_ids`: expire stale listings and award
+    /// games whose defender missed a sonar commit or reveal deadline. Games with
+    /// nothing due are skipped. Anyone may call this; the caller earns the bounty owed
+    /// for each listing expired. Returns the number of games resolved.
+    pub fn sweep_timeouts(env: Env, caller: Address, game_ids: Vec<u32>) -> u32 {
+        caller.require_auth();
+        assert!(game_ids.len() <= MAX_SWEEP, "too many games");
+
+        let mut resolved = 0;
+        let mut owed = Map::new(&env);
+        for game_id in game_ids.iter() {
+            if sweep_one(&env, &caller, game_id) {
+                lobby::take_bounty(&env, game_id, &mut owed);
+                resolved += 1;
+            }
+        }
+
+        let paid = lobby::pay_bounty(&env, &caller, owed);
+        keeper::record(&env, &caller, 0, resolved, paid);
+        resolved
+    }
+}
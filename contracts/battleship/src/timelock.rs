@@ -0,0 +1,162 @@
+//! Time-locked admin changes. Settings that change how games are played or paid for
+//! are proposed, wait out the admin delay in public view, and only then take effect;
+//! the admin may cancel a proposal before executing it. While the delay is zero (the
+//! default) the direct setters keep working; once the admin sets a delay, those
+//! settings can only change through `propose_admin_action`.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Vec};
+
+use crate::error::{ensure, Error, OrPanic};
+use crate::events;
+use crate::{require_admin, DataKey, GameConfig, ListingDeposit};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Most admin actions pending at once.
+pub const MAX_PENDING_ADMIN_ACTIONS: u32 = 10;
+
+/// A setting change that has to wait out the admin delay, holding the new value.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdminAction {
+    /// `set_listing_deposit`
+    ListingDeposit(ListingDeposit),
+    /// `set_max_pause_ledgers`
+    MaxPauseLedgers(u32),
+    /// `set_default_config`
+    DefaultConfig(GameConfig),
+    /// Rotate the game hub. Sessions already started report their result to the new
+    /// hub, so its operator should carry them over during the delay.
+    Hub(Address),
+    /// Change the admin delay itself
+    AdminDelay(u32),
+    /// Hand the admin role to another address, such as a multisig account or contract
+    Admin(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingAdminAction {
+    pub id: u32,
+    pub action: AdminAction,
+    /// First ledger the action can be executed in
+    pub executable_ledger: u32,
+}
+
+fn admin_delay(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::AdminDelay)
+        .unwrap_or(0)
+}
+
+/// Refuse a direct change to a time-locked setting once the admin delay is set.
+pub(crate) fn require_no_delay(env: &Env) {
+    ensure(env, admin_delay(env) == 0, Error::TimeLocked);
+}
+
+fn pending(env: &Env) -> Vec<PendingAdminAction> {
+    env.storage()
+        .instance()
+        .get(&DataKey::PendingAdminActions)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Remove a pending action, returning it.
+fn take(env: &Env, action_id: u32) -> PendingAdminAction {
+    let mut actions = pending(env);
+    let pos = actions
+        .iter()
+        .position(|action| action.id == action_id)
+        .ok_or(Error::AdminActionNotFound)
+        .or_panic(env);
+    let action = actions.get(pos as u32).unwrap();
+    actions.remove(pos as u32);
+    env.storage()
+        .instance()
+        .set(&DataKey::PendingAdminActions, &actions);
+    action
+}
+
+fn apply(env: &Env, action: AdminAction) {
+    let storage = env.storage().instance();
+    match action {
+        AdminAction::ListingDeposit(deposit) => storage.set(&DataKey::ListingDeposit, &deposit),
+        AdminAction::MaxPauseLedgers(ledgers) => storage.set(&DataKey::MaxPauseLedgers, &ledgers),
+        AdminAction::DefaultConfig(config) => storage.set(&DataKey::DefaultConfig, &config),
+        AdminAction::Hub(hub) => storage.set(&DataKey::Hub, &hub),
+        AdminAction::AdminDelay(ledgers) => storage.set(&DataKey::AdminDelay, &ledgers),
+        AdminAction::Admin(admin) => storage.set(&DataKey::Admin, &admin),
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Admin proposes a change, executable after the current admin delay. Returns the
+    /// action id.
+    pub fn propose_admin_action(env: Env, action: AdminAction) -> u32 {
+        require_admin(&env);
+        if let AdminAction::ListingDeposit(deposit) = &action {
+            ensure(&env, deposit.amount >= 0, Error::InvalidDeposit);
+        }
+        let mut actions = pending(&env);
+        let room = actions.len() < MAX_PENDING_ADMIN_ACTIONS;
+        ensure(&env, room, Error::TooManyAdminActions);
+
+        let action_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::AdminActionCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::AdminActionCount, &action_id);
+        let executable_ledger = env.ledger().sequence() + admin_delay(&env);
+        actions.push_back(PendingAdminAction {
+            id: action_id,
+            action: action.clone(),
+            executable_ledger,
+        });
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdminActions, &actions);
+
+        events::admin_action_proposed(&env, action_id, action, executable_ledger);
+        action_id
+    }
+
+    /// Admin applies a proposed change once its delay has passed.
+    pub fn execute_admin_action(env: Env, action_id: u32) {
+        require_admin(&env);
+        let pending = take(&env, action_id);
+        let unlocked = env.ledger().sequence() >= pending.executable_ledger;
+        ensure(&env, unlocked, Error::AdminActionTimeLocked);
+        apply(&env, pending.action);
+        events::admin_action_executed(&env, action_id);
+    }
+
+    /// Admin withdraws a proposed change.
+    pub fn cancel_admin_action(env: Env, action_id: u32) {
+        require_admin(&env);
+        take(&env, action_id);
+        events::admin_action_cancelled(&env, action_id);
+    }
+
+    /// Proposed changes not yet executed or cancelled, oldest first.
+    pub fn pending_admin_actions(env: Env) -> Vec<PendingAdminAction> {
+        pending(&env)
+    }
+
+    /// The address whose authorization admin calls need.
+    pub fn admin(env: Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .expect("not initialized")
+    }
+
+    /// Ledgers a proposed change waits before it can be executed.
+    pub fn admin_delay(env: Env) -> u32 {
+        admin_delay(&env)
+    }
+}
@@ -0,0 +1,158 @@
+//! The contract's own funds, per token. Today the only inflow is forfeited listing
+//! deposits; they pay the `gc` and `sweep_timeouts` bounties, and the admin may
+//! withdraw the rest through a time-locked request that players can see coming.
+//! Every movement is published as an event, so the balance can be audited from the
+//! event stream alone. Listing deposits still held for open listings and quest pools
+//! are not treasury funds.
+
+use soroban_sdk::{contractimpl, contracttype, token, Address, Env};
+
+use crate::error::{ensure, Error, OrPanic};
+use crate::events;
+use crate::{require_admin, DataKey};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+/// Ledgers between requesting a withdrawal and being able to execute it (about three
+/// days at 5s per ledger).
+pub const WITHDRAWAL_DELAY_LEDGERS: u32 = 51_840;
+
+/// Where treasury funds came from.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Inflow {
+    /// A stale listing was expired and its deposit kept
+    ForfeitedDeposit,
+}
+
+/// Where treasury funds went.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outflow {
+    /// Bounty for removing or resolving games with `gc` or `sweep_timeouts`
+    Bounty,
+    /// An executed admin withdrawal
+    Withdrawal,
+}
+
+/// An admin withdrawal waiting out its delay.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Withdrawal {
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    /// First ledger the withdrawal can be executed in
+    pub executable_ledger: u32,
+}
+
+/// The treasury balance in `token`.
+pub(crate) fn balance(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TreasuryBalance(token.clone()))
+        .unwrap_or(0)
+}
+
+fn set_balance(env: &Env, token: &Address, balance: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::TreasuryBalance(token.clone()), &balance);
+}
+
+/// Record funds the contract already holds as treasury funds.
+pub(crate) fn credit(env: &Env, token: &Address, source: Inflow, amount: i128) {
+    set_balance(env, token, balance(env, token) + amount);
+    events::treasury_inflow(env, token.clone(), source, amount);
+}
+
+/// Pay `amount` of treasury funds to `to`. The caller checks the balance covers it.
+pub(crate) fn pay(env: &Env, token: &Address, to: &Address, reason: Outflow, amount: i128) {
+    set_balance(env, token, balance(env, token) - amount);
+    token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+    events::treasury_outflow(env, token.clone(), to.clone(), reason, amount);
+}
+
+fn load_withdrawal(env: &Env, withdrawal_id: u32) -> Withdrawal {
+    env.storage()
+        .persistent()
+        .get(&DataKey::TreasuryWithdrawal(withdrawal_id))
+        .ok_or(Error::WithdrawalNotFound)
+        .or_panic(env)
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Treasury funds held in `token`.
+    pub fn treasury_balance(env: Env, token: Address) -> i128 {
+        balance(&env, &token)
+    }
+
+    /// Admin requests a withdrawal of treasury funds, executable after
+    /// `WITHDRAWAL_DELAY_LEDGERS`. Returns the withdrawal id.
+    pub fn request_withdrawal(env: Env, token: Address, to: Address, amount: i128) -> u32 {
+        require_admin(&env);
+        let valid = amount > 0 && amount <= balance(&env, &token);
+        ensure(&env, valid, Error::InvalidAmount);
+
+        let withdrawal_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TreasuryWithdrawalCount)
+            .unwrap_or(0)
+            + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::TreasuryWithdrawalCount, &withdrawal_id);
+        let executable_ledger = env.ledger().sequence() + WITHDRAWAL_DELAY_LEDGERS;
+        let withdrawal = Withdrawal {
+            token: token.clone(),
+            to: to.clone(),
+            amount,
+            executable_ledger,
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::TreasuryWithdrawal(withdrawal_id), &withdrawal);
+
+        events::withdrawal_requested(&env, withdrawal_id, token, to, amount, executable_ledger);
+        withdrawal_id
+    }
+
+    /// Admin executes a requested withdrawal once its delay has passed.
+    pub fn execute_withdrawal(env: Env, withdrawal_id: u32) {
+        require_admin(&env);
+        let withdrawal = load_withdrawal(&env, withdrawal_id);
+        let unlocked = env.ledger().sequence() >= withdrawal.executable_ledger;
+        ensure(&env, unlocked, Error::WithdrawalTimeLocked);
+        // Bounties paid during the delay may have drawn the balance down
+        let covered = withdrawal.amount <= balance(&env, &withdrawal.token);
+        ensure(&env, covered, Error::InsufficientTreasury);
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TreasuryWithdrawal(withdrawal_id));
+        pay(
+            &env,
+            &withdrawal.token,
+            &withdrawal.to,
+            Outflow::Withdrawal,
+            withdrawal.amount,
+        );
+    }
+
+    /// Admin cancels a requested withdrawal.
+    pub fn cancel_withdrawal(env: Env, withdrawal_id: u32) {
+        require_admin(&env);
+        load_withdrawal(&env, withdrawal_id);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::TreasuryWithdrawal(withdrawal_id));
+    }
+
+    /// A requested withdrawal that has been neither executed nor cancelled.
+    pub fn pending_withdrawal(env: Env, withdrawal_id: u32) -> Option<Withdrawal> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TreasuryWithdrawal(withdrawal_id))
+    }
+}
@@ -0,0 +1,95 @@
+//! Battleship on the shared turn engine (`crates/turn-engine`). A shot or sonar scan
+//! is the action and the defender's report the response. The `Game` record keeps its
+//! own fields, so the engine's view of the turn is rebuilt from them for each step;
+//! the deadline of a pending sonar answer lives with the commit–reveal state and is
+//! passed in where it matters.
+
+use soroban_sdk::Address;
+use turn_engine::{Pending, Seat, TurnError, TurnRules, TurnState};
+
+use crate::{Error, Game, Phase};
+
+/// What the defender owes a report on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Move {
+    Shot,
+    /// A sonar ping, line sonar or scout flight
+    Scan,
+}
+
+struct Rules {
+    /// The hunter keeps the move for every action of a hunt game
+    hunt: bool,
+}
+
+impl TurnRules for Rules {
+    type Action = Move;
+    type Response = ();
+
+    fn keeps_move(&self, _action: &Move, _response: &()) -> bool {
+        self.hunt
+    }
+}
+
+impl Game {
+    /// The turn as the engine sees it, with `deadline` for the pending response.
+    pub(crate) fn turns(&self, deadline: Option<u32>) -> TurnState<Move> {
+        let action = match self.phase {
+            Phase::AwaitingReport => Some(Move::Shot),
+            Phase::AwaitingSonarReport => Some(Move::Scan),
+            _ => None,
+        };
+        TurnState {
+            to_move: Seat::from_num(self.turn),
+            pending: action.map(|action| Pending { action, deadline }),
+        }
+    }
+
+    /// The seat `player` fills, resolving a player seated on both sides to `prefer`.
+    pub(crate) fn seat_of(&self, player: &Address, prefer: Seat) -> Seat {
+        if *player == self.player(prefer.num()) {
+            prefer
+        } else {
+            prefer.other()
+        }
+    }
+
+    /// Checks that `player` is the one to move.
+    pub(crate) fn check_turn(&self, player: &Address) -> Result<(), Error> {
+        let turns = self.turns(None);
+        if self.seat_of(player, turns.to_move) == turns.to_move {
+            Ok(())
+        } else {
+            Err(Error::NotYourTurn)
+        }
+    }
+
+    /// Checks that `player` is the defender who owes a report (the opponent of the
+    /// player to move).
+    pub(crate) fn check_defender(&self, player: &Address) -> Result<(), Error> {
+        let turns = self.turns(None);
+        match turns.check_respond(self.seat_of(player, turns.responder())) {
+            Ok(_) => Ok(()),
+            Err(TurnError::NothingToRespond) => Err(Error::NoShotToReport),
+            Err(_) => Err(Error::WrongPlayerReporting),
+        }
+    }
+
+    /// Hand the move over once the pending action has been answered, or pass it on
+    /// when nothing was waiting for an answer. In hunt mode the hunter keeps the move.
+    pub(crate) fn pass_move(&mut self) {
+        let rules = Rules {
+            hunt: self.config.hunt().is_some(),
+        };
+        let mut turns = self.turns(None);
+        let (mover, responder) = (turns.to_move, turns.responder());
+        let handed_over = match turns.pending {
+            Some(_) => turns.respond(&rules, responder, &()).map(|_| ()),
+            None if rules.hunt => Ok(()),
+            None => turns.pass(mover),
+        };
+        // Both steps are taken by the seats the engine expects
+        handed_over.expect("turn steps out of order");
+        self.turn = turns.to_move.num();
+    }
+}
@@ -0,0 +1,157 @@
+//! Tutorial games: a guided first game against a built-in scripted opponent. The script's
+//! fleet and shot sequence are fixed in the contract, and the contract's own address
+//! stands in as player 2. Shots at the script are resolved on the spot and the script
+//! answers immediately, so the new player only ever shoots and reports.
+
+use soroban_sdk::{contractimpl, Address, Bytes, BytesN, Env};
+
+use crate::action_log::{log_action, ActionKind};
+use crate::{end_game, heatmap, limits, DataKey, EndReason, FleetGrid, Game, GameConfig};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{Phase, Winner};
+
+/// The script's fleet on the standard 10x10 board: carrier, battleship, cruiser,
+/// submarine, destroyer.
+const SCRIPT_FLEET: [(u32, u32); 17] = [
+    (1, 1),
+    (2, 1),
+    (3, 1),
+    (4, 1),
+    (5, 1),
+    (8, 2),
+    (8, 3),
+    (8, 4),
+    (8, 5),
+    (2, 4),
+    (3, 4),
+    (4, 4),
+    (5, 6),
+    (5, 7),
+    (5, 8),
+    (0, 8),
+    (1, 8),
+];
+
+/// True if the game's opponent is the built-in script.
+pub(crate) fn is_scripted(env: &Env, game: &Game) -> bool {
+    game.player2 == env.current_contract_address()
+}
+
+fn fleet_has(x: u32, y: u32) -> bool {
+    SCRIPT_FLEET.contains(&(x, y))
+}
+
+/// The script's `n`th shot: a checkerboard sweep of the board (every other cell, row by
+/// row), then the remaining cells. Repeats after 100 shots.
+fn script_shot(n: u32) -> (u32, u32) {
+    let n = n % 100;
+    let parity = n / 50;
+    let y = (n % 50) / 5;
+    let x = (n % 5) * 2 + (y + parity) % 2;
+    (x, y)
+}
+
+/// Resolve the player's pending shot against the script's fleet, then fire the script's
+/// reply. Ends the game if the shot sank the last ship cell.
+pub(crate) fn answer_shot(env: &Env, game_id: u32, game: &mut Game) {
+    let hit = fleet_has(game.last_shot_x, game.last_shot_y);
+    game.p1_shots += 1;
+    if hit {
+        game.p1_hits += 1;
+    }
+    game.last_shot_hit = hit;
+    heatmap::record(env, game_id, game, hit);
+    let script = env.current_contract_address();
+    log_action(env, game_id, &script, ActionKind::Report(hit));
+
+    if game.p1_hits >= game.fleet_cells() {
+        end_game(
+            env,
+            game_id,
+            game,
+            Some(Winner::Player1),
+            EndReason::AllShipsSunk,
+        );
+        return;
+    }
+    game.end_turn();
+    fire_next(env, game_id, game);
+}
+
+/// Count the script's ship cells under the player's pending sonar, then fire the
+/// script's reply.
+pub(crate) fn answer_sonar(env: &Env, game_id: u32, game: &mut Game) {
+    let (cx, cy) = (game.sonar_center_x, game.sonar_center_y);
+    game.last_sonar_count = SCRIPT_FLEET
+        .iter()
+        .filter(|(x, y)| x.abs_diff(cx) <= 1 && y.abs_diff(cy) <= 1)
+        .count() as u32;
+    let script = env.current_contract_address();
+    log_action(
+        env,
+        game_id,
+        &script,
+        ActionKind::SonarReport(game.last_sonar_count),
+    );
+    game.end_turn();
+    fire_next(env, game_id, game);
+}
+
+/// After the player reports on the script's shot: the script wins once it has hit
+/// every cell of the player's fleet.
+pub(crate) fn after_report(env: &Env, game_id: u32, game: &mut Game) {
+    if game.p2_hits >= game.fleet_cells() {
+        end_game(
+            env,
+            game_id,
+            game,
+            Some(Winner::Player2),
+            EndReason::AllShipsSunk,
+        );
+    }
+}
+
+fn fire_next(env: &Env, game_id: u32, game: &mut Game) {
+    let (x, y) = script_shot(game.p2_shots_taken);
+    game.last_shot_x = x;
+    game.last_shot_y = y;
+    game.last_shot_grid = FleetGrid::Home;
+    game.p2_shots_taken += 1;
+    game.set_phase(Phase::AwaitingReport);
+    let script = env.current_contract_address();
+    log_action(
+        env,
+        game_id,
+        &script,
+        ActionKind::Shot(x, y, FleetGrid::Home),
+    );
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Start a tutorial game against the built-in script. The script's board is already
+    /// committed; the game starts once the player commits theirs. The hub is never notified.
+    pub fn new_tutorial_game(env: Env, player: Address) -> u32 {
+        let game_id =
+            Self::new_game_with_config(env.clone(), player.clone(), GameConfig::default());
+        // The script is already seated, so this never counts as an open game
+        limits::release_open_game(&env, &player);
+
+        let key = DataKey::Game(game_id);
+        let mut game: Game = env.storage().persistent().get(&key).unwrap();
+        let mut fleet = Bytes::new(&env);
+        for (x, y) in SCRIPT_FLEET {
+            fleet.push_back(x as u8);
+            fleet.push_back(y as u8);
+        }
+        let board_hash: BytesN<32> = env.crypto().sha256(&fleet).into();
+
+        game.player2 = env.current_contract_address();
+        game.board_hash2 = board_hash;
+        game.boards_committed = 1;
+        game.set_phase(Phase::AwaitingCommit);
+        env.storage().persistent().set(&key, &game);
+
+        game_id
+    }
+}
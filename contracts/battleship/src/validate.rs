@@ -0,0 +1,170 @@
+//! Dry-run views for the core game calls. Each `can_*` view runs the same checks
+//! as its entry point against current state and returns the error the call would
+//! fail with, without changing anything.
+
+use soroban_sdk::{contractimpl, Address, BytesN, Env};
+
+use crate::features::{self, FEATURE_SONAR};
+use crate::pause_level::{self, PAUSE_ALL, PAUSE_NEW_GAMES};
+use crate::{club, club_match, schedule, strikes, DataKey, Error, FleetGrid, Game};
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+
+fn load_game(env: &Env, game_id: u32) -> Result<Game, Error> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Game(game_id))
+        .ok_or(Error::GameNotFound)
+}
+
+/// All checks for `join_game`, including club and club-match membership.
+pub(crate) fn check_join(
+    env: &Env,
+    game_id: u32,
+    game: &Game,
+    player2: &Address,
+) -> Result<(), Error> {
+    pause_level::check(env, PAUSE_NEW_GAMES)?;
+    game.check_join(player2)?;
+    if strikes::is_barred(env, player2) {
+        return Err(Error::PlayerBarred);
+    }
+    if let Some(club_id) = club::club_of(env, game_id) {
+        if !club::is_member(env, club_id, player2) {
+            return Err(Error::NotClubMember);
+        }
+    }
+    if !club_match::may_join(env, game_id, player2) {
+        return Err(Error::NotClubMember);
+    }
+    schedule::check_join(env, game_id, player2)
+}
+
+/// All checks for `commit_board`, including a scheduled match's start. Returns which
+/// board (1 or 2) the commitment is for.
+pub(crate) fn check_commit(
+    env: &Env,
+    game_id: u32,
+    game: &Game,
+    player: &Address,
+) -> Result<u32, Error> {
+    pause_level::check(env, PAUSE_ALL)?;
+    let board = game.check_commit(env, player)?;
+    schedule::check_commit(env, game_id)?;
+    Ok(board)
+}
+
+/// Checks on the hash committed for `board`: the all-zero hash is the sentinel for
+/// a board not yet committed, and a copy of the other board's commitment is not a
+/// board the committer could open.
+pub(crate) fn check_board_hash(
+    env: &Env,
+    game: &Game,
+    board: u32,
+    board_hash: &BytesN<32>,
+) -> Result<(), Error> {
+    if *board_hash == BytesN::from_array(env, &[0u8; 32]) {
+        return Err(Error::ZeroBoardHash);
+    }
+    let other = if board == 1 {
+        &game.board_hash2
+    } else {
+        &game.board_hash1
+    };
+    if board_hash == other {
+        return Err(Error::DuplicateBoardHash);
+    }
+    Ok(())
+}
+
+/// All checks for `cancel_game`, including a confirmed scheduled match.
+pub(crate) fn check_cancel(
+    env: &Env,
+    game_id: u32,
+    game: &Game,
+    player: &Address,
+) -> Result<(), Error> {
+    game.check_cancel(player)?;
+    schedule::check_cancel(env, game_id)
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Whether `join_game(game_id, player2)` would succeed.
+    pub fn can_join_game(env: Env, game_id: u32, player2: Address) -> Result<(), Error> {
+        let game = load_game(&env, game_id)?;
+        check_join(&env, game_id, &game, &player2)
+    }
+
+    /// Whether `cancel_game(game_id, player)` would succeed.
+    pub fn can_cancel_game(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        let game = load_game(&env, game_id)?;
+        check_cancel(&env, game_id, &game, &player)
+    }
+
+    /// Whether `commit_board(game_id, player, _)` would succeed.
+    pub fn can_commit_board(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        let game = load_game(&env, game_id)?;
+        check_commit(&env, game_id, &game, &player).map(|_| ())
+    }
+
+    /// Whether `take_shot(game_id, player, x, y, grid, _)` would succeed.
+    pub fn can_take_shot(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        x: u32,
+        y: u32,
+        grid: FleetGrid,
+    ) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        load_game(&env, game_id)?.check_shot(&player, x, y, grid)
+    }
+
+    /// Whether `report_result(game_id, player, ..)` would succeed.
+    pub fn can_report_result(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        load_game(&env, game_id)?.check_report(&player)
+    }
+
+    /// Whether `use_sonar(game_id, player, center_x, center_y, _)` would succeed.
+    pub fn can_use_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        center_x: u32,
+        center_y: u32,
+    ) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        features::check(&env, FEATURE_SONAR)?;
+        load_game(&env, game_id)?.check_sonar(&player, center_x, center_y)
+    }
+
+    /// Whether `use_line_sonar(game_id, player, column, _)` would succeed.
+    pub fn can_use_line_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        column: u32,
+    ) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        features::check(&env, FEATURE_SONAR)?;
+        load_game(&env, game_id)?.check_line_sonar(&player, column)
+    }
+
+    /// Whether `report_sonar(game_id, player, count, ..)` would succeed.
+    pub fn can_report_sonar(
+        env: Env,
+        game_id: u32,
+        player: Address,
+        count: u32,
+    ) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        load_game(&env, game_id)?.check_sonar_report(&player, count)
+    }
+
+    /// Whether `claim_victory(game_id, player)` would succeed.
+    pub fn can_claim_victory(env: Env, game_id: u32, player: Address) -> Result<(), Error> {
+        pause_level::check(&env, PAUSE_ALL)?;
+        load_game(&env, game_id)?.check_claim(&player).map(|_| ())
+    }
+}
@@ -0,0 +1,74 @@
+//! Observer subscriptions. Spectators, coaches and tournament admins can watch a
+//! game; every action logged in it is then also published as a `GameNotice` with the
+//! observer's address as a topic, so push infrastructure can filter on that topic
+//! instead of scanning every event the contract emits.
+
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+use crate::action_log::ActionKind;
+use crate::events;
+use crate::{BattleshipContract, BattleshipContractArgs, BattleshipContractClient};
+use crate::{DataKey, Game, Phase};
+
+/// Most observers one game can have.
+pub const MAX_OBSERVERS: u32 = 8;
+
+fn observers(env: &Env, game_id: u32) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Observers(game_id))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Publish a notice of a logged action to each of the game's observers.
+pub(crate) fn notify(env: &Env, game_id: u32, actor: &Address, kind: &ActionKind) {
+    for observer in observers(env, game_id).iter() {
+        events::game_notice(env, observer, game_id, actor.clone(), kind.clone());
+    }
+}
+
+#[contractimpl]
+impl BattleshipContract {
+    /// Subscribe `observer` to notices for a game that has not finished yet.
+    /// Watching a game twice is a no-op.
+    pub fn watch_game(env: Env, game_id: u32, observer: Address) {
+        observer.require_auth();
+
+        let game: Game = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Game(game_id))
+            .expect("game not found");
+        assert!(
+            !matches!(game.phase, Phase::Completed(_) | Phase::Cancelled),
+            "game is over"
+        );
+        let mut list = observers(&env, game_id);
+        if list.contains(&observer) {
+            return;
+        }
+        assert!(list.len() < MAX_OBSERVERS, "too many observers");
+        list.push_back(observer);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Observers(game_id), &list);
+    }
+
+    /// Stop sending `observer` notices for a game.
+    pub fn unwatch_game(env: Env, game_id: u32, observer: Address) {
+        observer.require_auth();
+
+        let mut list = observers(&env, game_id);
+        if let Some(i) = list.first_index_of(&observer) {
+            list.remove(i);
+            env.storage()
+                .persistent()
+                .set(&DataKey::Observers(game_id), &list);
+        }
+    }
+
+    /// Addresses watching a game, in the order they subscribed.
+    pub fn game_observers(env: Env, game_id: u32) -> Vec<Address> {
+        observers(&env, game_id)
+    }
+}
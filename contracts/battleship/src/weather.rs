@@ -0,0 +1,46 @@
+//! Chaos weather (`GameConfig::chaos_weather`). Every `WEATHER_INTERVAL`th round the
+//! contract draws a weather event with `env.prng()` that holds for that round: in a
+//! storm every shot misses whatever the defender reports, and under clear skies sonar
+//! spends no charge. Other rounds are calm. A round is one turn by each player.
+
+use soroban_sdk::{contracttype, Env};
+
+use crate::{events, Game};
+
+/// Rounds between weather draws; round `WEATHER_INTERVAL` is the first.
+pub const WEATHER_INTERVAL: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Weather {
+    Calm,
+    /// Shots this round miss
+    Storm,
+    /// Sonar this round does not use up the player's charge
+    ClearSkies,
+}
+
+/// Set the weather once a turn ends, drawing an event if it starts a weather round.
+pub(crate) fn after_turn(env: &Env, game_id: u32, game: &mut Game) {
+    if !game.config.chaos_weather {
+        return;
+    }
+    let p1_turns = game.p1_shots_taken + game.p1_abilities_used;
+    let p2_turns = game.p2_shots_taken + game.p2_abilities_used;
+    // Mid-round: the second player has yet to move
+    if p1_turns != p2_turns {
+        return;
+    }
+
+    let round = p1_turns + 1;
+    game.weather = if !round.is_multiple_of(WEATHER_INTERVAL) {
+        Weather::Calm
+    } else if env.prng().gen_range::<u64>(0..=1) == 0 {
+        Weather::Storm
+    } else {
+        Weather::ClearSkies
+    };
+    if game.weather != Weather::Calm {
+        events::weather_changed(env, game_id, round, game.weather);
+    }
+}
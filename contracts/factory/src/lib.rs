@@ -0,0 +1,307 @@
+#![no_std]
+//! Deploys battleship instances for leagues and communities. Each instance is its own
+//! contract with its own games, game counter, admin and hub, so communities stay
+//! isolated; the factory keeps a registry of what it deployed for frontends to list.
+//!
+//! The battleship Wasm must be uploaded first and its hash given to the factory. The
+//! factory talks to instances through the few entry points it needs and passes the
+//! league's rules through untouched, so it does not depend on the battleship crate.
+//!
+//! `aggregate_stats` sums instance counters for ecosystem dashboards. Battleship holds
+//! no stakes, only small listing deposits in whichever token each instance chose, so
+//! there is no common value-locked figure to add up.
+
+use soroban_sdk::{
+    contract, contractclient, contractevent, contractimpl, contracttype, Address, BytesN, Env,
+    String, Val, Vec,
+};
+
+/// Longest instance name accepted, in bytes.
+pub const MAX_NAME_LEN: u32 = 32;
+
+/// Most instances returned by one `instances` call.
+pub const MAX_INSTANCES_PER_PAGE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Admin,
+    BattleshipWasm,
+    InstanceCount,
+    Instance(u32),
+}
+
+/// A deployed battleship instance.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+    pub address: Address,
+    pub name: String,
+    /// The instance's admin
+    pub owner: Address,
+    /// The game hub the instance reports to
+    pub hub: Address,
+    pub created_ledger: u32,
+}
+
+/// An instance's game counters, as returned by its `instance_stats`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InstanceStats {
+    pub game_count: u32,
+    pub active_games: u32,
+}
+
+/// Game counters summed over a range of instances.
+#[contracttype]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AggregateStats {
+    /// Instances included in the sums
+    pub instances: u32,
+    pub game_count: u64,
+    pub active_games: u64,
+}
+
+/// A new battleship instance was deployed.
+#[contractevent]
+pub struct InstanceDeployed {
+    #[topic]
+    pub instance_id: u32,
+    pub address: Address,
+    pub owner: Address,
+}
+
+/// The parts of the battleship contract the factory calls.
+#[allow(dead_code)]
+#[contractclient(name = "BattleshipClient")]
+trait Battleship {
+    fn initialize(env: Env, hub: Address, admin: Address);
+    /// Takes a `GameConfig`
+    fn set_default_config(env: Env, config: Val);
+    fn instance_stats(env: Env) -> InstanceStats;
+}
+
+fn require_admin(env: &Env) {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("not initialized");
+    admin.require_auth();
+}
+
+fn instance_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::InstanceCount)
+        .unwrap_or(0)
+}
+
+#[contract]
+pub struct FactoryContract;
+
+#[contractimpl]
+impl FactoryContract {
+    /// Initialize the factory with its admin and the hash of the uploaded battleship
+    /// Wasm.
+    pub fn initialize(env: Env, admin: Address, battleship_wasm: BytesN<32>) {
+        assert!(
+            !env.storage().instance().has(&DataKey::Admin),
+            "already initialized"
+        );
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::BattleshipWasm, &battleship_wasm);
+    }
+
+    /// Admin points new deployments at another uploaded battleship Wasm. Instances
+    /// already deployed are not upgraded.
+    pub fn set_battleship_wasm(env: Env, battleship_wasm: BytesN<32>) {
+        require_admin(&env);
+        env.storage()
+            .instance()
+            .set(&DataKey::BattleshipWasm, &battleship_wasm);
+    }
+
+    /// Hash of the battleship Wasm new instances are deployed from.
+    pub fn battleship_wasm(env: Env) -> BytesN<32> {
+        env.storage()
+            .instance()
+            .get(&DataKey::BattleshipWasm)
+            .expect("not initialized")
+    }
+
+    /// Deploy a battleship instance owned by `owner`, reporting to `hub`, whose
+    /// `new_game` plays by `config` (a battleship `GameConfig`). Returns the
+    /// instance id.
+    pub fn deploy_instance(
+        env: Env,
+        owner: Address,
+        name: String,
+        hub: Address,
+        config: Val,
+    ) -> u32 {
+        owner.require_auth();
+        assert!(name.len() <= MAX_NAME_LEN, "name too long");
+
+        let instance_id = instance_count(&env) + 1;
+        let mut salt = [0u8; 32];
+        salt[28..].copy_from_slice(&instance_id.to_be_bytes());
+        let address = env
+            .deployer()
+            .with_current_contract(salt)
+            .deploy_v2(Self::battleship_wasm(env.clone()), ());
+        let battleship = BattleshipClient::new(&env, &address);
+        battleship.initialize(&hub, &owner);
+        battleship.set_default_config(&config);
+
+        let instance = Instance {
+            address: address.clone(),
+            name,
+            owner: owner.clone(),
+            hub,
+            created_ledger: env.ledger().sequence(),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::Instance(instance_id), &instance);
+        env.storage()
+            .instance()
+            .set(&DataKey::InstanceCount, &instance_id);
+
+        InstanceDeployed {
+            instance_id,
+            address,
+            owner,
+        }
+        .publish(&env);
+        instance_id
+    }
+
+    /// Number of instances deployed; ids run from 1 to this.
+    pub fn instance_count(env: Env) -> u32 {
+        instance_count(&env)
+    }
+
+    pub fn instance(env: Env, instance_id: u32) -> Option<Instance> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Instance(instance_id))
+    }
+
+    /// Up to `limit` instances starting at id `start`, in deployment order.
+    pub fn instances(env: Env, start: u32, limit: u32) -> Vec<Instance> {
+        let limit = limit.min(MAX_INSTANCES_PER_PAGE);
+        let end = instance_count(&env).min(start.saturating_add(limit).saturating_sub(1));
+        let mut instances = Vec::new(&env);
+        for instance_id in start.max(1)..=end {
+            let instance: Instance = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Instance(instance_id))
+                .unwrap();
+            instances.push_back(instance);
+        }
+        instances
+    }
+
+    /// Game counters summed over up to `limit` instances starting at id `start`.
+    /// Dashboards page through all instances and add the pages up; each instance
+    /// costs one cross-contract call.
+    pub fn aggregate_stats(env: Env, start: u32, limit: u32) -> AggregateStats {
+        let mut totals = AggregateStats::default();
+        for instance in Self::instances(env.clone(), start, limit).iter() {
+            let stats = BattleshipClient::new(&env, &instance.address).instance_stats();
+            totals.instances += 1;
+            totals.game_count += stats.game_count as u64;
+            totals.active_games += stats.active_games as u64;
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_battleship_wasm_admin() {
+        let env = Env::default();
+        let contract_id = env.register(FactoryContract, ());
+        let client = FactoryContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let wasm = BytesN::from_array(&env, &[1u8; 32]);
+        client.initialize(&admin, &wasm);
+        assert!(client.try_initialize(&admin, &wasm).is_err());
+        assert_eq!(client.battleship_wasm(), wasm);
+
+        // Only the admin repoints deployments
+        env.mock_all_auths();
+        let newer = BytesN::from_array(&env, &[2u8; 32]);
+        client.set_battleship_wasm(&newer);
+        assert_eq!(env.auths()[0].0, admin);
+        assert_eq!(client.battleship_wasm(), newer);
+
+        assert_eq!(client.instance_count(), 0);
+        assert!(client.instances(&0, &10).is_empty());
+        assert_eq!(client.instance(&1), None);
+    }
+
+    #[contract]
+    pub struct MockInstance;
+
+    #[contractimpl]
+    impl MockInstance {
+        pub fn instance_stats(_env: Env) -> InstanceStats {
+            InstanceStats {
+                game_count: 7,
+                active_games: 2,
+            }
+        }
+    }
+
+    #[test]
+    fn test_aggregate_stats() {
+        let env = Env::default();
+        let contract_id = env.register(FactoryContract, ());
+        let client = FactoryContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &BytesN::from_array(&env, &[1u8; 32]),
+        );
+
+        // Register stand-in instances directly, since deploying needs the real Wasm
+        for instance_id in 1..=3u32 {
+            let instance = Instance {
+                address: env.register(MockInstance, ()),
+                name: String::from_str(&env, "league"),
+                owner: Address::generate(&env),
+                hub: Address::generate(&env),
+                created_ledger: 0,
+            };
+            env.as_contract(&contract_id, || {
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Instance(instance_id), &instance);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::InstanceCount, &instance_id);
+            });
+        }
+
+        assert_eq!(
+            client.aggregate_stats(&1, &10),
+            AggregateStats {
+                instances: 3,
+                game_count: 21,
+                active_games: 6,
+            }
+        );
+        assert_eq!(client.aggregate_stats(&3, &10).instances, 1);
+        assert_eq!(client.aggregate_stats(&4, &10), AggregateStats::default());
+    }
+}
@@ -0,0 +1,237 @@
+//! Generates the TypeScript client packages for the workspace contracts.
+//!
+//! For each contract this runs `stellar contract bindings typescript` on its release
+//! Wasm, then post-processes the output into an npm-ready package: the package is
+//! named `@dark-fleet/<contract>` and versioned with the contract crate, and the
+//! generated sources are checked to still export the types the frontend relies on,
+//! so a renamed or dropped `contracttype` fails here instead of in the browser.
+//!
+//! Usage (from the workspace root, after `cargo build --target wasm32v1-none --release`):
+//!
+//! ```text
+//! cargo run -p dark-fleet-bindgen -- [--out bindings] [--stellar stellar]
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+/// A contract in the workspace that gets a client package.
+struct Contract {
+    /// Package suffix and output directory
+    name: &'static str,
+    /// Crate directory, relative to the workspace root
+    crate_dir: &'static str,
+    /// File name of the release Wasm
+    wasm: &'static str,
+    /// Exported types clients depend on; generation fails if one goes missing
+    types: &'static [&'static str],
+    /// Union enums (`{ tag, values }` in TypeScript) that also get a `<Name>Tag` alias
+    tagged: &'static [&'static str],
+}
+
+const CONTRACTS: &[Contract] = &[
+    Contract {
+        name: "battleship",
+        crate_dir: "contracts/battleship",
+        wasm: "zk_battleship.wasm",
+        types: &[
+            "Coord",
+            "GameConfig",
+            "Phase",
+            "Game",
+            "GameSummary",
+            "Deadlines",
+            "Capabilities",
+            "InstanceStats",
+        ],
+        tagged: &["Phase", "FleetGrid", "Winner", "EndReason", "GameMode"],
+    },
+    Contract {
+        name: "factory",
+        crate_dir: "contracts/factory",
+        wasm: "dark_fleet_factory.wasm",
+        types: &["Instance", "AggregateStats"],
+        tagged: &[],
+    },
+];
+
+const WASM_DIR: &str = "target/wasm32v1-none/release";
+
+struct Options {
+    out: PathBuf,
+    stellar: String,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        out: PathBuf::from("bindings"),
+        stellar: String::from("stellar"),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or(format!("{arg} needs a value"))?;
+        match arg.as_str() {
+            "--out" => options.out = PathBuf::from(value),
+            "--stellar" => options.stellar = value,
+            _ => return Err(format!("unknown option {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+/// The `version` of a crate's `[package]` section.
+fn crate_version(manifest: &str) -> Option<&str> {
+    manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[package]")
+        .take_while(|line| !line.starts_with('[') || line.trim() == "[package]")
+        .find_map(|line| line.trim().strip_prefix("version"))
+        .and_then(|rest| rest.trim().strip_prefix('='))
+        .map(|value| value.trim().trim_matches('"'))
+}
+
+/// Replace the value of a top-level string field in a package.json.
+fn set_json_field(json: &str, field: &str, value: &str) -> Result<String, String> {
+    let key = format!("\"{field}\":");
+    let start = json
+        .find(&key)
+        .ok_or(format!("package.json has no {field}"))?
+        + key.len();
+    let open = start + json[start..].find('"').ok_or("malformed package.json")?;
+    let close = open + 1 + json[open + 1..].find('"').ok_or("malformed package.json")?;
+    Ok(format!(
+        "{}\"{value}\"{}",
+        &json[..open],
+        &json[close + 1..]
+    ))
+}
+
+/// Whether the generated sources export a type called `name`.
+fn exports_type(source: &str, name: &str) -> bool {
+    [
+        format!("export interface {name} "),
+        format!("export type {name} "),
+        format!("export enum {name} "),
+    ]
+    .iter()
+    .any(|declaration| source.contains(declaration.as_str()))
+}
+
+/// Check the expected types are exported and append the tag aliases.
+fn post_process_source(contract: &Contract, source: &str) -> Result<String, String> {
+    let missing: Vec<&str> = (contract.types.iter().chain(contract.tagged))
+        .copied()
+        .filter(|name| !exports_type(source, name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "{}: generated bindings are missing {}",
+            contract.name,
+            missing.join(", ")
+        ));
+    }
+
+    let mut source = source.to_string();
+    source.push_str("\n// Added by dark-fleet-bindgen\n");
+    for name in contract.tagged {
+        source.push_str(&format!("export type {name}Tag = {name}[\"tag\"];\n"));
+    }
+    Ok(source)
+}
+
+fn generate(contract: &Contract, options: &Options) -> Result<(), String> {
+    let wasm = Path::new(WASM_DIR).join(contract.wasm);
+    if !wasm.exists() {
+        return Err(format!(
+            "{} not found; build the contracts with `cargo build --target wasm32v1-none --release`",
+            wasm.display()
+        ));
+    }
+    let out = options.out.join(contract.name);
+    let status = Command::new(&options.stellar)
+        .args([
+            "contract",
+            "bindings",
+            "typescript",
+            "--overwrite",
+            "--wasm",
+        ])
+        .arg(&wasm)
+        .arg("--output-dir")
+        .arg(&out)
+        .status()
+        .map_err(|e| format!("could not run {}: {e}", options.stellar))?;
+    if !status.success() {
+        return Err(format!("{}: binding generation failed", contract.name));
+    }
+
+    let read =
+        |path: &Path| fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()));
+    let manifest = read(&Path::new(contract.crate_dir).join("Cargo.toml"))?;
+    let version = crate_version(&manifest).ok_or("contract crate has no version")?;
+    let package_path = out.join("package.json");
+    let package = read(&package_path)?;
+    let package = set_json_field(&package, "name", &format!("@dark-fleet/{}", contract.name))?;
+    let package = set_json_field(&package, "version", version)?;
+
+    let source_path = out.join("src").join("index.ts");
+    let source = post_process_source(contract, &read(&source_path)?)?;
+
+    let write = |path: &Path, data: &str| {
+        fs::write(path, data).map_err(|e| format!("{}: {e}", path.display()))
+    };
+    write(&package_path, &package)?;
+    write(&source_path, &source)?;
+    println!("{} -> {}", contract.name, out.display());
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args().and_then(|options| {
+        CONTRACTS
+            .iter()
+            .try_for_each(|contract| generate(contract, &options))
+    });
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_package_metadata() {
+        let manifest = "[package]\nname = \"zk-battleship\"\nversion = \"0.1.0\"\n\n[lib]\n";
+        assert_eq!(crate_version(manifest), Some("0.1.0"));
+        assert_eq!(crate_version("[lib]\nversion = \"1\"\n"), None);
+
+        let json = "{\n  \"version\": \"0.0.0\",\n  \"name\": \"battleship\"\n}";
+        let json = set_json_field(json, "name", "@dark-fleet/battleship").unwrap();
+        let json = set_json_field(&json, "version", "0.1.0").unwrap();
+        assert_eq!(
+            json,
+            "{\n  \"version\": \"0.1.0\",\n  \"name\": \"@dark-fleet/battleship\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_missing_types_fail_generation() {
+        let contract = Contract {
+            name: "battleship",
+            crate_dir: "",
+            wasm: "",
+            types: &["Coord"],
+            tagged: &["Phase"],
+        };
+        let source = "export interface Coord {\n}\nexport type Phase = {tag: \"Setup\"};\n";
+        let processed = post_process_source(&contract, source).unwrap();
+        assert!(processed.ends_with("export type PhaseTag = Phase[\"tag\"];\n"));
+
+        let err = post_process_source(&contract, "export interface Coord {\n}\n").unwrap_err();
+        assert!(err.contains("missing Phase"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Human battleship notation. A cell is written as its column letter and its row
+//! number from 1, so `B7` is column 1, row 6 in contract coordinates. Columns past
+//! `Z` continue as `AA`, `AB`, ... like spreadsheet columns, so the notation also
+//! covers boards larger than 26 cells a side.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A cell in contract coordinates: `x` is the column and `y` the row, both from 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Why a cell could not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseCoordError {
+    /// No column letters at the start
+    MissingColumn,
+    /// No row number after the column, or not a number from 1
+    BadRow,
+    /// Well formed, but outside the board
+    OffBoard,
+}
+
+impl fmt::Display for ParseCoordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ParseCoordError::MissingColumn => "cell must start with a column letter",
+            ParseCoordError::BadRow => "cell needs a row number from 1",
+            ParseCoordError::OffBoard => "cell is off the board",
+        })
+    }
+}
+
+impl std::error::Error for ParseCoordError {}
+
+impl Coord {
+    pub fn new(x: u32, y: u32) -> Self {
+        Coord { x, y }
+    }
+
+    /// Read a cell like `B7` or `aa12`. Letters may be either case and surrounding
+    /// whitespace is ignored; nothing is known about the board yet, so use
+    /// `parse_on` to also check the cell is on it.
+    pub fn parse(text: &str) -> Result<Self, ParseCoordError> {
+        let text = text.trim();
+        let split = text
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(text.len());
+        let (letters, digits) = text.split_at(split);
+        if letters.is_empty() {
+            return Err(ParseCoordError::MissingColumn);
+        }
+        // Bijective base 26: A = 1 ... Z = 26, AA = 27
+        let x = letters.bytes().try_fold(0u32, |column, letter| {
+            let digit = u32::from(letter.to_ascii_uppercase() - b'A') + 1;
+            column.checked_mul(26)?.checked_add(digit)
+        });
+        let x = x.ok_or(ParseCoordError::MissingColumn)? - 1;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseCoordError::BadRow);
+        }
+        let y = digits
+            .parse::<u32>()
+            .ok()
+            .and_then(|row| row.checked_sub(1))
+            .ok_or(ParseCoordError::BadRow)?;
+        Ok(Coord { x, y })
+    }
+
+    /// `parse`, then check the cell is on a `size` by `size` board.
+    pub fn parse_on(text: &str, size: u32) -> Result<Self, ParseCoordError> {
+        let coord = Self::parse(text)?;
+        if coord.on_board(size) {
+            Ok(coord)
+        } else {
+            Err(ParseCoordError::OffBoard)
+        }
+    }
+
+    /// Whether the cell is on a `size` by `size` board.
+    pub fn on_board(&self, size: u32) -> bool {
+        self.x < size && self.y < size
+    }
+
+    /// The letters naming column `x`.
+    pub fn column_name(x: u32) -> String {
+        let mut letters = Vec::new();
+        let mut rest = u64::from(x) + 1;
+        while rest > 0 {
+            rest -= 1;
+            letters.push(b'A' + (rest % 26) as u8);
+            rest /= 26;
+        }
+        letters.iter().rev().map(|&b| char::from(b)).collect()
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", Self::column_name(self.x), self.y + 1)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
@@ -0,0 +1,100 @@
+//! Helpers for Rust clients of the battleship contract: bots, the simulator and
+//! other tooling. Keeping them here means every client reads and writes board
+//! positions the same way.
+
+mod coord;
+mod render;
+
+pub use coord::{Coord, ParseCoordError};
+pub use render::{render_grid, render_replay, side_by_side, Mark, Style};
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{Address, Env};
+    use zk_battleship::{ActionKind, BattleshipContract, BattleshipContractClient};
+    use zk_battleship::{FleetGrid, GameAction, Grid};
+
+    #[test]
+    fn test_coord_notation() {
+        assert_eq!(Coord::parse("B7"), Ok(Coord::new(1, 6)));
+        assert_eq!(" j10 ".parse(), Ok(Coord::new(9, 9)));
+        assert_eq!(Coord::parse("AA1"), Ok(Coord::new(26, 0)));
+        assert_eq!(Coord::parse("7"), Err(ParseCoordError::MissingColumn));
+        assert_eq!(Coord::parse("A0"), Err(ParseCoordError::BadRow));
+        assert_eq!(Coord::parse("A1x"), Err(ParseCoordError::BadRow));
+        assert_eq!(Coord::parse("A+1"), Err(ParseCoordError::BadRow));
+        assert_eq!(Coord::parse_on("K1", 10), Err(ParseCoordError::OffBoard));
+        assert_eq!(Coord::parse_on("K1", 12), Ok(Coord::new(10, 0)));
+
+        // Names round-trip, including past Z
+        for (x, name) in [
+            (0, "A"),
+            (25, "Z"),
+            (26, "AA"),
+            (51, "AZ"),
+            (52, "BA"),
+            (701, "ZZ"),
+        ] {
+            assert_eq!(Coord::column_name(x), name);
+            let coord = Coord::new(x, 11);
+            assert_eq!(coord.to_string().parse(), Ok(coord));
+        }
+    }
+
+    #[test]
+    fn test_render_replay() {
+        let lines = render_grid(Grid::Square, Style::Plain, |coord| {
+            match (coord.x, coord.y) {
+                (0, 0) => Mark::Ship,
+                (1, 0) => Mark::Hit,
+                (0, 1) => Mark::Miss,
+                _ => Mark::Empty,
+            }
+        });
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "    ABCDEFGHIJ");
+        assert_eq!(lines[1], "  1 #X........");
+        assert_eq!(lines[2], "  2 o.........");
+        assert_eq!(lines[10], " 10 ..........");
+        let coloured = render_grid(Grid::Square, Style::Ansi, |_| Mark::Hit);
+        assert!(coloured[1].starts_with("  1 \x1b[31mX\x1b[0m"));
+        // Colour codes take no room when lining grids up
+        assert!(side_by_side(&coloured, &lines).starts_with("    ABCDEFGHIJ  "));
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = BattleshipContractClient::new(&env, &env.register(BattleshipContract, ()));
+        let (p1, p2) = (Address::generate(&env), Address::generate(&env));
+        let game_id = client.new_game(&p1, &None);
+        client.join_game(&game_id, &p2);
+        let game = client.get_game(&game_id);
+
+        let action = |actor: &Address, kind| GameAction {
+            actor: actor.clone(),
+            kind,
+            ledger: 0,
+        };
+        let actions = [
+            action(&p1, ActionKind::Shot(1, 6, FleetGrid::Home)),
+            action(&p2, ActionKind::Report(true)),
+            action(&p2, ActionKind::Shot(9, 9, FleetGrid::Home)),
+            action(&p1, ActionKind::Report(false)),
+            action(&p2, ActionKind::Sonar(4, 4)),
+            action(&p1, ActionKind::SonarReport(2)),
+            action(&Address::generate(&env), ActionKind::SonarTimeout),
+        ];
+        let replay = render_replay(&game, &actions, Style::Plain);
+        let lines: Vec<&str> = replay.lines().collect();
+        assert_eq!(lines[0], "   1  P1  fires at B7");
+        assert_eq!(lines[1], "   2  P2  reports a hit");
+        assert_eq!(lines[3], "   4  P1  reports a miss");
+        assert_eq!(lines[4], "   5  P2  pings sonar at E5");
+        assert_eq!(lines[5], "   6  P1  reports 2 ship cells");
+        assert_eq!(lines[6], "   7  --  SonarTimeout");
+        assert_eq!(lines[8], "P1 shots        P2 shots");
+        assert_eq!(lines[16], "  7 .X........    7 ..........");
+        assert_eq!(lines[19], " 10 ..........   10 .........o");
+    }
+}
@@ -0,0 +1,174 @@
+//! ASCII rendering of boards, shot maps and whole games, for terminals and for test
+//! failure messages. Cells are drawn as `.` (nothing known or open water), `#` (a
+//! ship), `X` (a hit) and `o` (a miss); with `Style::Ansi`, hits and misses are also
+//! coloured. Cells outside a hex board are left blank.
+
+use zk_battleship::{ActionKind, FleetGrid, Game, GameAction, Grid};
+
+use crate::Coord;
+
+/// Whether to add ANSI colour codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Ansi,
+}
+
+/// What to draw in a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mark {
+    Empty,
+    Ship,
+    Hit,
+    Miss,
+}
+
+impl Mark {
+    fn symbol(self) -> char {
+        match self {
+            Mark::Empty => '.',
+            Mark::Ship => '#',
+            Mark::Hit => 'X',
+            Mark::Miss => 'o',
+        }
+    }
+
+    fn paint(self, style: Style) -> String {
+        let colour = match (style, self) {
+            (Style::Ansi, Mark::Hit) => "31",
+            (Style::Ansi, Mark::Miss) => "34",
+            _ => return self.symbol().to_string(),
+        };
+        format!("\x1b[{colour}m{}\x1b[0m", self.symbol())
+    }
+}
+
+/// Draw `grid` with column names along the top and row numbers from 1 down the side,
+/// one string per line. `mark` is asked about every cell on the board.
+pub fn render_grid(grid: Grid, style: Style, mark: impl Fn(Coord) -> Mark) -> Vec<String> {
+    let size = grid.width();
+    // Boards wider than 26 columns need two-letter names, so cells get spaced out
+    let cell_width = Coord::column_name(size - 1).len();
+    let sep = if cell_width > 1 { " " } else { "" };
+
+    let names: Vec<String> = (0..size)
+        .map(|x| format!("{:<cell_width$}", Coord::column_name(x)))
+        .collect();
+    let mut lines = vec![format!("    {}", names.join(sep))];
+    for y in 0..size {
+        let cells: Vec<String> = (0..size)
+            .map(|x| {
+                let cell = if grid.contains(x, y) {
+                    mark(Coord::new(x, y)).paint(style)
+                } else {
+                    String::from(" ")
+                };
+                format!("{cell}{}", " ".repeat(cell_width - 1))
+            })
+            .collect();
+        lines.push(format!("{:>3} {}", y + 1, cells.join(sep)));
+    }
+    lines
+}
+
+/// Length of `line` as shown, not counting ANSI escape sequences.
+fn visible_len(line: &str) -> usize {
+    let mut len = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        match (in_escape, c) {
+            (false, '\x1b') => in_escape = true,
+            (false, _) => len += 1,
+            (true, 'm') => in_escape = false,
+            (true, _) => {}
+        }
+    }
+    len
+}
+
+/// Put two rendered grids next to each other, two spaces apart.
+pub fn side_by_side(left: &[String], right: &[String]) -> String {
+    let width = left.iter().map(|line| visible_len(line)).max().unwrap_or(0);
+    let rows = left.len().max(right.len());
+    let mut out = String::new();
+    for i in 0..rows {
+        let l = left.get(i).map_or("", String::as_str);
+        let r = right.get(i).map_or("", String::as_str);
+        let pad = " ".repeat(width - visible_len(l) + 2);
+        out.push_str(format!("{l}{pad}{r}").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// A game's action log (from `game_actions`) replayed as one numbered line per
+/// action, followed by the shots each player fired at the other's home grid.
+/// Actions by anyone but the two players (the hub, a keeper) are labelled by kind only.
+pub fn render_replay(game: &Game, actions: &[GameAction], style: Style) -> String {
+    let mut shots: [Vec<(Coord, bool)>; 2] = [Vec::new(), Vec::new()];
+    // The shot waiting for its report: shooter and cell, if on the home grid
+    let mut pending: Option<(usize, Option<Coord>)> = None;
+    let mut out = String::new();
+
+    for (seq, action) in actions.iter().enumerate() {
+        let seat = if action.actor == game.player1 {
+            Some(0)
+        } else if action.actor == game.player2 {
+            Some(1)
+        } else {
+            None
+        };
+        let what = match &action.kind {
+            ActionKind::Shot(x, y, grid) => {
+                let coord = Coord::new(*x, *y);
+                let home = *grid == FleetGrid::Home;
+                pending = seat.map(|seat| (seat, home.then_some(coord)));
+                if home {
+                    format!("fires at {coord}")
+                } else {
+                    format!("fires at {coord} on the forward grid")
+                }
+            }
+            ActionKind::Report(hit) => {
+                if let Some((shooter, Some(coord))) = pending.take() {
+                    shots[shooter].push((coord, *hit));
+                }
+                String::from(if *hit {
+                    "reports a hit"
+                } else {
+                    "reports a miss"
+                })
+            }
+            ActionKind::ReportHidden => {
+                pending = None;
+                String::from("reports (outcome hidden)")
+            }
+            ActionKind::Sonar(x, y) => format!("pings sonar at {}", Coord::new(*x, *y)),
+            ActionKind::LineSonar(x) => format!("pings column {}", Coord::column_name(*x)),
+            ActionKind::Scout(x, y) => format!("scouts from {}", Coord::new(*x, *y)),
+            ActionKind::SonarReport(count) => format!("reports {count} ship cells"),
+            kind => format!("{kind:?}"),
+        };
+        let who = match seat {
+            Some(seat) => format!("P{}", seat + 1),
+            None => String::from("--"),
+        };
+        out.push_str(&format!("{:>4}  {who}  {what}\n", seq + 1));
+    }
+
+    let grid = game.config.grid;
+    let maps = shots.clone().map(|fired| {
+        render_grid(grid, style, |coord| {
+            match fired.iter().find(|(cell, _)| *cell == coord) {
+                Some((_, true)) => Mark::Hit,
+                Some((_, false)) => Mark::Miss,
+                None => Mark::Empty,
+            }
+        })
+    });
+    out.push_str("\nP1 shots");
+    out.push_str(&" ".repeat(visible_len(&maps[0][0]) + 2 - "P1 shots".len()));
+    out.push_str("P2 shots\n");
+    out.push_str(&side_by_side(&maps[0], &maps[1]));
+    out
+}
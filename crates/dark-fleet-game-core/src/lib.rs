@@ -0,0 +1,229 @@
+//! Game hub plumbing shared by the workspace's game contracts.
+//!
+//! A game contract reports each session it runs to the game hub: `start_game` when
+//! play begins and an end call once it is decided. Hubs speak one of three interface
+//! versions. Legacy hubs take a six-argument `start_game`; version 2 hubs also take a
+//! `HubStartInfo` describing the session; version 3 hubs are told how a session ended
+//! through `end_game_with_outcome`, which can report draws and voids as well as wins,
+//! while older hubs only get `end_game(session_id, player1_won)`.
+//!
+//! A contract plugs in by implementing `HubReportable`, which only asks where to keep
+//! each hub's interface version; the calls themselves are provided. Session ids are
+//! the game's own and only need to be unique within the contract.
+
+#![no_std]
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, InvokeError, Symbol, Val, Vec};
+
+/// Six-argument `start_game(game, session_id, player1, player2, p1_points, p2_points)`.
+pub const HUB_INTERFACE_V1: u32 = 1;
+/// `start_game` with a trailing `HubStartInfo` argument.
+pub const HUB_INTERFACE_V2: u32 = 2;
+/// Version 2 plus `end_game_with_outcome(session_id, outcome)` in place of `end_game`.
+pub const HUB_INTERFACE_V3: u32 = 3;
+
+/// Extra session details sent to version 2 hubs when a session starts.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct HubStartInfo {
+    /// sha256 of the rules the session is played under, in whatever encoding the game
+    /// fixes them
+    pub config_digest: BytesN<32>,
+    /// Who moves first, or `None` when the session is not a single game
+    pub first_mover: Option<Address>,
+}
+
+/// How a session ended, as reported to version 3 hubs.
+#[contracttype]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HubOutcome {
+    Player1Won,
+    Player2Won,
+    /// Neither side won
+    Draw,
+    /// The session was voided after review and should be treated as not played
+    Voided,
+}
+
+impl HubOutcome {
+    pub fn win(player1_won: bool) -> Self {
+        if player1_won {
+            HubOutcome::Player1Won
+        } else {
+            HubOutcome::Player2Won
+        }
+    }
+}
+
+/// A session being reported to the hub as started.
+#[derive(Clone, Debug)]
+pub struct SessionStart {
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    /// Points (stakes) each player puts on the session; zero for unstaked games
+    pub player1_points: i128,
+    pub player2_points: i128,
+    pub info: HubStartInfo,
+}
+
+/// Ask the hub which interface it speaks, capped at the newest one known here. Hubs
+/// that don't answer are treated as legacy.
+pub fn probe_version(env: &Env, hub: &Address) -> u32 {
+    let probe = Symbol::new(env, "hub_interface_version");
+    match env.try_invoke_contract::<u32, InvokeError>(hub, &probe, Vec::new(env)) {
+        Ok(Ok(version)) => version.clamp(HUB_INTERFACE_V1, HUB_INTERFACE_V3),
+        _ => HUB_INTERFACE_V1,
+    }
+}
+
+/// A game contract that reports its sessions to the game hub.
+pub trait HubReportable {
+    /// The interface version remembered for `hub`, if any.
+    fn stored_hub_version(env: &Env, hub: &Address) -> Option<u32>;
+
+    /// Remember the interface version to use with `hub`.
+    fn store_hub_version(env: &Env, hub: &Address, version: u32);
+
+    /// The interface version to use with `hub`, probing and remembering it if unknown.
+    fn hub_version(env: &Env, hub: &Address) -> u32 {
+        Self::stored_hub_version(env, hub).unwrap_or_else(|| {
+            let version = probe_version(env, hub);
+            Self::store_hub_version(env, hub, version);
+            version
+        })
+    }
+
+    /// Tell the hub a session has started.
+    fn notify_hub_start(env: &Env, hub: &Address, session: SessionStart) {
+        let mut args: Vec<Val> = Vec::from_array(
+            env,
+            [
+                env.current_contract_address().into_val(env),
+                session.session_id.into_val(env),
+                session.player1.into_val(env),
+                session.player2.into_val(env),
+                session.player1_points.into_val(env),
+                session.player2_points.into_val(env),
+            ],
+        );
+        if Self::hub_version(env, hub) >= HUB_INTERFACE_V2 {
+            args.push_back(session.info.into_val(env));
+        }
+        env.invoke_contract::<Val>(hub, &Symbol::new(env, "start_game"), args);
+    }
+
+    /// Tell the hub how a session it was told about has ended. Older hubs only know
+    /// wins: a draw reaches them as a player 2 win and a void is not sent at all.
+    fn notify_hub_end(env: &Env, hub: &Address, session_id: u32, outcome: HubOutcome) {
+        if Self::hub_version(env, hub) >= HUB_INTERFACE_V3 {
+            let args: Vec<Val> =
+                Vec::from_array(env, [session_id.into_val(env), outcome.into_val(env)]);
+            env.invoke_contract::<Val>(hub, &Symbol::new(env, "end_game_with_outcome"), args);
+            return;
+        }
+        let player1_won = match outcome {
+            HubOutcome::Player1Won => true,
+            HubOutcome::Player2Won | HubOutcome::Draw => false,
+            HubOutcome::Voided => return,
+        };
+        let args: Vec<Val> =
+            Vec::from_array(env, [session_id.into_val(env), player1_won.into_val(env)]);
+        env.invoke_contract::<Val>(hub, &Symbol::new(env, "end_game"), args);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{contract, contractimpl};
+
+    /// Minimal game that keeps hub versions in instance storage.
+    #[contract]
+    struct Game;
+
+    impl HubReportable for Game {
+        fn stored_hub_version(env: &Env, hub: &Address) -> Option<u32> {
+            env.storage().instance().get(hub)
+        }
+
+        fn store_hub_version(env: &Env, hub: &Address, version: u32) {
+            env.storage().instance().set(hub, &version);
+        }
+    }
+
+    #[contractimpl]
+    impl Game {
+        pub fn end(env: Env, hub: Address, session_id: u32, outcome: HubOutcome) {
+            Self::notify_hub_end(&env, &hub, session_id, outcome);
+        }
+
+        pub fn version(env: Env, hub: Address) -> u32 {
+            Self::hub_version(&env, &hub)
+        }
+    }
+
+    /// Legacy hub recording `player1_won` per session.
+    #[contract]
+    struct LegacyHub;
+
+    #[contractimpl]
+    impl LegacyHub {
+        pub fn end_game(env: Env, session_id: u32, player1_won: bool) {
+            env.storage().instance().set(&session_id, &player1_won);
+        }
+
+        pub fn result(env: Env, session_id: u32) -> Option<bool> {
+            env.storage().instance().get(&session_id)
+        }
+    }
+
+    /// Version 3 hub recording the outcome per session.
+    #[contract]
+    struct OutcomeHub;
+
+    #[contractimpl]
+    impl OutcomeHub {
+        pub fn end_game_with_outcome(env: Env, session_id: u32, outcome: HubOutcome) {
+            env.storage().instance().set(&session_id, &outcome);
+        }
+
+        pub fn hub_interface_version() -> u32 {
+            7
+        }
+
+        pub fn result(env: Env, session_id: u32) -> Option<HubOutcome> {
+            env.storage().instance().get(&session_id)
+        }
+    }
+
+    #[test]
+    fn test_end_notifications_by_version() {
+        let env = Env::default();
+        let game = GameClient::new(&env, &env.register(Game, ()));
+
+        let legacy = env.register(LegacyHub, ());
+        let legacy_client = LegacyHubClient::new(&env, &legacy);
+        assert_eq!(game.version(&legacy), HUB_INTERFACE_V1);
+        game.end(&legacy, &1, &HubOutcome::Player1Won);
+        game.end(&legacy, &2, &HubOutcome::Draw);
+        game.end(&legacy, &3, &HubOutcome::Voided);
+        assert_eq!(legacy_client.result(&1), Some(true));
+        assert_eq!(legacy_client.result(&2), Some(false));
+        assert_eq!(legacy_client.result(&3), None);
+
+        // Newer versions than known here are capped
+        let hub = env.register(OutcomeHub, ());
+        let hub_client = OutcomeHubClient::new(&env, &hub);
+        assert_eq!(game.version(&hub), HUB_INTERFACE_V3);
+        game.end(&hub, &1, &HubOutcome::Draw);
+        game.end(&hub, &2, &HubOutcome::Voided);
+        assert_eq!(hub_client.result(&1), Some(HubOutcome::Draw));
+        assert_eq!(hub_client.result(&2), Some(HubOutcome::Voided));
+
+        assert_eq!(game.version(&Address::generate(&env)), HUB_INTERFACE_V1);
+    }
+}
@@ -0,0 +1,72 @@
+//! Headless batch mode: many games between two computer strategies under the same
+//! rules, summarized as win rates and game length, for tuning rule changes with data
+//! instead of intuition.
+
+use zk_battleship::GameConfig;
+
+use crate::game::Match;
+use crate::rng::Rng;
+use crate::strategy;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub games: u32,
+    /// Games won by player 1 and player 2
+    pub wins: [u32; 2],
+    /// Games won by whichever player moved first
+    pub first_mover_wins: u32,
+    /// Shots fired by both players, over all games
+    pub total_shots: u32,
+}
+
+impl Summary {
+    fn percent(&self, count: u32) -> f64 {
+        100.0 * count as f64 / self.games.max(1) as f64
+    }
+
+    pub fn print(&self, names: [&str; 2]) {
+        println!("games              {}", self.games);
+        for (i, name) in names.iter().enumerate() {
+            let wins = self.wins[i];
+            println!(
+                "player {} ({name:>6}) {wins} wins, {:.1}%",
+                i + 1,
+                self.percent(wins)
+            );
+        }
+        println!(
+            "first mover        {:.1}% wins",
+            self.percent(self.first_mover_wins)
+        );
+        let length = self.total_shots as f64 / self.games.max(1) as f64;
+        println!("average length     {length:.1} shots");
+    }
+}
+
+/// Play `games` games of `names[0]` against `names[1]`. Game `i` is seeded with
+/// `seed + i`, so any single game can be replayed interactively.
+pub fn run(config: &GameConfig, names: [&str; 2], games: u32, seed: u64) -> Summary {
+    let mut summary = Summary::default();
+    for i in 0..games {
+        let mut rng = Rng::new(seed.wrapping_add(i as u64));
+        let mut game = Match::new(config, &mut rng);
+        let mut strategies = names.map(|name| strategy::by_name(name).expect("unknown strategy"));
+        let first_mover = game.turn();
+        let winner = loop {
+            if let Some(winner) = game.winner() {
+                break winner;
+            }
+            let index = game.turn();
+            let (x, y) = strategies[index].next_shot(&game.tracking[index], &mut rng);
+            game.shoot(x, y).expect("strategy picked an illegal shot");
+        };
+
+        summary.games += 1;
+        summary.wins[winner] += 1;
+        if winner == first_mover {
+            summary.first_mover_wins += 1;
+        }
+        summary.total_shots += game.shots(0) + game.shots(1);
+    }
+    summary
+}
@@ -0,0 +1,114 @@
+//! Ship layouts on the classic 10x10 board. The contract only ever sees a board's
+//! hash, so the simulator keeps the real layout and answers reports from it the way
+//! an honest client would.
+
+use zk_battleship::{headings, ship_cell, ships_cross, GameConfig, Grid};
+
+use crate::rng::Rng;
+
+pub const SIZE: u32 = 10;
+
+/// Ship lengths of the standard fleet (17 cells).
+pub const FLEET: [u32; 5] = [5, 4, 3, 3, 2];
+
+pub struct Board {
+    /// Index into `FLEET` of the ship on each cell, by `[y][x]`
+    cells: [[Option<usize>; SIZE as usize]; SIZE as usize],
+}
+
+impl Board {
+    pub fn empty() -> Self {
+        Board {
+            cells: [[None; SIZE as usize]; SIZE as usize],
+        }
+    }
+
+    /// Place the standard fleet at random under `config`'s placement rules: without
+    /// overlaps, along the headings the rules allow and, under `no_touching`, without
+    /// ships touching.
+    pub fn random(rng: &mut Rng, config: &GameConfig) -> Self {
+        let mut board = Board::empty();
+        let headings = headings(Grid::Square, config.diagonal_ships);
+        for (ship, &len) in FLEET.iter().enumerate() {
+            loop {
+                let heading = headings[rng.below(headings.len() as u32) as usize];
+                // Starting cells from which the whole ship stays on the board
+                let starts = |step: i32| if step == 0 { SIZE } else { SIZE - len + 1 };
+                let x = rng.below(starts(heading.0));
+                let y = rng.below(starts(heading.1)) + if heading.1 < 0 { len - 1 } else { 0 };
+                let cells: Vec<(u32, u32)> = (0..len)
+                    .filter_map(|i| ship_cell((x, y), heading, i))
+                    .collect();
+                if board.can_place(&cells, config.no_touching) {
+                    board.place(ship, &cells);
+                    break;
+                }
+            }
+        }
+        board
+    }
+
+    pub fn ship_at(&self, x: u32, y: u32) -> Option<usize> {
+        self.cells[y as usize][x as usize]
+    }
+
+    /// Whether a new ship may go on `cells`: on the board, clear of other ships, not
+    /// crossing one in an X and, under `no_touching`, not next to one, diagonals
+    /// included. This is the rule the board proof enforces, so a layout that passes
+    /// here commits cleanly.
+    pub fn can_place(&self, cells: &[(u32, u32)], no_touching: bool) -> bool {
+        let on_board = cells.iter().all(|&(x, y)| x < SIZE && y < SIZE);
+        on_board
+            && cells.iter().all(|&(x, y)| {
+                self.ship_at(x, y).is_none() && !(no_touching && self.ships_around(x, y) > 0)
+            })
+            && !(0..FLEET.len()).any(|ship| ships_cross(cells, &self.cells_of(ship)))
+    }
+
+    /// Put `ship`, an index into `FLEET`, on `cells`.
+    pub fn place(&mut self, ship: usize, cells: &[(u32, u32)]) {
+        for &(x, y) in cells {
+            self.cells[y as usize][x as usize] = Some(ship);
+        }
+    }
+
+    /// Cells of `ship`, an index into `FLEET`; empty until it is placed.
+    pub fn cells_of(&self, ship: usize) -> Vec<(u32, u32)> {
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.ship_at(x, y) == Some(ship))
+            .collect()
+    }
+
+    /// Ship cells in the 3x3 area around `(x, y)`.
+    fn ships_around(&self, x: u32, y: u32) -> u32 {
+        let mut count = 0;
+        for cy in y.saturating_sub(1)..=(y + 1).min(SIZE - 1) {
+            for cx in x.saturating_sub(1)..=(x + 1).min(SIZE - 1) {
+                if self.ship_at(cx, cy).is_some() {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Ship cells in the 3x3 area around `(x, y)`, as a sonar report counts them.
+    pub fn sonar_count(&self, x: u32, y: u32) -> u32 {
+        self.ships_around(x, y)
+    }
+
+    /// A stand-in commitment: one byte per cell, distinct for distinct layouts.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hash = [0u8; 32];
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                if self.ship_at(x, y).is_some() {
+                    let i = (y * SIZE + x) as usize;
+                    hash[i % 32] = hash[i % 32].wrapping_mul(31).wrapping_add(i as u8 + 1);
+                }
+            }
+        }
+        hash
+    }
+}
@@ -0,0 +1,155 @@
+//! One game played against a locally registered contract. Both boards live here, and
+//! every shot and sonar ping goes through the contract exactly as a client would send
+//! it, with the defender's report answered from their real board.
+
+use dark_fleet_client::{render_replay, Style};
+use soroban_sdk::testutils::{Address as _, EnvTestConfig};
+use soroban_sdk::{Address, Bytes, BytesN, Env};
+use zk_battleship::{BattleshipContract, BattleshipContractClient};
+use zk_battleship::{Error, FleetGrid, Game, GameAction, GameConfig, Phase, Winner};
+
+use crate::board::{Board, FLEET};
+use crate::rng::Rng;
+use crate::strategy::Tracking;
+
+pub struct Match {
+    client: BattleshipContractClient<'static>,
+    game_id: u32,
+    players: [Address; 2],
+    pub boards: [Board; 2],
+    /// What each player has learned about the other's board
+    pub tracking: [Tracking; 2],
+    /// The game record as of the last move, so reading it costs no contract call
+    state: Game,
+}
+
+/// Turn a `try_can_*` result into the typed check error.
+fn check<T, C>(result: Result<T, Result<Error, C>>) -> Result<(), Error> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(Ok(e)) => Err(e),
+        Err(Err(_)) => panic!("contract call failed"),
+    }
+}
+
+impl Match {
+    /// Register the contract, create a game with `config` and commit random boards.
+    pub fn new(config: &GameConfig, rng: &mut Rng) -> Self {
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        env.mock_all_auths();
+        let contract_id = env.register(BattleshipContract, ());
+        let client = BattleshipContractClient::new(&env, &contract_id);
+        let players = [Address::generate(&env), Address::generate(&env)];
+        let boards = [Board::random(rng, config), Board::random(rng, config)];
+
+        let game_id = client.new_game_with_config(&players[0], config);
+        client.join_game(&game_id, &players[1]);
+        for (player, board) in players.iter().zip(&boards) {
+            let hash = BytesN::from_array(&env, &board.commitment());
+            client.commit_board(&game_id, player, &hash);
+        }
+        Match {
+            state: client.get_game(&game_id),
+            client,
+            game_id,
+            players,
+            boards,
+            tracking: [Tracking::default(), Tracking::default()],
+        }
+    }
+
+    /// Index (0 or 1) of the player to move.
+    pub fn turn(&self) -> usize {
+        self.state.turn as usize - 1
+    }
+
+    /// Index of the winner once the game is over.
+    pub fn winner(&self) -> Option<usize> {
+        match self.state.phase {
+            Phase::Completed(Winner::Player1) => Some(0),
+            Phase::Completed(Winner::Player2) => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Hits player `index` has landed.
+    pub fn hits(&self, index: usize) -> u32 {
+        if index == 0 {
+            self.state.p1_hits
+        } else {
+            self.state.p2_hits
+        }
+    }
+
+    /// Shots player `index` has fired.
+    pub fn shots(&self, index: usize) -> u32 {
+        if index == 0 {
+            self.state.p1_shots
+        } else {
+            self.state.p2_shots
+        }
+    }
+
+    /// The player to move fires at `(x, y)`; the defender reports honestly, and a
+    /// shot that sinks the last ship claims the win. Returns whether it hit.
+    pub fn shoot(&mut self, x: u32, y: u32) -> Result<bool, Error> {
+        let shooter = self.turn();
+        let defender = 1 - shooter;
+        let (player, id) = (&self.players[shooter], &self.game_id);
+        // Only ask the view why when the shot is refused
+        let grid = FleetGrid::Home;
+        if self
+            .client
+            .try_take_shot(id, player, &x, &y, &grid, &None)
+            .is_err()
+        {
+            check(self.client.try_can_take_shot(id, player, &x, &y, &grid))?;
+            panic!("take_shot refused a shot can_take_shot allows");
+        }
+
+        let hit = self.boards[defender].ship_at(x, y).is_some();
+        let proof = Bytes::new(&self.client.env);
+        let reporter = &self.players[defender];
+        self.client.report_result(id, reporter, &hit, &proof, &None);
+        self.tracking[shooter].record(x, y, hit);
+        self.state = self.client.get_game(id);
+        if self.hits(shooter) == FLEET.iter().sum::<u32>() {
+            self.client.claim_victory(id, player);
+            self.state = self.client.get_game(id);
+        }
+        Ok(hit)
+    }
+
+    /// The game so far as an action log and both shot maps, read back from the
+    /// contract the way any client would replay it.
+    pub fn replay(&self, style: Style) -> String {
+        let mut actions: Vec<GameAction> = Vec::new();
+        let count = self.client.action_count(&self.game_id);
+        while (actions.len() as u32) < count {
+            let page = self
+                .client
+                .game_actions(&self.game_id, &(actions.len() as u32), &count);
+            actions.extend(page.iter());
+        }
+        render_replay(&self.state, &actions, style)
+    }
+
+    /// The player to move pings sonar around `(x, y)`; returns the reported count.
+    pub fn sonar(&mut self, x: u32, y: u32) -> Result<u32, Error> {
+        let shooter = self.turn();
+        let defender = 1 - shooter;
+        let (player, id) = (&self.players[shooter], &self.game_id);
+        check(self.client.try_can_use_sonar(id, player, &x, &y))?;
+        self.client.use_sonar(id, player, &x, &y, &None);
+
+        let count = self.boards[defender].sonar_count(x, y);
+        let proof = Bytes::new(&self.client.env);
+        let reporter = &self.players[defender];
+        self.client
+            .report_sonar(id, reporter, &count, &proof, &None);
+        self.state = self.client.get_game(id);
+        Ok(count)
+    }
+}
@@ -0,0 +1,328 @@
+//! Terminal playtesting against a local copy of the contract. Two humans share the
+//! terminal, or a human plays a computer strategy (or two strategies play each
+//! other). Every move runs through the real contract in a test `Env`, so rule and
+//! ability changes can be tried out before a testnet deployment.
+//!
+//! ```text
+//! cargo run -p dark-fleet-sim -- [--p1 human] [--p2 hunt] [--seed 42] [--no-touching] [--diagonal]
+//! cargo run -p dark-fleet-sim -- --p1 hunt --p2 parity --replay
+//! cargo run --release -p dark-fleet-sim -- --batch 1000 --p1 hunt --p2 parity
+//! ```
+//!
+//! Players are `human`, `random`, `hunt` or `parity`. Humans enter a cell like `B7`,
+//! or `sonar B7` to ping the 3x3 area around it. `--batch N` plays N games between
+//! two computer players without output per move and prints win rates and average
+//! game length. `--no-touching` and `--diagonal` place fleets under those house
+//! rules. `--replay` prints the game's action log and both shot maps at the end.
+
+mod batch;
+mod board;
+mod game;
+mod rng;
+mod strategy;
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dark_fleet_client::{render_grid, side_by_side, Coord, Mark, ParseCoordError, Style};
+use zk_battleship::{GameConfig, Grid};
+
+use board::SIZE;
+use game::Match;
+use rng::Rng;
+use strategy::{Strategy, Tracking};
+
+enum Player {
+    Human,
+    Computer(Box<dyn Strategy>),
+}
+
+struct Options {
+    players: [String; 2],
+    seed: u64,
+    batch: Option<u32>,
+    replay: bool,
+    config: GameConfig,
+}
+
+fn parse_player(name: &str) -> Result<Player, String> {
+    match name {
+        "human" => Ok(Player::Human),
+        _ => strategy::by_name(name)
+            .map(Player::Computer)
+            .ok_or(format!("unknown player {name}")),
+    }
+}
+
+fn parse_args() -> Result<Options, String> {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let mut options = Options {
+        players: [String::from("human"), String::from("hunt")],
+        seed,
+        batch: None,
+        replay: false,
+        config: GameConfig::default(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            options.replay = true;
+            continue;
+        }
+        // Rule flags take no value
+        let rule = match arg.as_str() {
+            "--no-touching" => Some(&mut options.config.no_touching),
+            "--diagonal" => Some(&mut options.config.diagonal_ships),
+            _ => None,
+        };
+        if let Some(rule) = rule {
+            *rule = true;
+            continue;
+        }
+        let value = args.next().ok_or(format!("{arg} needs a value"))?;
+        match arg.as_str() {
+            "--p1" => options.players[0] = value,
+            "--p2" => options.players[1] = value,
+            "--seed" => options.seed = value.parse().map_err(|_| "bad seed")?,
+            "--batch" => options.batch = Some(value.parse().map_err(|_| "bad game count")?),
+            _ => return Err(format!("unknown option {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+/// Parse a cell like `B7` on the simulator's board.
+fn parse_cell(text: &str) -> Result<(u32, u32), ParseCoordError> {
+    Coord::parse_on(text, SIZE).map(|coord| (coord.x, coord.y))
+}
+
+fn cell_name(x: u32, y: u32) -> String {
+    Coord::new(x, y).to_string()
+}
+
+/// Colour the output only when it goes to a terminal.
+fn style() -> Style {
+    if io::stdout().is_terminal() {
+        Style::Ansi
+    } else {
+        Style::Plain
+    }
+}
+
+/// The player's own fleet (`#`, hit `X`) beside what they know of the opponent's.
+fn print_boards(game: &Match, index: usize) {
+    let own = &game.boards[index];
+    let incoming: &Tracking = &game.tracking[1 - index];
+    let mine = render_grid(Grid::Square, style(), |Coord { x, y }| {
+        match (own.ship_at(x, y), incoming.get(x, y)) {
+            (Some(_), Some(true)) => Mark::Hit,
+            (Some(_), _) => Mark::Ship,
+            (None, Some(false)) => Mark::Miss,
+            _ => Mark::Empty,
+        }
+    });
+    let theirs = render_grid(Grid::Square, style(), |Coord { x, y }| {
+        match game.tracking[index].get(x, y) {
+            Some(true) => Mark::Hit,
+            Some(false) => Mark::Miss,
+            None => Mark::Empty,
+        }
+    });
+    print!("{}", side_by_side(&mine, &theirs));
+}
+
+/// Read moves from a human until one is accepted.
+fn human_move(game: &mut Match, index: usize, input: &mut impl BufRead) -> io::Result<()> {
+    print_boards(game, index);
+    loop {
+        print!("Player {} > ", index + 1);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 || line.trim() == "quit" {
+            std::process::exit(0);
+        }
+        let (sonar, cell) = match line.trim().strip_prefix("sonar ") {
+            Some(cell) => (true, cell),
+            None => (false, line.trim()),
+        };
+        let (x, y) = match parse_cell(cell) {
+            Ok(cell) => cell,
+            Err(e) => {
+                println!("{e}: enter a cell like B7, or `sonar B7`");
+                continue;
+            }
+        };
+        let result = if sonar {
+            game.sonar(x, y)
+                .map(|count| format!("sonar: {count} ship cells"))
+        } else if game.tracking[index].get(x, y).is_some() {
+            println!("Already fired at {}", cell_name(x, y));
+            continue;
+        } else {
+            game.shoot(x, y)
+                .map(|hit| if hit { "hit" } else { "miss" }.to_string())
+        };
+        match result {
+            Ok(outcome) => {
+                println!("{}: {outcome}", cell_name(x, y));
+                return Ok(());
+            }
+            Err(e) => println!("{}", e.message()),
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let fail = |e: String| -> ! {
+        eprintln!("error: {e}");
+        std::process::exit(2);
+    };
+    let options = parse_args().unwrap_or_else(|e| fail(e));
+    let names = [options.players[0].as_str(), options.players[1].as_str()];
+    let config = &options.config;
+
+    if let Some(games) = options.batch {
+        if let Some(name) = names.iter().find(|name| !strategy::NAMES.contains(name)) {
+            fail(format!("batch games need computer players, not {name}"));
+        }
+        println!("seed {}", options.seed);
+        batch::run(config, names, games, options.seed).print(names);
+        return Ok(());
+    }
+
+    let mut players = names.map(|name| parse_player(name).unwrap_or_else(|e| fail(e)));
+    println!("seed {}", options.seed);
+    let mut rng = Rng::new(options.seed);
+    let mut game = Match::new(config, &mut rng);
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    while game.winner().is_none() {
+        let index = game.turn();
+        match &mut players[index] {
+            Player::Human => human_move(&mut game, index, &mut input)?,
+            Player::Computer(strategy) => {
+                let (x, y) = strategy.next_shot(&game.tracking[index], &mut rng);
+                let hit = game.shoot(x, y).expect("strategy picked an illegal shot");
+                let outcome = if hit { "hit" } else { "miss" };
+                println!(
+                    "Player {} fires at {}: {outcome}",
+                    index + 1,
+                    cell_name(x, y)
+                );
+            }
+        }
+    }
+    let winner = game.winner().unwrap();
+    println!("Player {} wins", winner + 1);
+    print_boards(&game, winner);
+    if options.replay {
+        print!("\n{}", game.replay(style()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use board::{Board, FLEET};
+    use zk_battleship::{is_ship_line, Grid};
+
+    #[test]
+    fn test_parse_cell() {
+        assert_eq!(parse_cell("B7"), Ok((1, 6)));
+        assert_eq!(parse_cell(" j10 "), Ok((9, 9)));
+        assert_eq!(parse_cell("K1"), Err(ParseCoordError::OffBoard));
+        assert_eq!(parse_cell("A0"), Err(ParseCoordError::BadRow));
+        assert_eq!(cell_name(1, 6), "B7");
+    }
+
+    #[test]
+    fn test_placement_rules() {
+        let mut rng = Rng::new(3);
+        let config = GameConfig {
+            no_touching: true,
+            diagonal_ships: true,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            let board = Board::random(&mut rng, &config);
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    let Some(ship) = board.ship_at(x, y) else {
+                        continue;
+                    };
+                    for (nx, ny) in [
+                        (x + 1, y),
+                        (x, y + 1),
+                        (x + 1, y + 1),
+                        (x + 1, y.wrapping_sub(1)),
+                    ] {
+                        if nx < SIZE && ny < SIZE {
+                            assert!(board.ship_at(nx, ny).is_none_or(|other| other == ship));
+                        }
+                    }
+                }
+            }
+            // Every ship is one the contract accepts when it is disclosed as sunk
+            for (ship, &len) in FLEET.iter().enumerate() {
+                let cells = board.cells_of(ship);
+                assert_eq!(cells.len() as u32, len);
+                assert!(is_ship_line(Grid::Square, true, &cells));
+            }
+        }
+
+        // An open cell beside a ship is fine only without the rule
+        let board = Board::random(&mut rng, &GameConfig::default());
+        let beside = (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .find(|&(x, y)| board.ship_at(x, y).is_none() && board.sonar_count(x, y) > 0)
+            .unwrap();
+        assert!(board.can_place(&[beside], false));
+        assert!(!board.can_place(&[beside], true));
+
+        // Diagonal ships may touch without the rule, but never cross
+        let mut board = Board::empty();
+        board.place(0, &[(1, 1), (2, 2)]);
+        assert!(board.can_place(&[(2, 1), (3, 2)], false));
+        assert!(!board.can_place(&[(1, 2), (2, 1)], false));
+    }
+
+    #[test]
+    fn test_computer_game_finishes() {
+        let mut rng = Rng::new(1);
+        let mut game = Match::new(&GameConfig::default(), &mut rng);
+        let mut strategies = [
+            strategy::by_name("hunt").unwrap(),
+            strategy::by_name("random").unwrap(),
+        ];
+        while game.winner().is_none() {
+            let index = game.turn();
+            let (x, y) = strategies[index].next_shot(&game.tracking[index], &mut rng);
+            game.shoot(x, y).unwrap();
+        }
+        let replay = game.replay(Style::Plain);
+        assert_eq!(game.hits(game.winner().unwrap()), 17, "\n{replay}");
+        let shots = game.shots(0) + game.shots(1);
+        assert_eq!(
+            replay.matches(" fires at ").count() as u32,
+            shots,
+            "\n{replay}"
+        );
+    }
+
+    #[test]
+    fn test_batch_summary() {
+        let summary = batch::run(&GameConfig::default(), ["parity", "random"], 3, 5);
+        assert_eq!(summary.games, 3);
+        assert_eq!(summary.wins[0] + summary.wins[1], 3);
+        // Someone sank 17 cells in every game
+        assert!(summary.total_shots >= 3 * 17);
+        assert_eq!(
+            summary,
+            batch::run(&GameConfig::default(), ["parity", "random"], 3, 5)
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Shot-picking strategies for computer players. A strategy only sees what a real
+//! player would: its own record of hits and misses on the opponent's board.
+
+use crate::board::SIZE;
+use crate::rng::Rng;
+
+/// What a player knows about the opponent's board.
+pub struct Tracking {
+    /// `Some(hit)` for cells already fired at, by `[y][x]`
+    cells: [[Option<bool>; SIZE as usize]; SIZE as usize],
+}
+
+impl Default for Tracking {
+    fn default() -> Self {
+        Tracking {
+            cells: [[None; SIZE as usize]; SIZE as usize],
+        }
+    }
+}
+
+impl Tracking {
+    pub fn get(&self, x: u32, y: u32) -> Option<bool> {
+        self.cells[y as usize][x as usize]
+    }
+
+    pub fn record(&mut self, x: u32, y: u32, hit: bool) {
+        self.cells[y as usize][x as usize] = Some(hit);
+    }
+
+    /// Whether some hit still has an unknown neighbour to follow up on.
+    fn has_open_hits(&self) -> bool {
+        !self.targets().is_empty()
+    }
+
+    /// Unknown cells next to a hit.
+    fn targets(&self) -> Vec<(u32, u32)> {
+        self.unknown()
+            .into_iter()
+            .filter(|&(x, y)| {
+                let neighbours = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                neighbours
+                    .iter()
+                    .any(|&(nx, ny)| nx < SIZE && ny < SIZE && self.get(nx, ny) == Some(true))
+            })
+            .collect()
+    }
+
+    fn unknown(&self) -> Vec<(u32, u32)> {
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.get(x, y).is_none())
+            .collect()
+    }
+}
+
+pub trait Strategy {
+    fn next_shot(&mut self, tracking: &Tracking, rng: &mut Rng) -> (u32, u32);
+}
+
+/// Any cell not fired at yet.
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn next_shot(&mut self, tracking: &Tracking, rng: &mut Rng) -> (u32, u32) {
+        let cells = tracking.unknown();
+        cells[rng.below(cells.len() as u32) as usize]
+    }
+}
+
+/// Random shots until something is hit, then the unknown neighbours of hits.
+pub struct HuntTargetStrategy;
+
+impl Strategy for HuntTargetStrategy {
+    fn next_shot(&mut self, tracking: &Tracking, rng: &mut Rng) -> (u32, u32) {
+        let targets = tracking.targets();
+        if targets.is_empty() {
+            RandomStrategy.next_shot(tracking, rng)
+        } else {
+            targets[rng.below(targets.len() as u32) as usize]
+        }
+    }
+}
+
+/// Hunt/target, but hunting only on one colour of the checkerboard: every ship is
+/// at least two cells long, so it always covers a cell of each colour.
+pub struct ParityStrategy;
+
+impl Strategy for ParityStrategy {
+    fn next_shot(&mut self, tracking: &Tracking, rng: &mut Rng) -> (u32, u32) {
+        let shot = HuntTargetStrategy.next_shot(tracking, rng);
+        if tracking.has_open_hits() {
+            return shot;
+        }
+        let cells: Vec<(u32, u32)> = tracking
+            .unknown()
+            .into_iter()
+            .filter(|&(x, y)| (x + y) % 2 == 0)
+            .collect();
+        if cells.is_empty() {
+            shot
+        } else {
+            cells[rng.below(cells.len() as u32) as usize]
+        }
+    }
+}
+
+/// Names accepted by `by_name`.
+pub const NAMES: [&str; 3] = ["random", "hunt", "parity"];
+
+/// Build a computer strategy by name.
+pub fn by_name(name: &str) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(RandomStrategy)),
+        "hunt" => Some(Box::new(HuntTargetStrategy)),
+        "parity" => Some(Box::new(ParityStrategy)),
+        _ => None,
+    }
+}
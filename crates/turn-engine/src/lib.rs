@@ -0,0 +1,335 @@
+//! Alternating-turn machinery for two-player games, independent of any one game.
+//!
+//! One seat has the move. It takes an action, and the other seat owes a response to
+//! it before anything else happens; once the response is in, the move passes to the
+//! responder unless the game's rules let the mover keep it. A pending action may
+//! carry a deadline (a ledger sequence or any other monotonic clock). Once it has
+//! passed, the mover can claim the game by forfeit.
+//!
+//! Games describe their actions, responses and rule tweaks through `TurnRules` and
+//! keep a `TurnState` per game. Battleship is the first consumer: a shot or sonar scan
+//! is the action, the defender's report the response.
+
+#![no_std]
+
+/// One of the two players, by seating order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seat {
+    First,
+    Second,
+}
+
+impl Seat {
+    /// The seat for player number 1 or 2. Anything but 1 is the second seat.
+    pub fn from_num(num: u32) -> Self {
+        if num == 1 {
+            Seat::First
+        } else {
+            Seat::Second
+        }
+    }
+
+    /// Player number, 1 or 2.
+    pub fn num(self) -> u32 {
+        match self {
+            Seat::First => 1,
+            Seat::Second => 2,
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Seat::First => Seat::Second,
+            Seat::Second => Seat::First,
+        }
+    }
+}
+
+/// Why a turn step was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TurnError {
+    /// The seat acting does not have the move
+    NotYourTurn,
+    /// The seat responding is not the one the pending action is waiting on
+    NotResponder,
+    /// An action is still waiting for its response
+    AwaitingResponse,
+    /// There is no pending action to respond to
+    NothingToRespond,
+    /// The pending action has no deadline, or it has not passed yet
+    DeadlineNotPassed,
+}
+
+/// A game's actions, responses and the rules that vary between game modes.
+pub trait TurnRules {
+    type Action;
+    type Response;
+
+    /// Whether the mover keeps the move once `response` answers `action`, as in salvo
+    /// turns or modes where one side always moves. Defaults to handing it over.
+    fn keeps_move(&self, _action: &Self::Action, _response: &Self::Response) -> bool {
+        false
+    }
+
+    /// How long the responder has to answer `action`, or `None` for no deadline.
+    fn response_window(&self, _action: &Self::Action) -> Option<u32> {
+        None
+    }
+}
+
+/// An action waiting for its response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pending<A> {
+    pub action: A,
+    /// Last tick in which the response may still be given
+    pub deadline: Option<u32>,
+}
+
+/// Whose move it is and what, if anything, is waiting for a response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TurnState<A> {
+    pub to_move: Seat,
+    pub pending: Option<Pending<A>>,
+}
+
+impl<A> TurnState<A> {
+    /// A fresh game with `first` to move.
+    pub fn new(first: Seat) -> Self {
+        TurnState {
+            to_move: first,
+            pending: None,
+        }
+    }
+
+    /// The seat a pending action waits on: always the one without the move.
+    pub fn responder(&self) -> Seat {
+        self.to_move.other()
+    }
+
+    /// Checks that `seat` may act now.
+    pub fn check_act(&self, seat: Seat) -> Result<(), TurnError> {
+        if self.pending.is_some() {
+            return Err(TurnError::AwaitingResponse);
+        }
+        if seat != self.to_move {
+            return Err(TurnError::NotYourTurn);
+        }
+        Ok(())
+    }
+
+    /// Checks that `seat` owes the response to the pending action, returning it.
+    pub fn check_respond(&self, seat: Seat) -> Result<&A, TurnError> {
+        let pending = self.pending.as_ref().ok_or(TurnError::NothingToRespond)?;
+        if seat != self.responder() {
+            return Err(TurnError::NotResponder);
+        }
+        Ok(&pending.action)
+    }
+
+    /// `seat` takes `action` at tick `now`; the responder's deadline follows from the
+    /// rules' response window.
+    pub fn act<R>(&mut self, rules: &R, seat: Seat, action: A, now: u32) -> Result<(), TurnError>
+    where
+        R: TurnRules<Action = A>,
+    {
+        self.check_act(seat)?;
+        let deadline = rules
+            .response_window(&action)
+            .map(|window| now.saturating_add(window));
+        self.pending = Some(Pending { action, deadline });
+        Ok(())
+    }
+
+    /// `seat` answers the pending action with `response`. The move passes to the
+    /// responder unless the rules say otherwise. Returns the answered action.
+    pub fn respond<R>(
+        &mut self,
+        rules: &R,
+        seat: Seat,
+        response: &R::Response,
+    ) -> Result<A, TurnError>
+    where
+        R: TurnRules<Action = A>,
+    {
+        self.check_respond(seat)?;
+        let pending = self.pending.take().ok_or(TurnError::NothingToRespond)?;
+        if !rules.keeps_move(&pending.action, response) {
+            self.to_move = self.to_move.other();
+        }
+        Ok(pending.action)
+    }
+
+    /// `seat` gives up the move without taking an action, so nothing is owed a
+    /// response, and the other seat moves next.
+    pub fn pass(&mut self, seat: Seat) -> Result<(), TurnError> {
+        self.check_act(seat)?;
+        self.to_move = self.to_move.other();
+        Ok(())
+    }
+
+    /// Whether the pending action's deadline has passed at tick `now`.
+    pub fn timed_out(&self, now: u32) -> bool {
+        self.pending
+            .as_ref()
+            .and_then(|pending| pending.deadline)
+            .is_some_and(|deadline| now > deadline)
+    }
+
+    /// `seat` claims the game because the responder missed the deadline. Only the
+    /// mover can claim; returns the winning seat.
+    pub fn claim_forfeit(&self, seat: Seat, now: u32) -> Result<Seat, TurnError> {
+        if seat != self.to_move {
+            return Err(TurnError::NotYourTurn);
+        }
+        if !self.timed_out(now) {
+            return Err(TurnError::DeadlineNotPassed);
+        }
+        Ok(self.to_move)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Guess a number: a hit lets the guesser go again, answers are due in 5 ticks.
+    struct Guessing;
+
+    impl TurnRules for Guessing {
+        type Action = u32;
+        type Response = bool;
+
+        fn keeps_move(&self, _guess: &u32, hit: &bool) -> bool {
+            *hit
+        }
+
+        fn response_window(&self, _guess: &u32) -> Option<u32> {
+            Some(5)
+        }
+    }
+
+    #[test]
+    fn test_turns_and_forfeit() {
+        let rules = Guessing;
+        let mut turns = TurnState::new(Seat::First);
+
+        assert_eq!(
+            turns.act(&rules, Seat::Second, 3, 0),
+            Err(TurnError::NotYourTurn)
+        );
+        assert_eq!(
+            turns.check_respond(Seat::Second),
+            Err(TurnError::NothingToRespond)
+        );
+        turns.act(&rules, Seat::First, 3, 10).unwrap();
+        assert_eq!(
+            turns.act(&rules, Seat::First, 4, 10),
+            Err(TurnError::AwaitingResponse)
+        );
+        assert_eq!(
+            turns.respond(&rules, Seat::First, &true),
+            Err(TurnError::NotResponder)
+        );
+
+        // A hit keeps the move, a miss hands it over
+        assert_eq!(turns.respond(&rules, Seat::Second, &true), Ok(3));
+        assert_eq!(turns.to_move, Seat::First);
+        turns.act(&rules, Seat::First, 4, 20).unwrap();
+        assert_eq!(turns.respond(&rules, Seat::Second, &false), Ok(4));
+        assert_eq!(turns.to_move, Seat::Second);
+
+        // An unanswered action can be claimed once its deadline has passed
+        turns.act(&rules, Seat::Second, 5, 30).unwrap();
+        assert_eq!(
+            turns.claim_forfeit(Seat::Second, 35),
+            Err(TurnError::DeadlineNotPassed)
+        );
+        assert_eq!(
+            turns.claim_forfeit(Seat::First, 36),
+            Err(TurnError::NotYourTurn)
+        );
+        assert_eq!(turns.claim_forfeit(Seat::Second, 36), Ok(Seat::Second));
+    }
+
+    /// Rules that change nothing: no deadlines, and every response hands the move over.
+    struct Plain;
+
+    impl TurnRules for Plain {
+        type Action = u32;
+        type Response = bool;
+    }
+
+    #[test]
+    fn test_pass() {
+        let rules = Guessing;
+        let mut turns = TurnState::new(Seat::First);
+
+        assert_eq!(turns.pass(Seat::Second), Err(TurnError::NotYourTurn));
+        turns.pass(Seat::First).unwrap();
+        assert_eq!(turns.to_move, Seat::Second);
+
+        // Not while an action waits for its response
+        turns.act(&rules, Seat::Second, 7, 0).unwrap();
+        assert_eq!(turns.pass(Seat::Second), Err(TurnError::AwaitingResponse));
+        assert_eq!(turns.respond(&rules, Seat::First, &false), Ok(7));
+        turns.pass(Seat::First).unwrap();
+        assert_eq!(turns.to_move, Seat::Second);
+        assert_eq!(turns.pending, None);
+    }
+
+    #[test]
+    fn test_forfeit_deadlines() {
+        let mut turns = TurnState::new(Seat::First);
+
+        // Without a response window nothing ever times out
+        turns.act(&Plain, Seat::First, 1, 0).unwrap();
+        assert_eq!(turns.pending.as_ref().unwrap().deadline, None);
+        assert!(!turns.timed_out(u32::MAX));
+        assert_eq!(
+            turns.claim_forfeit(Seat::First, u32::MAX),
+            Err(TurnError::DeadlineNotPassed)
+        );
+        turns.respond(&Plain, Seat::Second, &false).unwrap();
+
+        // The deadline tick itself is still in time
+        turns.act(&Guessing, Seat::Second, 2, 10).unwrap();
+        assert_eq!(turns.pending.as_ref().unwrap().deadline, Some(15));
+        assert!(!turns.timed_out(15));
+        assert!(turns.timed_out(16));
+
+        // Answering clears the deadline
+        turns.respond(&Guessing, Seat::First, &false).unwrap();
+        assert!(!turns.timed_out(16));
+        assert_eq!(
+            turns.claim_forfeit(Seat::First, 16),
+            Err(TurnError::DeadlineNotPassed)
+        );
+
+        // A deadline past the end of the clock saturates instead of wrapping
+        turns.act(&Guessing, Seat::First, 3, u32::MAX - 2).unwrap();
+        assert_eq!(turns.pending.as_ref().unwrap().deadline, Some(u32::MAX));
+        assert!(!turns.timed_out(u32::MAX));
+    }
+
+    #[test]
+    fn test_keeps_move() {
+        // By default every response hands the move over, hit or not
+        let mut turns = TurnState::new(Seat::First);
+        for (seat, hit) in [(Seat::First, true), (Seat::Second, false)] {
+            turns.act(&Plain, seat, 1, 0).unwrap();
+            turns.respond(&Plain, seat.other(), &hit).unwrap();
+            assert_eq!(turns.to_move, seat.other());
+        }
+
+        // Rules can let the mover go again, as many times as they allow
+        let mut turns = TurnState::new(Seat::Second);
+        for guess in 0..3 {
+            turns.act(&Guessing, Seat::Second, guess, 0).unwrap();
+            turns.respond(&Guessing, Seat::First, &true).unwrap();
+            assert_eq!(turns.to_move, Seat::Second);
+        }
+        turns.act(&Guessing, Seat::Second, 3, 0).unwrap();
+        turns.respond(&Guessing, Seat::First, &false).unwrap();
+        assert_eq!(turns.to_move, Seat::First);
+    }
+}
@@ -11,6 +11,7 @@ import {
   reportSonar as reportSonarOnChain,
   claimVictory,
   pollGameState,
+  newActionId,
   type OnChainGame,
 } from '../lib/stellar';
 import { gameIdToCode } from '../lib/gameCode';
@@ -203,6 +204,8 @@ export default function OnlineBattle({
     lastReportedRef.current = key;
 
     const hit = checkHit(myShips, g.lastShotX, g.lastShotY);
+    // One key for the report and its retry, so a retry of a report that landed is a no-op
+    const actionId = newActionId();
     setOpponentShots(prev => [...prev, { x: g.lastShotX, y: g.lastShotY, hit }]);
     addLog(`Opponent fires at ${coord(g.lastShotX,g.lastShotY)} -> ${hit ? 'HIT' : 'MISS'}`);
 
@@ -217,7 +220,7 @@ export default function OnlineBattle({
     }
 
     try {
-      const txHash = await reportResultOnChain(gameId, walletAddress, hit, proofBytes);
+      const txHash = await reportResultOnChain(gameId, walletAddress, hit, proofBytes, actionId);
       addLog(`Reported ${hit ? 'HIT' : 'MISS'} on-chain`, txHash);
       setTurnPhase('my-turn');
     } catch (err: unknown) {
@@ -227,7 +230,7 @@ export default function OnlineBattle({
       if (proofBytes.length > 0) {
         addLog('Retrying report without proof...');
         try {
-          const txHash = await reportResultOnChain(gameId, walletAddress, hit, new Uint8Array(0));
+          const txHash = await reportResultOnChain(gameId, walletAddress, hit, new Uint8Array(0), actionId);
           addLog(`Reported ${hit ? 'HIT' : 'MISS'} on-chain (no proof)`, txHash);
           setTurnPhase('my-turn');
           return;
@@ -246,6 +249,7 @@ export default function OnlineBattle({
     lastReportedRef.current = key;
 
     const count = sonarCount(myShips, g.sonarCenterX, g.sonarCenterY);
+    const actionId = newActionId();
     addLog(`Opponent sonar at ${coord(g.sonarCenterX,g.sonarCenterY)} -> ${count} cells`);
 
     let proofBytes: Uint8Array = new Uint8Array(0);
@@ -259,7 +263,7 @@ export default function OnlineBattle({
     }
 
     try {
-      const txHash = await reportSonarOnChain(gameId, walletAddress, count, proofBytes);
+      const txHash = await reportSonarOnChain(gameId, walletAddress, count, proofBytes, actionId);
       addLog(`Reported sonar count=${count} on-chain`, txHash);
       setTurnPhase('my-turn');
     } catch (err: unknown) {
@@ -268,7 +272,7 @@ export default function OnlineBattle({
       if (proofBytes.length > 0) {
         addLog('Retrying sonar report without proof...');
         try {
-          const txHash = await reportSonarOnChain(gameId, walletAddress, count, new Uint8Array(0));
+          const txHash = await reportSonarOnChain(gameId, walletAddress, count, new Uint8Array(0), actionId);
           addLog(`Reported sonar count=${count} on-chain (no proof)`, txHash);
           setTurnPhase('my-turn');
           return;
@@ -295,7 +299,7 @@ export default function OnlineBattle({
     setMyShots(prev => [...prev, { x, y, hit: null }]);
 
     try {
-      const txHash = await takeShotOnChain(gameId, walletAddress, x, y);
+      const txHash = await takeShotOnChain(gameId, walletAddress, x, y, newActionId());
       addLog(`Fired at ${coord(x,y)}`, txHash);
       setTurnPhase('awaiting-report');
     } catch (err: unknown) {
@@ -319,7 +323,7 @@ export default function OnlineBattle({
     pendingSonarRef.current = { centerX, centerY };
 
     try {
-      const txHash = await useSonarOnChain(gameId, walletAddress, centerX, centerY);
+      const txHash = await useSonarOnChain(gameId, walletAddress, centerX, centerY, newActionId());
       addLog(`Used sonar at ${coord(centerX,centerY)}`, txHash);
       setTurnPhase('awaiting-report');
     } catch (err: unknown) {
@@ -2,8 +2,10 @@ import { Noir } from '@noir-lang/noir_js';
 import { BarretenbergBackend } from '@noir-lang/backend_barretenberg';
 
 let boardCircuit: any = null;
+let boardRulesCircuit: any = null;
 let shotCircuit: any = null;
 let sonarCircuit: any = null;
+let scoutCircuit: any = null;
 
 async function loadCircuit(name: string) {
   const res = await fetch(`/circuits/${name}.json`);
@@ -16,6 +18,14 @@ export async function initCircuits() {
   if (!sonarCircuit) sonarCircuit = await loadCircuit('sonar');
 }
 
+// Boards in games with a placement rule prove with `board_rules`, which takes the
+// rules as public inputs; classic boards use the cheaper `board` circuit.
+async function boardCircuitFor(noTouching: boolean, diagonalShips: boolean) {
+  if (!noTouching && !diagonalShips) return boardCircuit;
+  if (!boardRulesCircuit) boardRulesCircuit = await loadCircuit('board_rules');
+  return boardRulesCircuit;
+}
+
 export interface BoardProofResult {
   proof: Uint8Array;
   boardHash: string;
@@ -29,14 +39,28 @@ export interface SonarProofResult {
   proof: Uint8Array;
 }
 
+export interface ScoutProofResult {
+  proof: Uint8Array;
+}
+
 // Generate a board proof. Returns the proof and the computed board hash.
-export async function generateBoardProof(ships: string[]): Promise<BoardProofResult> {
+// `noTouching` and `diagonalShips` must match the game's `GameConfig` rules.
+export async function generateBoardProof(
+  ships: string[],
+  noTouching = false,
+  diagonalShips = false
+): Promise<BoardProofResult> {
   await initCircuits();
 
-  const backend = new BarretenbergBackend(boardCircuit);
-  const noir = new Noir(boardCircuit);
+  const circuit = await boardCircuitFor(noTouching, diagonalShips);
+  const backend = new BarretenbergBackend(circuit);
+  const noir = new Noir(circuit);
 
-  const { witness, returnValue } = await noir.execute({ ships });
+  const inputs =
+    circuit === boardCircuit
+      ? { ships }
+      : { ships, no_touching: noTouching, diagonal_ships: diagonalShips };
+  const { witness, returnValue } = await noir.execute(inputs);
 
   // returnValue is the Pedersen hash of the ships array
   const boardHash = returnValue as string;
@@ -49,15 +73,23 @@ export async function generateBoardProof(ships: string[]): Promise<BoardProofRes
 }
 
 // Verify a board proof
-export async function verifyBoardProof(proof: Uint8Array, boardHash: string): Promise<boolean> {
+export async function verifyBoardProof(
+  proof: Uint8Array,
+  boardHash: string,
+  noTouching = false,
+  diagonalShips = false
+): Promise<boolean> {
   await initCircuits();
 
-  const backend = new BarretenbergBackend(boardCircuit);
+  const circuit = await boardCircuitFor(noTouching, diagonalShips);
+  const backend = new BarretenbergBackend(circuit);
+  const rules =
+    circuit === boardCircuit ? [] : [noTouching ? '1' : '0', diagonalShips ? '1' : '0'];
 
   try {
     const verified = await backend.verifyProof({
       proof,
-      publicInputs: [boardHash],
+      publicInputs: [...rules, boardHash],
     });
     return verified;
   } finally {
@@ -65,13 +97,14 @@ export async function verifyBoardProof(proof: Uint8Array, boardHash: string): Pr
   }
 }
 
-// Generate a shot proof
+// Generate a shot proof. `submerged` is the contract's `shot_submerged` for this shot.
 export async function generateShotProof(
   ships: string[],
   boardHash: string,
   shotX: number,
   shotY: number,
-  isHit: boolean
+  isHit: boolean,
+  submerged = false
 ): Promise<ShotProofResult> {
   await initCircuits();
 
@@ -84,6 +117,7 @@ export async function generateShotProof(
     ships,
     shot_x: shotX.toString(),
     shot_y: shotY.toString(),
+    submerged,
   });
 
   const proof = await backend.generateProof(witness);
@@ -99,7 +133,8 @@ export async function verifyShotProof(
   boardHash: string,
   hit: boolean,
   shotX: number,
-  shotY: number
+  shotY: number,
+  submerged = false
 ): Promise<boolean> {
   await initCircuits();
 
@@ -108,7 +143,13 @@ export async function verifyShotProof(
   try {
     const verified = await backend.verifyProof({
       proof,
-      publicInputs: [boardHash, hit ? '1' : '0', shotX.toString(), shotY.toString()],
+      publicInputs: [
+        boardHash,
+        hit ? '1' : '0',
+        shotX.toString(),
+        shotY.toString(),
+        submerged ? '1' : '0',
+      ],
     });
     return verified;
   } finally {
@@ -166,3 +207,56 @@ export async function generateSonarProof(
 
   return { proof: proof.proof };
 }
+
+// Verify a scout proof
+export async function verifyScoutProof(
+  proof: Uint8Array,
+  boardHash: string,
+  areaX: number,
+  areaY: number,
+  found: boolean
+): Promise<boolean> {
+  // Loaded on first use; only loadout games with scout planes need it
+  if (!scoutCircuit) scoutCircuit = await loadCircuit('scout');
+
+  const backend = new BarretenbergBackend(scoutCircuit);
+
+  try {
+    const verified = await backend.verifyProof({
+      proof,
+      publicInputs: [boardHash, areaX.toString(), areaY.toString(), found ? '1' : '0'],
+    });
+    return verified;
+  } finally {
+    await backend.destroy();
+  }
+}
+
+// Generate a scout proof — proves whether a 2x2 area holds any ship cell
+export async function generateScoutProof(
+  ships: string[],
+  boardHash: string,
+  areaX: number,
+  areaY: number,
+  found: boolean
+): Promise<ScoutProofResult> {
+  // Loaded on first use; only loadout games with scout planes need it
+  if (!scoutCircuit) scoutCircuit = await loadCircuit('scout');
+
+  const backend = new BarretenbergBackend(scoutCircuit);
+  const noir = new Noir(scoutCircuit);
+
+  const { witness } = await noir.execute({
+    ships,
+    board_hash: boardHash,
+    area_x: areaX.toString(),
+    area_y: areaY.toString(),
+    found,
+  });
+
+  const proof = await backend.generateProof(witness);
+
+  await backend.destroy();
+
+  return { proof: proof.proof };
+}
@@ -154,7 +154,10 @@ export interface OnChainGame {
   turn: number;
   p1Hits: number;
   p2Hits: number;
-  status: number; // 0=created, 1=in_progress, 2=completed
+  p1Shots: number;
+  p2Shots: number;
+  status: number; // 0=created, 1=in_progress, 2=completed, 3=cancelled
+  phase: string; // Setup, AwaitingCommit, AwaitingShot, AwaitingReport, AwaitingSonarReport, Completed, Cancelled
   sessionId: number;
   awaitingReport: boolean;
   lastShotX: number;
@@ -169,6 +172,8 @@ export interface OnChainGame {
   lastSonarCount: number;
   lastShotProof: Uint8Array;
   lastSonarProof: Uint8Array;
+  winner: string | null;
+  endReason: string; // NotEnded, AllShipsSunk, Cancelled, CarriersSunk, Survived, HubSettled, SonarTimeout, FlagshipSunk, ConvoyCaptured, Voided
 }
 
 // Convert bytes (Buffer, Uint8Array, or string) to hex string
@@ -182,6 +187,8 @@ function bytesToHex(val: unknown): string {
 
 function decodeGame(val: StellarSdk.xdr.ScVal): OnChainGame {
   const native = StellarSdk.scValToNative(val) as Record<string, unknown>;
+  // Enum variants decode as [name, ...payload]
+  const phase = String((native.phase as unknown[])[0]);
   return {
     player1: native.player1 as string,
     player2: native.player2 as string,
@@ -191,16 +198,20 @@ function decodeGame(val: StellarSdk.xdr.ScVal): OnChainGame {
     turn: Number(native.turn),
     p1Hits: Number(native.p1_hits),
     p2Hits: Number(native.p2_hits),
+    p1Shots: Number(native.p1_shots),
+    p2Shots: Number(native.p2_shots),
     status: Number(native.status),
+    phase,
     sessionId: Number(native.session_id),
-    awaitingReport: Boolean(native.awaiting_report),
+    awaitingReport: phase === 'AwaitingReport',
     lastShotX: Number(native.last_shot_x),
     lastShotY: Number(native.last_shot_y),
-    p1TurnsTaken: Number(native.p1_turns_taken),
-    p2TurnsTaken: Number(native.p2_turns_taken),
+    // Shots plus ability uses — changes on every action, which OnlineBattle relies on
+    p1TurnsTaken: Number(native.p1_shots_taken) + Number(native.p1_abilities_used),
+    p2TurnsTaken: Number(native.p2_shots_taken) + Number(native.p2_abilities_used),
     p1SonarUsed: Boolean(native.p1_sonar_used),
     p2SonarUsed: Boolean(native.p2_sonar_used),
-    awaitingSonar: Boolean(native.awaiting_sonar),
+    awaitingSonar: phase === 'AwaitingSonarReport',
     sonarCenterX: Number(native.sonar_center_x),
     sonarCenterY: Number(native.sonar_center_y),
     lastSonarCount: Number(native.last_sonar_count),
@@ -210,13 +221,33 @@ function decodeGame(val: StellarSdk.xdr.ScVal): OnChainGame {
     lastSonarProof: native.last_sonar_proof instanceof Uint8Array
       ? native.last_sonar_proof
       : new Uint8Array(native.last_sonar_proof as ArrayLike<number> ?? []),
+    winner: (native.winner as string | undefined) ?? null,
+    endReason: String((native.end_reason as unknown[])[0]),
   };
 }
 
 // --- Contract calls ---
 
+// Idempotency key for one user action. Create it once and pass the same key to
+// every attempt at that action, so a resubmitted transaction is a no-op on-chain.
+export function newActionId(): Uint8Array {
+  return crypto.getRandomValues(new Uint8Array(16));
+}
+
+function actionIdParam(actionId: Uint8Array): StellarSdk.xdr.ScVal {
+  return StellarSdk.nativeToScVal(actionId, { type: 'bytes' });
+}
+
 export async function newGame(player1: string): Promise<{ gameId: number; txHash: string }> {
-  const params = [new StellarSdk.Address(player1).toScVal()];
+  // Pin the creation to the creator's next nonce so a resubmitted transaction
+  // returns the same game instead of opening a second one
+  const creator = new StellarSdk.Address(player1).toScVal();
+  const nonce = await readContract(
+    'creator_nonce',
+    [creator],
+    (val) => Number(StellarSdk.scValToNative(val))
+  );
+  const params = [creator, StellarSdk.nativeToScVal(nonce, { type: 'u32' })];
   const result = await buildAndSendTx(player1, 'new_game', params);
 
   // Extract game ID from contract return value
@@ -267,13 +298,17 @@ export async function takeShot(
   gameId: number,
   player: string,
   x: number,
-  y: number
+  y: number,
+  actionId: Uint8Array
 ): Promise<string> {
   const params = [
     StellarSdk.nativeToScVal(gameId, { type: 'u32' }),
     new StellarSdk.Address(player).toScVal(),
     StellarSdk.nativeToScVal(x, { type: 'u32' }),
     StellarSdk.nativeToScVal(y, { type: 'u32' }),
+    // FleetGrid::Home — unit enum variants encode as a one-element vec
+    StellarSdk.xdr.ScVal.scvVec([StellarSdk.xdr.ScVal.scvSymbol('Home')]),
+    actionIdParam(actionId),
   ];
   return (await buildAndSendTx(player, 'take_shot', params)).hash;
 }
@@ -282,13 +317,15 @@ export async function reportResult(
   gameId: number,
   player: string,
   hit: boolean,
-  proof: Uint8Array = new Uint8Array(0)
+  proof: Uint8Array,
+  actionId: Uint8Array
 ): Promise<string> {
   const params = [
     StellarSdk.nativeToScVal(gameId, { type: 'u32' }),
     new StellarSdk.Address(player).toScVal(),
     StellarSdk.nativeToScVal(hit, { type: 'bool' }),
     StellarSdk.nativeToScVal(proof, { type: 'bytes' }),
+    actionIdParam(actionId),
   ];
   return (await buildAndSendTx(player, 'report_result', params)).hash;
 }
@@ -297,13 +334,15 @@ export async function useSonar(
   gameId: number,
   player: string,
   centerX: number,
-  centerY: number
+  centerY: number,
+  actionId: Uint8Array
 ): Promise<string> {
   const params = [
     StellarSdk.nativeToScVal(gameId, { type: 'u32' }),
     new StellarSdk.Address(player).toScVal(),
     StellarSdk.nativeToScVal(centerX, { type: 'u32' }),
     StellarSdk.nativeToScVal(centerY, { type: 'u32' }),
+    actionIdParam(actionId),
   ];
   return (await buildAndSendTx(player, 'use_sonar', params)).hash;
 }
@@ -312,13 +351,15 @@ export async function reportSonar(
   gameId: number,
   player: string,
   count: number,
-  proof: Uint8Array = new Uint8Array(0)
+  proof: Uint8Array,
+  actionId: Uint8Array
 ): Promise<string> {
   const params = [
     StellarSdk.nativeToScVal(gameId, { type: 'u32' }),
     new StellarSdk.Address(player).toScVal(),
     StellarSdk.nativeToScVal(count, { type: 'u32' }),
     StellarSdk.nativeToScVal(proof, { type: 'bytes' }),
+    actionIdParam(actionId),
   ];
   return (await buildAndSendTx(player, 'report_sonar', params)).hash;
 }
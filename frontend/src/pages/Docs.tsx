@@ -161,13 +161,13 @@ export default function Docs() {
 
           <div className="bg-slate-900 rounded-lg border border-slate-800 divide-y divide-slate-800">
             {[
-              { fn: 'new_game(player1)', desc: 'Create a new game session. Returns game ID for sharing.' },
+              { fn: 'new_game(player1, creation_nonce)', desc: 'Create a new game session. Returns game ID for sharing; retries with the same nonce return the same game.' },
               { fn: 'join_game(game_id, player2)', desc: 'Join an existing game with the game code.' },
               { fn: 'commit_board(game_id, player, hash)', desc: 'Submit Pedersen hash commitment of ship positions.' },
-              { fn: 'take_shot(game_id, player, x, y)', desc: 'Fire a shot at the opponent\'s board (must be your turn).' },
-              { fn: 'report_result(game_id, player, hit, proof)', desc: 'Report hit/miss with ZK proof stored on-chain for verification.' },
-              { fn: 'use_sonar(game_id, player, cx, cy)', desc: 'Use sonar ping instead of firing (available after 3 turns, once per game).' },
-              { fn: 'report_sonar(game_id, player, count, proof)', desc: 'Report sonar count with ZK proof stored on-chain for verification.' },
+              { fn: 'take_shot(game_id, player, x, y, grid, action_id)', desc: 'Fire a shot at the opponent\'s board (must be your turn). The grid is Home unless playing armada; a retry with the same action ID does not fire twice.' },
+              { fn: 'report_result(game_id, player, hit, proof, action_id)', desc: 'Report hit/miss with ZK proof stored on-chain for verification.' },
+              { fn: 'use_sonar(game_id, player, cx, cy, action_id)', desc: 'Use sonar ping instead of firing (available after 3 turns, once per game).' },
+              { fn: 'report_sonar(game_id, player, count, proof, action_id)', desc: 'Report sonar count with ZK proof stored on-chain for verification.' },
               { fn: 'claim_victory(game_id, player)', desc: 'Claim win after scoring 17 hits (all ship cells sunk).' },
               { fn: 'get_game(game_id)', desc: 'Read current game state (view function, no tx needed).' },
             ].map(({ fn, desc }) => (